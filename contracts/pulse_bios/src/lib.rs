@@ -1,20 +1,24 @@
 #![no_std]
-#![no_main]
+#![cfg_attr(not(test), no_main)]
 extern crate alloc;
 
 mod native;
 
 use alloc::vec::Vec;
-use pulse_cdt::contracts::{require_auth, set_privileged, set_resource_limits, sha256, Authority};
+use pulse_cdt::contracts::{
+    is_feature_activated, preactivate_feature, require_auth, set_blockchain_parameters,
+    set_privileged, set_proposed_producers_ex, set_resource_limits, sha256, Authority,
+    BlockchainParameters,
+};
 use pulse_cdt::{action, contract, SAME_PAYER};
-use pulse_cdt::core::Name;
+use pulse_cdt::core::{check, Checksum256, Name, ProducerAuthority};
 
 use crate::native::{AbiHash, ABI_HASH_TABLE};
 
 #[derive(Default)]
 struct BiosContract;
 
-#[contract]
+#[contract(tables(ABI_HASH_TABLE))]
 impl BiosContract {
     #[action]
     fn setpriv(account: Name, is_priv: u8) {
@@ -28,6 +32,32 @@ impl BiosContract {
         set_resource_limits(account, ram_bytes, net_weight, cpu_weight);
     }
 
+    #[action]
+    fn setprods(schedule: Vec<ProducerAuthority>) {
+        require_auth(get_self());
+        set_proposed_producers_ex(&schedule);
+    }
+
+    #[action]
+    fn setparams(params: BlockchainParameters) {
+        require_auth(get_self());
+        set_blockchain_parameters(&params);
+    }
+
+    #[action]
+    fn activate(feature_digest: Checksum256) {
+        require_auth(get_self());
+        preactivate_feature(&feature_digest);
+    }
+
+    #[action]
+    fn reqactivated(feature_digest: Checksum256) {
+        check(
+            is_feature_activated(&feature_digest),
+            "protocol feature is not activated",
+        );
+    }
+
     #[action]
     fn reqauth(from: Name) {
         require_auth(from);
@@ -82,4 +112,93 @@ impl BiosContract {
     fn unlinkauth(account: Name, code: Name, message_type: Name) {
         // No action required
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use alloc::vec;
+    use pulse_cdt::{
+        Write,
+        contracts::{Action, PermissionLevel},
+        name,
+        testing::TestChain,
+    };
+
+    use super::*;
+
+    const BIOS: Name = name!("pulse");
+    const ALICE: Name = name!("alice");
+    const FEATURE: Checksum256 = Checksum256::new([1; 32]);
+
+    fn action<T: Write>(actor: Name, name: Name, data: T) -> Action {
+        Action::new(
+            vec![PermissionLevel::new(actor, name!("active"))],
+            BIOS,
+            name,
+            data.pack().unwrap(),
+        )
+    }
+
+    fn setup() -> TestChain {
+        let mut chain = TestChain::new();
+        chain.deploy(BIOS, crate::apply);
+        chain.create_account(ALICE);
+        chain
+    }
+
+    #[test]
+    fn activate_requires_contract_authority() {
+        let mut chain = setup();
+        let err = chain
+            .push_action(action(ALICE, name!("activate"), FEATURE))
+            .unwrap_err();
+
+        assert_eq!(err.message, "missing authority of pulse");
+        assert!(chain.preactivated_features().is_empty());
+    }
+
+    #[test]
+    fn reqactivated_fails_for_inactive_feature() {
+        let mut chain = setup();
+        let err = chain
+            .push_action(action(ALICE, name!("reqactivated"), FEATURE))
+            .unwrap_err();
+
+        assert_eq!(err.message, "protocol feature is not activated");
+    }
+
+    #[test]
+    fn reqactivated_passes_after_activate() {
+        let mut chain = setup();
+        chain
+            .push_action(action(BIOS, name!("activate"), FEATURE))
+            .unwrap();
+        assert_eq!(chain.preactivated_features(), vec![FEATURE]);
+
+        chain.activate_preactivated_features();
+        chain
+            .push_action(action(ALICE, name!("reqactivated"), FEATURE))
+            .unwrap();
+    }
+
+    #[test]
+    fn abi_matches_build_script() {
+        let mut abi = pulse_cdt::abi::generate::<BiosContract>();
+        // The build script also reads the Ricardian clauses next to the manifest.
+        for (id, body) in [
+            (
+                "BlockProducerAgreement",
+                include_str!("../ricardian/BlockProducerAgreement.md"),
+            ),
+            (
+                "UserAgreement",
+                include_str!("../ricardian/UserAgreement.md"),
+            ),
+        ] {
+            abi.ricardian_clauses
+                .insert(id.into(), body.trim_end().into());
+        }
+        assert_eq!(abi.to_json(), include_str!("../abi.json"));
+    }
+}
@@ -1,5 +1,5 @@
 #![no_std]
-#![no_main]
+#![cfg_attr(not(test), no_main)]
 extern crate alloc;
 
 use alloc::{collections::btree_set::BTreeSet, vec::Vec};
@@ -63,7 +63,7 @@ const INVALIDATIONS: MultiIndexDefinition<Invalidation> =
 #[derive(Default)]
 struct MsigContract;
 
-#[contract]
+#[contract(tables(PROPOSALS, APPROVALS, INVALIDATIONS))]
 impl MsigContract {
     #[action]
     fn propose(
@@ -274,3 +274,28 @@ impl MsigContract {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abi_matches_build_script() {
+        let mut abi = pulse_cdt::abi::generate::<MsigContract>();
+        // The build script also reads the Ricardian clauses next to the manifest.
+        for (id, body) in [
+            (
+                "BlockProducerAgreement",
+                include_str!("../ricardian/BlockProducerAgreement.md"),
+            ),
+            (
+                "UserAgreement",
+                include_str!("../ricardian/UserAgreement.md"),
+            ),
+        ] {
+            abi.ricardian_clauses
+                .insert(id.into(), body.trim_end().into());
+        }
+        assert_eq!(abi.to_json(), include_str!("../abi.json"));
+    }
+}
@@ -1,5 +1,5 @@
 #![no_std]
-#![no_main]
+#![cfg_attr(not(test), no_main)]
 extern crate alloc;
 
 mod exchange_state;
@@ -20,17 +20,18 @@ use libm::pow;
 use pulse_cdt::{
     SAME_PAYER, Write, action, constructor, contract,
     contracts::{
-        Action, ActionWrapper, Authority, KeyWeight, PermissionLevel, current_block_time,
-        current_time_point, get_resource_limits, require_auth, set_privileged, set_resource_limits,
-        sha256,
+        Action, Authority, KeyWeight, PermissionLevel, current_block_time, current_time_point,
+        get_resource_limits, preactivate_feature, require_auth, set_privileged,
+        set_resource_limits, sha256,
     },
     core::{
-        Asset, BlockHeader, BlockSigningAuthority, BlockTimestamp, ConstIterator, Microseconds,
-        MultiIndexDefinition, Name, PublicKey, SingletonDefinition, Symbol, SymbolCode, TimePoint,
-        check, has_field,
+        Asset, BlockHeader, BlockSigningAuthority, BlockTimestamp, Checksum256, ConstIterator,
+        Microseconds, MultiIndexDefinition, Name, PublicKey, Rounding, SingletonDefinition,
+        Symbol, SymbolCode, TimePoint, check, has_field,
     },
     destructor, name, symbol_with_code,
 };
+use pulse_token_client::actions as token;
 
 use crate::{
     exchange_state::get_bancor_input,
@@ -164,11 +165,37 @@ struct SystemContract {
     gstateram: GlobalStateRAM,
 }
 
-const OPEN_ACTION: ActionWrapper<(Name, Symbol, Name)> = ActionWrapper::new(name!("open"));
-const TRANSFER_ACTION: ActionWrapper<(Name, Name, Asset, String)> =
-    ActionWrapper::new(name!("transfer"));
-
-#[contract]
+#[contract(tables(
+    ABI_HASH_TABLE,
+    RAMMARKET,
+    NAME_BID_TABLE,
+    BID_REFUND_TABLE,
+    PRODUCERS_TABLE,
+    PRODUCERS_TABLE2,
+    VOTERS_TABLE,
+    USER_RESOURCES_TABLE,
+    DEL_BANDWIDTH_TABLE,
+    REFUNDS_TABLE,
+    DEL_XPR_TABLE,
+    VOTERS_XPR_TABLE,
+    XPR_REFUNDS_TABLE,
+    GLOBAL_STATEXPR_SINGLETON,
+    GLOBAL_STATESD_SINGLETON,
+    GLOBAL_STATE_RAM_SINGLETON,
+    USERRAM_TABLE,
+    REX_POOL_TABLE,
+    REX_RETURN_POOL_TABLE,
+    REX_RETURN_BUCKETS_TABLE,
+    REX_FUND_TABLE,
+    REX_BALANCE_TABLE,
+    REX_CPU_LOAN_TABLE,
+    REX_NET_LOAN_TABLE,
+    GLOBAL_STATE_SINGLETON,
+    GLOBAL_STATE2_SINGLETON,
+    GLOBAL_STATE3_SINGLETON,
+    GLOBAL_STATE4_SINGLETON,
+    STATS,
+))]
 impl SystemContract {
     #[constructor]
     fn constructor() -> Self {
@@ -228,6 +255,12 @@ impl SystemContract {
         set_privileged(account, is_priv == 1);
     }
 
+    #[action]
+    fn activate(feature_digest: Checksum256) {
+        require_auth(get_self());
+        preactivate_feature(&feature_digest);
+    }
+
     #[action]
     fn newaccount(creator: Name, name: Name, owner: Authority, active: Authority) {
         require_auth(get_self());
@@ -390,13 +423,10 @@ impl SystemContract {
             },
         );
 
-        OPEN_ACTION
-            .to_action(
-                TOKEN_ACCOUNT,
-                vec![PermissionLevel::new(get_self(), ACTIVE_PERMISSION)],
-                (REX_ACCOUNT, core, get_self()),
-            )
-            .send();
+        token::open(REX_ACCOUNT, core, get_self()).send(
+            TOKEN_ACCOUNT,
+            vec![PermissionLevel::new(get_self(), ACTIVE_PERMISSION)],
+        );
     }
 
     #[action]
@@ -431,34 +461,22 @@ impl SystemContract {
         );
         check(quant.amount > 0, "must purchase a positive amount");
 
-        let fee = Asset {
-            amount: (quant.amount + 199) / 200,
-            symbol: quant.symbol,
-        }; // ram fee 0.5%
-        let quant_after_fee = Asset {
-            amount: quant.amount - fee.amount,
-            symbol: quant.symbol,
-        };
-
-        TRANSFER_ACTION
-            .to_action(
-                TOKEN_ACCOUNT,
-                vec![
-                    PermissionLevel::new(payer, ACTIVE_PERMISSION),
-                    PermissionLevel::new(RAM_ACCOUNT, ACTIVE_PERMISSION),
-                ],
-                (payer, RAM_ACCOUNT, quant_after_fee, "buy ram".to_string()),
-            )
-            .send();
+        let fee = quant.mul_ratio(1, 200, Rounding::Ceil); // ram fee 0.5%
+        let quant_after_fee = quant - fee;
+
+        token::transfer(payer, RAM_ACCOUNT, quant_after_fee, "buy ram".to_string()).send(
+            TOKEN_ACCOUNT,
+            vec![
+                PermissionLevel::new(payer, ACTIVE_PERMISSION),
+                PermissionLevel::new(RAM_ACCOUNT, ACTIVE_PERMISSION),
+            ],
+        );
 
         if fee.amount > 0 {
-            TRANSFER_ACTION
-                .to_action(
-                    TOKEN_ACCOUNT,
-                    vec![PermissionLevel::new(payer, ACTIVE_PERMISSION)],
-                    (payer, RAMFEE_ACCOUNT, fee, "ram fee".to_string()),
-                )
-                .send();
+            token::transfer(payer, RAMFEE_ACCOUNT, fee, "ram fee".to_string()).send(
+                TOKEN_ACCOUNT,
+                vec![PermissionLevel::new(payer, ACTIVE_PERMISSION)],
+            );
         }
 
         let mut bytes_out = 0i64;
@@ -726,18 +744,16 @@ impl SystemContract {
 
             let transfer_amount = net_balance + cpu_balance;
             if 0 < transfer_amount.amount {
-                TRANSFER_ACTION
-                    .to_action(
-                        TOKEN_ACCOUNT,
-                        vec![PermissionLevel::new(source_stake_from, ACTIVE_PERMISSION)],
-                        (
-                            source_stake_from,
-                            STAKE_ACCOUNT,
-                            transfer_amount,
-                            "stake bandwidth".to_owned(),
-                        ),
-                    )
-                    .send();
+                token::transfer(
+                    source_stake_from,
+                    STAKE_ACCOUNT,
+                    transfer_amount,
+                    "stake bandwidth".to_owned(),
+                )
+                .send(
+                    TOKEN_ACCOUNT,
+                    vec![PermissionLevel::new(source_stake_from, ACTIVE_PERMISSION)],
+                );
             }
         }
 
@@ -830,21 +846,13 @@ impl SystemContract {
             req.request_time <= current_time_point().into(),
             "refund is not available yet",
         );
-        TRANSFER_ACTION
-            .to_action(
-                TOKEN_ACCOUNT,
-                vec![
-                    PermissionLevel::new(STAKE_ACCOUNT, ACTIVE_PERMISSION),
-                    PermissionLevel::new(req.owner, ACTIVE_PERMISSION),
-                ],
-                (
-                    STAKE_ACCOUNT,
-                    req.owner,
-                    req.net_amount,
-                    "unstake".to_owned(),
-                ),
-            )
-            .send();
+        token::transfer(STAKE_ACCOUNT, req.owner, req.net_amount, "unstake".to_owned()).send(
+            TOKEN_ACCOUNT,
+            vec![
+                PermissionLevel::new(STAKE_ACCOUNT, ACTIVE_PERMISSION),
+                PermissionLevel::new(req.owner, ACTIVE_PERMISSION),
+            ],
+        );
         refunds_tbl.erase(req);
     }
 
@@ -1398,3 +1406,28 @@ fn stake_to_vote(staked: i64) -> f64 {
         / 52.0;
     (staked as f64) * pow(2.0, weight)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abi_matches_build_script() {
+        let mut abi = pulse_cdt::abi::generate::<SystemContract>();
+        // The build script also reads the Ricardian clauses next to the manifest.
+        for (id, body) in [
+            (
+                "BlockProducerAgreement",
+                include_str!("../ricardian/BlockProducerAgreement.md"),
+            ),
+            (
+                "UserAgreement",
+                include_str!("../ricardian/UserAgreement.md"),
+            ),
+        ] {
+            abi.ricardian_clauses
+                .insert(id.into(), body.trim_end().into());
+        }
+        assert_eq!(abi.to_json(), include_str!("../abi.json"));
+    }
+}
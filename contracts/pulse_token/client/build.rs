@@ -0,0 +1,4 @@
+fn main() {
+    // Tells `#[contract]` to leave out the exported `apply`.
+    println!("cargo:rustc-env=PULSE_CONTRACT_CLIENT=1");
+}
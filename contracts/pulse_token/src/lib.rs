@@ -1,5 +1,5 @@
 #![no_std]
-#![no_main]
+#![cfg_attr(not(test), no_main)]
 extern crate alloc;
 use alloc::string::String;
 
@@ -31,7 +31,7 @@ pub const STATS: MultiIndexDefinition<CurrencyStats> = MultiIndexDefinition::new
 #[derive(Default)]
 struct TokenContract;
 
-#[contract]
+#[contract(tables(ACCOUNTS, STATS))]
 impl TokenContract {
     #[action]
     fn create(issuer: Name, max_supply: Asset) {
@@ -217,3 +217,108 @@ pub fn get_supply(token_contract_account: Name, sym_code: SymbolCode) -> Asset {
     let st = stats_table.get(sym_code.raw(), "symbol does not exist");
     st.supply
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use alloc::{string::ToString, vec};
+    use pulse_cdt::{
+        contracts::{Action, PermissionLevel},
+        symbol_with_code,
+        testing::TestChain,
+    };
+
+    use super::*;
+
+    const TOKEN: Name = name!("pulse.token");
+    const ALICE: Name = name!("alice");
+    const BOB: Name = name!("bob");
+    const SYS: Symbol = symbol_with_code!(4, "SYS");
+
+    fn action<T: Write>(actor: Name, name: Name, data: T) -> Action {
+        Action::new(
+            vec![PermissionLevel::new(actor, name!("active"))],
+            TOKEN,
+            name,
+            data.pack().unwrap(),
+        )
+    }
+
+    fn sys(amount: i64) -> Asset {
+        Asset { amount, symbol: SYS }
+    }
+
+    fn balance(chain: &TestChain, owner: Name) -> Option<i64> {
+        chain
+            .get_table_row::<Account>(TOKEN, owner.raw(), name!("accounts"), SYS.code().raw())
+            .map(|row| row.balance.amount)
+    }
+
+    fn setup() -> TestChain {
+        let mut chain = TestChain::new();
+        chain.deploy(TOKEN, crate::apply);
+        chain.create_account(ALICE);
+        chain.create_account(BOB);
+        chain
+            .push_action(action(TOKEN, name!("create"), (ALICE, sys(1_000_000_0000))))
+            .unwrap();
+        chain
+            .push_action(action(ALICE, name!("issue"), (ALICE, sys(100_0000), "".to_string())))
+            .unwrap();
+        chain
+    }
+
+    #[test]
+    fn issue_credits_issuer() {
+        let chain = setup();
+        assert_eq!(balance(&chain, ALICE), Some(100_0000));
+
+        let stats = chain.get_table_row::<CurrencyStats>(TOKEN, SYS.code().raw(), name!("stat"), SYS.code().raw());
+        assert_eq!(stats.map(|s| s.supply.amount), Some(100_0000));
+    }
+
+    #[test]
+    fn transfer_moves_balance_and_notifies() {
+        let mut chain = setup();
+        let trace = chain
+            .push_action(action(ALICE, name!("transfer"), (ALICE, BOB, sys(25_0000), "hi".to_string())))
+            .unwrap();
+
+        assert_eq!(balance(&chain, ALICE), Some(75_0000));
+        assert_eq!(balance(&chain, BOB), Some(25_0000));
+        assert_eq!(trace.receivers_of(TOKEN, name!("transfer")), vec![TOKEN, ALICE, BOB]);
+    }
+
+    #[test]
+    fn transfer_requires_sender_authority() {
+        let mut chain = setup();
+        let err = chain
+            .push_action(action(BOB, name!("transfer"), (ALICE, BOB, sys(1_0000), "".to_string())))
+            .unwrap_err();
+
+        assert_eq!(err.message, "missing authority of alice");
+        assert_eq!(balance(&chain, BOB), None);
+    }
+
+    #[test]
+    fn failed_transaction_is_rolled_back() {
+        let mut chain = setup();
+        let err = chain
+            .push_transaction(vec![
+                action(ALICE, name!("transfer"), (ALICE, BOB, sys(50_0000), "".to_string())),
+                action(ALICE, name!("transfer"), (ALICE, BOB, sys(60_0000), "".to_string())),
+            ])
+            .unwrap_err();
+
+        assert_eq!(err.message, "overdrawn balance");
+        assert_eq!(balance(&chain, ALICE), Some(100_0000));
+        assert_eq!(balance(&chain, BOB), None);
+    }
+
+    #[test]
+    fn abi_matches_build_script() {
+        let abi = pulse_cdt::abi::generate::<TokenContract>();
+        assert_eq!(abi.to_json(), include_str!("../abi.json"));
+    }
+}
@@ -0,0 +1,816 @@
+use std::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use pulse_cdt::{
+    abi::{Abi, AbiStruct},
+    core::{
+        Asset, BlockTimestamp, PublicKey, Signature, Symbol, SymbolCode, TimePoint, TimePointSec,
+    },
+    Read, ReadErrorKind, VarInt32, VarUint32, Write,
+};
+use pulse_name::{name_from_bytes, name_to_bytes};
+use serde_json::{Map, Number, Value};
+
+use crate::{abi_from_json, AbiError};
+
+/// Aliases, struct bases and nested types may go this deep, which stops
+/// self-referencing ABIs.
+pub const MAX_RECURSION_DEPTH: usize = 32;
+
+/// Converts values between their packed binary form and JSON according to
+/// an ABI.
+///
+/// The JSON form is the one the node's API uses:
+///
+/// - integers up to 64 bits and floats are numbers; 128-bit integers are
+///   decimal strings. Strings are accepted for all integers.
+/// - `bytes` and checksums are lowercase hex; `float128` is `0x`-prefixed
+///   hex.
+/// - `name`, `symbol`, `symbol_code`, `asset`, `public_key` and `signature`
+///   are their text forms, e.g. `"4,XPR"` or `"PUB_K1_..."`.
+/// - `time_point` and `block_timestamp_type` are `YYYY-MM-DDTHH:MM:SS.mmm`,
+///   `time_point_sec` drops the milliseconds.
+/// - optionals (`T?`) are `null` when absent and arrays (`T[]`) are arrays.
+/// - variants are `["<alternative type>", value]`.
+/// - structs are objects holding their base's fields and their own; a
+///   trailing binary extension (`T$`) field is left out when the data ends
+///   before it.
+#[derive(Debug, Clone)]
+pub struct AbiCodec {
+    abi: Abi,
+}
+
+impl AbiCodec {
+    pub fn new(abi: Abi) -> Self {
+        Self { abi }
+    }
+
+    /// Loads an `abi.json` document.
+    pub fn from_json(json: &str) -> Result<Self, AbiError> {
+        abi_from_json(json).map(Self::new)
+    }
+
+    pub fn abi(&self) -> &Abi {
+        &self.abi
+    }
+
+    /// Decodes `data`, which must hold exactly one value of `ty`.
+    pub fn binary_to_json(&self, ty: &str, data: &[u8]) -> Result<Value, AbiError> {
+        let mut decoder = Decoder {
+            abi: &self.abi,
+            data,
+            pos: 0,
+        };
+        let value = decoder.value(ty, 0)?;
+        match data.len() - decoder.pos {
+            0 => Ok(value),
+            remaining => Err(AbiError::TrailingData { remaining }),
+        }
+    }
+
+    /// Packs `value` as a `ty`.
+    pub fn json_to_binary(&self, ty: &str, value: &Value) -> Result<Vec<u8>, AbiError> {
+        let mut encoder = Encoder {
+            abi: &self.abi,
+            out: Vec::new(),
+        };
+        encoder.value(ty, value, 0)?;
+        Ok(encoder.out)
+    }
+
+    /// The type of the data of `action`.
+    pub fn action_type(&self, action: &str) -> Result<&str, AbiError> {
+        self.abi
+            .actions
+            .get(action)
+            .map(|action| action.ty.as_str())
+            .ok_or_else(|| AbiError::UnknownAction(action.to_owned()))
+    }
+
+    /// The type of the rows of `table`.
+    pub fn table_type(&self, table: &str) -> Result<&str, AbiError> {
+        self.abi
+            .tables
+            .get(table)
+            .map(|table| table.ty.as_str())
+            .ok_or_else(|| AbiError::UnknownTable(table.to_owned()))
+    }
+
+    pub fn action_to_json(&self, action: &str, data: &[u8]) -> Result<Value, AbiError> {
+        self.binary_to_json(self.action_type(action)?, data)
+    }
+
+    pub fn json_to_action(&self, action: &str, value: &Value) -> Result<Vec<u8>, AbiError> {
+        self.json_to_binary(self.action_type(action)?, value)
+    }
+
+    /// Decodes a row of `table`, as stored by `MultiIndex` or `Singleton`.
+    pub fn table_row_to_json(&self, table: &str, data: &[u8]) -> Result<Value, AbiError> {
+        self.binary_to_json(self.table_type(table)?, data)
+    }
+
+    pub fn json_to_table_row(&self, table: &str, value: &Value) -> Result<Vec<u8>, AbiError> {
+        self.json_to_binary(self.table_type(table)?, value)
+    }
+}
+
+/// Follows aliases from `ty` to the type they stand for.
+fn resolve<'a>(abi: &'a Abi, mut ty: &'a str) -> Result<&'a str, AbiError> {
+    for _ in 0..MAX_RECURSION_DEPTH {
+        match abi.types.get(ty) {
+            Some(target) => ty = target,
+            None => return Ok(ty),
+        }
+    }
+    Err(AbiError::RecursionLimit)
+}
+
+/// The struct named `ty`, aliases followed.
+fn find_struct<'a>(abi: &'a Abi, ty: &str) -> Result<&'a AbiStruct, AbiError> {
+    let resolved = resolve(abi, ty)?;
+    abi.structs
+        .get(resolved)
+        .ok_or_else(|| AbiError::UnknownType(ty.to_owned()))
+}
+
+struct Decoder<'a> {
+    abi: &'a Abi,
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize, ty: &str) -> Result<&'a [u8], AbiError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| AbiError::UnexpectedEnd { ty: ty.to_owned() })?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn fixed<const N: usize>(&mut self, ty: &str) -> Result<[u8; N], AbiError> {
+        let mut bytes = [0_u8; N];
+        bytes.copy_from_slice(self.take(N, ty)?);
+        Ok(bytes)
+    }
+
+    /// Reads a `T` with its `Read` impl, so the result matches what a
+    /// contract would read.
+    fn read<T: Read>(&mut self, ty: &str) -> Result<T, AbiError> {
+        T::read(self.data, &mut self.pos).map_err(|e| match e.kind() {
+            ReadErrorKind::NotEnoughBytes => AbiError::UnexpectedEnd { ty: ty.to_owned() },
+            _ => AbiError::BadData {
+                ty: ty.to_owned(),
+                message: e.to_string(),
+            },
+        })
+    }
+
+    fn length(&mut self, ty: &str) -> Result<usize, AbiError> {
+        Ok(self.read::<VarUint32>(ty)?.0 as usize)
+    }
+
+    fn value(&mut self, ty: &str, depth: usize) -> Result<Value, AbiError> {
+        if depth > MAX_RECURSION_DEPTH {
+            return Err(AbiError::RecursionLimit);
+        }
+        let ty = resolve(self.abi, ty)?;
+
+        if let Some(inner) = ty.strip_suffix('$') {
+            return self.value(inner, depth + 1);
+        }
+        if let Some(inner) = ty.strip_suffix('?') {
+            return match self.fixed::<1>(ty)?[0] {
+                0 => Ok(Value::Null),
+                1 => self.value(inner, depth + 1),
+                flag => Err(bad_data(ty, format!("invalid optional flag {flag}"))),
+            };
+        }
+        if let Some(elem) = ty.strip_suffix("[]") {
+            let len = self.length(ty)?;
+            // Every element takes at least one byte.
+            if len > self.data.len() - self.pos {
+                return Err(AbiError::UnexpectedEnd { ty: ty.to_owned() });
+            }
+            return (0..len)
+                .map(|_| self.value(elem, depth + 1))
+                .collect::<Result<_, _>>()
+                .map(Value::Array);
+        }
+        if let Some(value) = self.builtin(ty)? {
+            return Ok(value);
+        }
+        if let Some(types) = self.abi.variants.get(ty) {
+            let index = self.length(ty)?;
+            let alternative = types
+                .get(index)
+                .ok_or_else(|| bad_data(ty, format!("variant index {index} out of range")))?;
+            let value = self.value(alternative, depth + 1)?;
+            return Ok(Value::Array(vec![
+                Value::String(alternative.clone()),
+                value,
+            ]));
+        }
+        if self.abi.structs.contains_key(ty) {
+            let mut fields = Map::new();
+            self.struct_fields(ty, &mut fields, depth)?;
+            return Ok(Value::Object(fields));
+        }
+        Err(AbiError::UnknownType(ty.to_owned()))
+    }
+
+    fn struct_fields(
+        &mut self,
+        ty: &str,
+        fields: &mut Map<String, Value>,
+        depth: usize,
+    ) -> Result<(), AbiError> {
+        if depth > MAX_RECURSION_DEPTH {
+            return Err(AbiError::RecursionLimit);
+        }
+        let def = find_struct(self.abi, ty)?;
+        if !def.base.is_empty() {
+            self.struct_fields(&def.base, fields, depth + 1)?;
+        }
+        for (name, field_ty) in &def.fields {
+            if field_ty.ends_with('$') && self.pos == self.data.len() {
+                break;
+            }
+            fields.insert(name.clone(), self.value(field_ty, depth + 1)?);
+        }
+        Ok(())
+    }
+
+    /// `None` if `ty` isn't built in.
+    fn builtin(&mut self, ty: &str) -> Result<Option<Value>, AbiError> {
+        let value = match ty {
+            "bool" => match self.fixed::<1>(ty)?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                b => return Err(bad_data(ty, format!("invalid bool {b}"))),
+            },
+            "int8" => i8::from_le_bytes(self.fixed(ty)?).into(),
+            "uint8" => u8::from_le_bytes(self.fixed(ty)?).into(),
+            "int16" => i16::from_le_bytes(self.fixed(ty)?).into(),
+            "uint16" => u16::from_le_bytes(self.fixed(ty)?).into(),
+            "int32" => i32::from_le_bytes(self.fixed(ty)?).into(),
+            "uint32" => u32::from_le_bytes(self.fixed(ty)?).into(),
+            "int64" => i64::from_le_bytes(self.fixed(ty)?).into(),
+            "uint64" => u64::from_le_bytes(self.fixed(ty)?).into(),
+            "int128" => i128::from_le_bytes(self.fixed(ty)?).to_string().into(),
+            "uint128" => u128::from_le_bytes(self.fixed(ty)?).to_string().into(),
+            "varint32" => self.read::<VarInt32>(ty)?.0.into(),
+            "varuint32" => self.read::<VarUint32>(ty)?.0.into(),
+            "float32" => float(ty, f64::from(f32::from_le_bytes(self.fixed(ty)?)))?,
+            "float64" => float(ty, f64::from_le_bytes(self.fixed(ty)?))?,
+            "float128" => format!("0x{}", hex::encode(self.fixed::<16>(ty)?)).into(),
+            "time_point" => self.read::<TimePoint>(ty)?.to_string().into(),
+            "time_point_sec" => self.read::<TimePointSec>(ty)?.to_string().into(),
+            "block_timestamp_type" => self.read::<BlockTimestamp>(ty)?.to_string().into(),
+            "name" => name_to_string(u64::from_le_bytes(self.fixed(ty)?)).into(),
+            "bytes" => {
+                let len = self.length(ty)?;
+                hex::encode(self.take(len, ty)?).into()
+            }
+            "string" => {
+                let len = self.length(ty)?;
+                let bytes = self.take(len, ty)?;
+                core::str::from_utf8(bytes)
+                    .map_err(|e| bad_data(ty, e.to_string()))?
+                    .into()
+            }
+            "checksum160" => hex::encode(self.fixed::<20>(ty)?).into(),
+            "checksum256" => hex::encode(self.fixed::<32>(ty)?).into(),
+            "checksum512" => hex::encode(self.fixed::<64>(ty)?).into(),
+            "public_key" => self.read::<PublicKey>(ty)?.to_string().into(),
+            "signature" => self.read::<Signature>(ty)?.to_string().into(),
+            "symbol" => self.read::<Symbol>(ty)?.to_string().into(),
+            "symbol_code" => self.read::<SymbolCode>(ty)?.to_string().into(),
+            "asset" => self.read::<Asset>(ty)?.to_string().into(),
+            "extended_symbol" => {
+                let mut fields = Map::new();
+                fields.insert(
+                    "sym".to_owned(),
+                    self.read::<Symbol>(ty)?.to_string().into(),
+                );
+                let contract = u64::from_le_bytes(self.fixed(ty)?);
+                fields.insert("contract".to_owned(), name_to_string(contract).into());
+                Value::Object(fields)
+            }
+            "extended_asset" => {
+                let mut fields = Map::new();
+                fields.insert(
+                    "quantity".to_owned(),
+                    self.read::<Asset>(ty)?.to_string().into(),
+                );
+                let contract = u64::from_le_bytes(self.fixed(ty)?);
+                fields.insert("contract".to_owned(), name_to_string(contract).into());
+                Value::Object(fields)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(value))
+    }
+}
+
+struct Encoder<'a> {
+    abi: &'a Abi,
+    out: Vec<u8>,
+}
+
+impl Encoder<'_> {
+    fn pack<T: Write>(&mut self, value: &T, ty: &str) -> Result<(), AbiError> {
+        let packed = value.pack().map_err(|e| bad_value(ty, format!("{e:?}")))?;
+        self.out.extend_from_slice(&packed);
+        Ok(())
+    }
+
+    fn length(&mut self, len: usize, ty: &str) -> Result<(), AbiError> {
+        let len = u32::try_from(len).map_err(|_| bad_value(ty, "too long".to_owned()))?;
+        self.pack(&VarUint32(len), ty)
+    }
+
+    fn value(&mut self, ty: &str, value: &Value, depth: usize) -> Result<(), AbiError> {
+        if depth > MAX_RECURSION_DEPTH {
+            return Err(AbiError::RecursionLimit);
+        }
+        let ty = resolve(self.abi, ty)?;
+
+        if let Some(inner) = ty.strip_suffix('$') {
+            return self.value(inner, value, depth + 1);
+        }
+        if let Some(inner) = ty.strip_suffix('?') {
+            if value.is_null() {
+                self.out.push(0);
+                return Ok(());
+            }
+            self.out.push(1);
+            return self.value(inner, value, depth + 1);
+        }
+        if let Some(elem) = ty.strip_suffix("[]") {
+            let items = value
+                .as_array()
+                .ok_or_else(|| bad_value(ty, "expected an array".to_owned()))?;
+            self.length(items.len(), ty)?;
+            return items
+                .iter()
+                .try_for_each(|item| self.value(elem, item, depth + 1));
+        }
+        if self.builtin(ty, value)? {
+            return Ok(());
+        }
+        if let Some(types) = self.abi.variants.get(ty) {
+            let (alternative, inner) = match value.as_array().map(Vec::as_slice) {
+                Some([Value::String(alternative), inner]) => (alternative, inner),
+                _ => return Err(bad_value(ty, "expected [type, value]".to_owned())),
+            };
+            let index = types
+                .iter()
+                .position(|t| t == alternative)
+                .ok_or_else(|| bad_value(ty, format!("`{alternative}` is not an alternative")))?;
+            self.length(index, ty)?;
+            return self.value(alternative, inner, depth + 1);
+        }
+        if self.abi.structs.contains_key(ty) {
+            let fields = value
+                .as_object()
+                .ok_or_else(|| bad_value(ty, "expected an object".to_owned()))?;
+            let mut extensions_ended = false;
+            return self.struct_fields(ty, fields, &mut extensions_ended, depth);
+        }
+        Err(AbiError::UnknownType(ty.to_owned()))
+    }
+
+    /// Once a binary extension field is missing, every later one must be
+    /// too, as there would be no telling which ones were written.
+    fn struct_fields(
+        &mut self,
+        ty: &str,
+        fields: &Map<String, Value>,
+        extensions_ended: &mut bool,
+        depth: usize,
+    ) -> Result<(), AbiError> {
+        if depth > MAX_RECURSION_DEPTH {
+            return Err(AbiError::RecursionLimit);
+        }
+        let def = find_struct(self.abi, ty)?;
+        if !def.base.is_empty() {
+            self.struct_fields(&def.base, fields, extensions_ended, depth + 1)?;
+        }
+        for (name, field_ty) in &def.fields {
+            let value = fields.get(name);
+            if field_ty.ends_with('$') {
+                match value {
+                    None => *extensions_ended = true,
+                    Some(_) if *extensions_ended => {
+                        return Err(bad_value(
+                            ty,
+                            format!("`{name}` follows a missing binary extension"),
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+            if *extensions_ended {
+                continue;
+            }
+            let value = value.ok_or_else(|| AbiError::MissingField {
+                ty: ty.to_owned(),
+                field: name.clone(),
+            })?;
+            self.value(field_ty, value, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    /// `false` if `ty` isn't built in.
+    fn builtin(&mut self, ty: &str, value: &Value) -> Result<bool, AbiError> {
+        match ty {
+            "bool" => {
+                let b = value
+                    .as_bool()
+                    .ok_or_else(|| bad_value(ty, "expected a bool".to_owned()))?;
+                self.out.push(u8::from(b));
+            }
+            "int8" => self.out.extend(integer::<i8>(ty, value)?.to_le_bytes()),
+            "uint8" => self.out.extend(integer::<u8>(ty, value)?.to_le_bytes()),
+            "int16" => self.out.extend(integer::<i16>(ty, value)?.to_le_bytes()),
+            "uint16" => self.out.extend(integer::<u16>(ty, value)?.to_le_bytes()),
+            "int32" => self.out.extend(integer::<i32>(ty, value)?.to_le_bytes()),
+            "uint32" => self.out.extend(integer::<u32>(ty, value)?.to_le_bytes()),
+            "int64" => self.out.extend(integer::<i64>(ty, value)?.to_le_bytes()),
+            "uint64" => self.out.extend(integer::<u64>(ty, value)?.to_le_bytes()),
+            "int128" => self.out.extend(integer::<i128>(ty, value)?.to_le_bytes()),
+            "uint128" => {
+                let n = match value {
+                    Value::String(s) => s.parse::<u128>().ok(),
+                    Value::Number(n) => n.as_u64().map(u128::from),
+                    _ => None,
+                };
+                let n =
+                    n.ok_or_else(|| bad_value(ty, "expected an unsigned integer".to_owned()))?;
+                self.out.extend(n.to_le_bytes());
+            }
+            "varint32" => self.pack(&VarInt32(integer(ty, value)?), ty)?,
+            "varuint32" => self.pack(&VarUint32(integer(ty, value)?), ty)?,
+            "float32" => self.out.extend((number(ty, value)? as f32).to_le_bytes()),
+            "float64" => self.out.extend(number(ty, value)?.to_le_bytes()),
+            "float128" => {
+                let text = text(ty, value)?;
+                let bytes = hex_bytes::<16>(ty, text.strip_prefix("0x").unwrap_or(text))?;
+                self.out.extend(bytes);
+            }
+            "time_point" => self.pack(&parse::<TimePoint>(ty, value)?, ty)?,
+            "time_point_sec" => self.pack(&parse::<TimePointSec>(ty, value)?, ty)?,
+            "block_timestamp_type" => self.pack(&parse::<BlockTimestamp>(ty, value)?, ty)?,
+            "name" => self.out.extend(name(ty, value)?.to_le_bytes()),
+            "bytes" => {
+                let bytes =
+                    hex::decode(text(ty, value)?).map_err(|e| bad_value(ty, e.to_string()))?;
+                self.length(bytes.len(), ty)?;
+                self.out.extend(bytes);
+            }
+            "string" => {
+                let s = text(ty, value)?;
+                self.length(s.len(), ty)?;
+                self.out.extend(s.as_bytes());
+            }
+            "checksum160" => self.out.extend(hex_bytes::<20>(ty, text(ty, value)?)?),
+            "checksum256" => self.out.extend(hex_bytes::<32>(ty, text(ty, value)?)?),
+            "checksum512" => self.out.extend(hex_bytes::<64>(ty, text(ty, value)?)?),
+            "public_key" => self.pack(&parse::<PublicKey>(ty, value)?, ty)?,
+            "signature" => self.pack(&parse::<Signature>(ty, value)?, ty)?,
+            "symbol" => self.pack(&parse::<Symbol>(ty, value)?, ty)?,
+            "symbol_code" => self.pack(&parse::<SymbolCode>(ty, value)?, ty)?,
+            "asset" => self.pack(&parse::<Asset>(ty, value)?, ty)?,
+            "extended_symbol" => {
+                self.pack(&parse::<Symbol>(ty, member(ty, value, "sym")?)?, ty)?;
+                self.out
+                    .extend(name(ty, member(ty, value, "contract")?)?.to_le_bytes());
+            }
+            "extended_asset" => {
+                self.pack(&parse::<Asset>(ty, member(ty, value, "quantity")?)?, ty)?;
+                self.out
+                    .extend(name(ty, member(ty, value, "contract")?)?.to_le_bytes());
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+fn member<'v>(ty: &str, value: &'v Value, field: &str) -> Result<&'v Value, AbiError> {
+    value.get(field).ok_or_else(|| AbiError::MissingField {
+        ty: ty.to_owned(),
+        field: field.to_owned(),
+    })
+}
+
+fn bad_data(ty: &str, message: String) -> AbiError {
+    AbiError::BadData {
+        ty: ty.to_owned(),
+        message,
+    }
+}
+
+fn bad_value(ty: &str, message: String) -> AbiError {
+    AbiError::BadValue {
+        ty: ty.to_owned(),
+        message,
+    }
+}
+
+fn float(ty: &str, value: f64) -> Result<Value, AbiError> {
+    Number::from_f64(value)
+        .map(Value::Number)
+        .ok_or_else(|| bad_data(ty, format!("{value} has no JSON form")))
+}
+
+fn text<'v>(ty: &str, value: &'v Value) -> Result<&'v str, AbiError> {
+    value
+        .as_str()
+        .ok_or_else(|| bad_value(ty, "expected a string".to_owned()))
+}
+
+/// An integer from a JSON number or a decimal string.
+fn integer<T: TryFrom<i128>>(ty: &str, value: &Value) -> Result<T, AbiError> {
+    let n = match value {
+        Value::Number(n) => n.as_i64().map(i128::from).or(n.as_u64().map(i128::from)),
+        Value::String(s) => s.parse::<i128>().ok(),
+        _ => None,
+    };
+    n.and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| bad_value(ty, format!("expected an integer in range, got {value}")))
+}
+
+fn number(ty: &str, value: &Value) -> Result<f64, AbiError> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| bad_value(ty, "expected a number".to_owned()))
+}
+
+fn hex_bytes<const N: usize>(ty: &str, text: &str) -> Result<[u8; N], AbiError> {
+    let mut bytes = [0_u8; N];
+    hex::decode_to_slice(text, &mut bytes).map_err(|e| bad_value(ty, e.to_string()))?;
+    Ok(bytes)
+}
+
+fn parse<T>(ty: &str, value: &Value) -> Result<T, AbiError>
+where
+    T: core::str::FromStr,
+    T::Err: core::fmt::Debug,
+{
+    text(ty, value)?
+        .parse()
+        .map_err(|e| bad_value(ty, format!("{e:?}")))
+}
+
+fn name(ty: &str, value: &Value) -> Result<u64, AbiError> {
+    let text = text(ty, value)?;
+    let raw = name_from_bytes(text.bytes()).map_err(|e| bad_value(ty, format!("{e:?}")))?;
+    // Rejects names the node would write differently, e.g. with trailing
+    // dots.
+    if name_to_string(raw) != text {
+        return Err(bad_value(ty, format!("`{text}` is not a normalized name")));
+    }
+    Ok(raw)
+}
+
+fn name_to_string(raw: u64) -> String {
+    let bytes = name_to_bytes(raw);
+    // Names only hold ASCII characters.
+    core::str::from_utf8(&bytes)
+        .unwrap_or_default()
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use pulse_cdt::core::{
+        BlockSigningAuthority, Name, PublicKey, TimePointSec, Transaction, TransactionHeader,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    const ABI: &str = r#"{
+        "types": [{"new_type_name": "account_name", "type": "name"}],
+        "structs": [
+            {"name": "base", "base": "", "fields": [{"name": "owner", "type": "account_name"}]},
+            {"name": "row", "base": "base", "fields": [
+                {"name": "balance", "type": "asset"},
+                {"name": "memo", "type": "string?"},
+                {"name": "tags", "type": "symbol_code[]"},
+                {"name": "amount", "type": "amount"},
+                {"name": "at", "type": "time_point_sec"},
+                {"name": "extra", "type": "uint64$"}
+            ]},
+            {"name": "loop", "base": "loop", "fields": []}
+        ],
+        "variants": [{"name": "amount", "types": ["uint8", "int128"]}],
+        "actions": [{"name": "store", "type": "row"}],
+        "tables": [{"name": "rows", "type": "row"}]
+    }"#;
+
+    fn packed<T: Write>(value: T) -> Vec<u8> {
+        value.pack().unwrap()
+    }
+
+    #[test]
+    fn decodes_rows_written_by_contracts() {
+        let codec = AbiCodec::from_json(ABI).unwrap();
+        let balance: Asset = "1.5000 XPR".parse().unwrap();
+        let tags: Vec<SymbolCode> = vec!["XPR".parse().unwrap(), "USD".parse().unwrap()];
+        let mut row = packed(Name::new(pulse_cdt::name_raw!("alice")));
+        row.extend(packed(balance));
+        row.extend(packed(Some("hi".to_owned())));
+        row.extend(packed(tags));
+        row.extend(packed(VarUint32(1)));
+        row.extend(packed(-5_i128));
+        row.extend(packed(1_577_836_800_u32));
+
+        let expected = json!({
+            "owner": "alice",
+            "balance": "1.5000 XPR",
+            "memo": "hi",
+            "tags": ["XPR", "USD"],
+            "amount": ["int128", "-5"],
+            "at": "2020-01-01T00:00:00",
+        });
+        assert_eq!(codec.table_row_to_json("rows", &row).unwrap(), expected);
+        assert_eq!(codec.json_to_action("store", &expected).unwrap(), row);
+
+        // The binary extension is read when present.
+        let mut extended = row.clone();
+        extended.extend(packed(7_u64));
+        let mut with_extra = expected.clone();
+        with_extra["extra"] = json!(7);
+        assert_eq!(
+            codec.action_to_json("store", &extended).unwrap(),
+            with_extra
+        );
+        assert_eq!(
+            codec.json_to_table_row("rows", &with_extra).unwrap(),
+            extended
+        );
+    }
+
+    #[test]
+    fn decodes_token_balances() {
+        let codec =
+            AbiCodec::from_json(include_str!("../../../contracts/pulse_token/abi.json")).unwrap();
+        let balance: Asset = "12.3400 XPR".parse().unwrap();
+        let row = codec
+            .table_row_to_json("accounts", &packed(balance))
+            .unwrap();
+        assert_eq!(row, json!({ "balance": "12.3400 XPR" }));
+    }
+
+    #[test]
+    fn decodes_system_types_as_the_node_writes_them() {
+        let codec =
+            AbiCodec::from_json(include_str!("../../../contracts/pulse_msig/abi.json")).unwrap();
+        let transaction = Transaction {
+            header: TransactionHeader {
+                expiration: TimePointSec::new(60),
+                ref_block_num: 2,
+                ref_block_prefix: 3,
+                max_net_usage_words: VarUint32(0),
+                max_cpu_usage: 0,
+                delay_sec: VarUint32(10),
+            },
+            context_free_actions: Vec::new(),
+            actions: Vec::new(),
+            transaction_extensions: vec![(1, vec![0xab])],
+        };
+        let expected = json!({
+            "expiration": "1970-01-01T00:01:00",
+            "ref_block_num": 2,
+            "ref_block_prefix": 3,
+            "max_net_usage_words": 0,
+            "max_cpu_usage": 0,
+            "delay_sec": 10,
+            "context_free_actions": [],
+            "actions": [],
+            "transaction_extensions": [{"type": 1, "data": "ab"}],
+        });
+        assert_eq!(
+            codec
+                .binary_to_json("transaction", &packed(transaction.clone()))
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            codec.json_to_binary("transaction", &expected).unwrap(),
+            packed(transaction)
+        );
+
+        let codec =
+            AbiCodec::from_json(include_str!("../../../contracts/pulse_bios/abi.json")).unwrap();
+        let key = "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63";
+        let authority = BlockSigningAuthority::from(key.parse::<PublicKey>().unwrap());
+        assert_eq!(
+            codec
+                .binary_to_json("block_signing_authority", &packed(authority))
+                .unwrap(),
+            json!([
+                "block_signing_authority_v0",
+                {"threshold": 1, "keys": [{"key": key, "weight": 1}]},
+            ])
+        );
+    }
+
+    #[test]
+    fn round_trips_builtins() {
+        let codec = AbiCodec::new(Abi::default());
+        for (ty, value) in [
+            ("bool", json!(true)),
+            ("int8", json!(-8)),
+            ("uint64", json!(u64::MAX)),
+            ("uint128", json!(u128::MAX.to_string())),
+            ("varint32", json!(-300)),
+            ("varuint32", json!(300)),
+            ("float64", json!(1.5)),
+            ("float128", json!("0x000102030405060708090a0b0c0d0e0f")),
+            ("time_point", json!("2020-02-29T12:34:56.789")),
+            ("block_timestamp_type", json!("2020-01-01T00:00:00.500")),
+            ("name", json!("pulse.token")),
+            ("bytes", json!("00ff")),
+            ("checksum256", json!("ab".repeat(32))),
+            ("symbol", json!("4,XPR")),
+            (
+                "public_key",
+                json!("PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63"),
+            ),
+            (
+                "extended_symbol",
+                json!({ "sym": "4,XPR", "contract": "pulse.token" }),
+            ),
+            (
+                "extended_asset",
+                json!({ "quantity": "1.0000 XPR", "contract": "pulse.token" }),
+            ),
+        ] {
+            let packed = codec.json_to_binary(ty, &value).unwrap();
+            assert_eq!(codec.binary_to_json(ty, &packed).unwrap(), value, "{ty}");
+        }
+        assert_eq!(
+            codec.json_to_binary("uint16", &json!("513")).unwrap(),
+            [1, 2]
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        let codec = AbiCodec::from_json(ABI).unwrap();
+        assert_eq!(
+            codec.binary_to_json("uint32", &[1, 2]),
+            Err(AbiError::UnexpectedEnd {
+                ty: "uint32".to_owned()
+            })
+        );
+        assert_eq!(
+            codec.binary_to_json("uint8", &[1, 2]),
+            Err(AbiError::TrailingData { remaining: 1 })
+        );
+        assert_eq!(
+            codec.json_to_binary("base", &json!({})),
+            Err(AbiError::MissingField {
+                ty: "base".to_owned(),
+                field: "owner".to_owned()
+            })
+        );
+        assert!(matches!(
+            codec.json_to_binary("name", &json!("Alice")),
+            Err(AbiError::BadValue { .. })
+        ));
+        assert!(matches!(
+            codec.binary_to_json("amount", &[2]),
+            Err(AbiError::BadData { .. })
+        ));
+        assert_eq!(
+            codec.binary_to_json("loop", &[]),
+            Err(AbiError::RecursionLimit)
+        );
+        assert_eq!(
+            codec.json_to_binary("nope", &json!(1)),
+            Err(AbiError::UnknownType("nope".to_owned()))
+        );
+        assert_eq!(
+            codec.action_to_json("nope", &[]),
+            Err(AbiError::UnknownAction("nope".to_owned()))
+        );
+    }
+}
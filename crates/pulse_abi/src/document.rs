@@ -0,0 +1,131 @@
+use std::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use pulse_cdt::abi::{Abi, AbiAction, AbiStruct, AbiTable};
+use serde_json::{Map, Value};
+
+use crate::AbiError;
+
+/// Parses an `abi.json` document, such as the one `pulse_abigen` writes.
+///
+/// Missing sections are taken as empty, so documents of older ABI versions
+/// load as well.
+pub fn abi_from_json(json: &str) -> Result<Abi, AbiError> {
+    let doc: Value = serde_json::from_str(json).map_err(|e| AbiError::BadAbi(e.to_string()))?;
+    let doc = doc
+        .as_object()
+        .ok_or_else(|| AbiError::BadAbi("expected an object".to_owned()))?;
+
+    let mut abi = Abi::default();
+    for entry in section(doc, "types")? {
+        abi.types
+            .insert(string(entry, "new_type_name")?, string(entry, "type")?);
+    }
+    for entry in section(doc, "structs")? {
+        let fields = array(entry, "fields")?
+            .iter()
+            .map(|field| Ok((string(field, "name")?, string(field, "type")?)))
+            .collect::<Result<_, AbiError>>()?;
+        let base = optional_string(entry, "base")?;
+        abi.structs
+            .insert(string(entry, "name")?, AbiStruct { base, fields });
+    }
+    for entry in section(doc, "actions")? {
+        let action = AbiAction {
+            ty: string(entry, "type")?,
+            ricardian_contract: optional_string(entry, "ricardian_contract")?,
+        };
+        abi.actions.insert(string(entry, "name")?, action);
+    }
+    for entry in section(doc, "tables")? {
+        let table = AbiTable {
+            ty: string(entry, "type")?,
+            index_type: optional_string(entry, "index_type")?,
+            key_names: strings(entry, "key_names")?,
+            key_types: strings(entry, "key_types")?,
+        };
+        abi.tables.insert(string(entry, "name")?, table);
+    }
+    for entry in section(doc, "ricardian_clauses")? {
+        abi.ricardian_clauses
+            .insert(string(entry, "id")?, string(entry, "body")?);
+    }
+    for entry in section(doc, "variants")? {
+        abi.variants
+            .insert(string(entry, "name")?, strings(entry, "types")?);
+    }
+    for entry in section(doc, "action_results")? {
+        abi.action_results
+            .insert(string(entry, "name")?, string(entry, "result_type")?);
+    }
+    Ok(abi)
+}
+
+fn section<'a>(doc: &'a Map<String, Value>, key: &str) -> Result<&'a [Value], AbiError> {
+    match doc.get(key) {
+        None => Ok(&[]),
+        Some(Value::Array(entries)) => Ok(entries),
+        Some(_) => Err(AbiError::BadAbi(format!("`{key}` must be an array"))),
+    }
+}
+
+fn array<'a>(entry: &'a Value, key: &str) -> Result<&'a [Value], AbiError> {
+    entry
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .ok_or_else(|| AbiError::BadAbi(format!("expected an array in `{key}`")))
+}
+
+fn string(entry: &Value, key: &str) -> Result<String, AbiError> {
+    entry
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::to_owned)
+        .ok_or_else(|| AbiError::BadAbi(format!("expected a string in `{key}`")))
+}
+
+fn optional_string(entry: &Value, key: &str) -> Result<String, AbiError> {
+    match entry.get(key) {
+        None => Ok(String::new()),
+        Some(_) => string(entry, key),
+    }
+}
+
+fn strings(entry: &Value, key: &str) -> Result<Vec<String>, AbiError> {
+    match entry.get(key) {
+        None => Ok(Vec::new()),
+        Some(_) => array(entry, key)?
+            .iter()
+            .map(|s| {
+                s.as_str()
+                    .map(str::to_owned)
+                    .ok_or_else(|| AbiError::BadAbi(format!("expected strings in `{key}`")))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_generated_abi() {
+        let json = include_str!("../../../contracts/pulse_token/abi.json");
+        let abi = abi_from_json(json).unwrap();
+        assert!(abi.actions.contains_key("transfer"));
+        assert_eq!(abi.tables["accounts"].ty, "Account");
+        assert_eq!(abi.to_json(), json);
+
+        assert!(matches!(abi_from_json("[]"), Err(AbiError::BadAbi(_))));
+        assert!(matches!(
+            abi_from_json(r#"{"structs": [{"name": "s"}]}"#),
+            Err(AbiError::BadAbi(_))
+        ));
+    }
+}
@@ -0,0 +1,48 @@
+use core::fmt;
+use std::string::String;
+
+/// An error loading an ABI or converting a value with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiError {
+    /// The ABI document isn't valid JSON or has a malformed section.
+    BadAbi(String),
+    /// A type is neither built in nor defined by the ABI.
+    UnknownType(String),
+    /// The ABI has no action with this name.
+    UnknownAction(String),
+    /// The ABI has no table with this name.
+    UnknownTable(String),
+    /// Aliases, bases or nested types go deeper than
+    /// [`MAX_RECURSION_DEPTH`](crate::MAX_RECURSION_DEPTH).
+    RecursionLimit,
+    /// The binary data ended in the middle of a value of `ty`.
+    UnexpectedEnd { ty: String },
+    /// Bytes were left after the value.
+    TrailingData { remaining: usize },
+    /// The bytes of a `ty` don't decode, e.g. a string that isn't UTF-8 or
+    /// a variant index out of range.
+    BadData { ty: String, message: String },
+    /// A JSON value can't be converted to `ty`.
+    BadValue { ty: String, message: String },
+    /// A struct field is missing from a JSON object.
+    MissingField { ty: String, field: String },
+}
+
+impl fmt::Display for AbiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadAbi(message) => write!(f, "invalid ABI: {message}"),
+            Self::UnknownType(ty) => write!(f, "unknown type `{ty}`"),
+            Self::UnknownAction(name) => write!(f, "unknown action `{name}`"),
+            Self::UnknownTable(name) => write!(f, "unknown table `{name}`"),
+            Self::RecursionLimit => f.write_str("recursion limit reached"),
+            Self::UnexpectedEnd { ty } => write!(f, "unexpected end of data reading `{ty}`"),
+            Self::TrailingData { remaining } => write!(f, "{remaining} bytes left after the value"),
+            Self::BadData { ty, message } => write!(f, "bad `{ty}` data: {message}"),
+            Self::BadValue { ty, message } => write!(f, "bad `{ty}` value: {message}"),
+            Self::MissingField { ty, field } => write!(f, "`{ty}` is missing field `{field}`"),
+        }
+    }
+}
+
+impl std::error::Error for AbiError {}
@@ -0,0 +1,32 @@
+//! Converts action data and table rows between the packed binary format and
+//! JSON, driven by a contract's `abi.json` instead of its Rust types.
+//!
+//! This lets a backend or a test decode what a contract wrote without
+//! linking it:
+//!
+//! ```ignore
+//! let codec = AbiCodec::from_json(include_str!("../pulse_token/abi.json"))?;
+//! let row = codec.table_row_to_json("accounts", &packed)?;
+//! assert_eq!(row["balance"], "1.0000 XPR");
+//! ```
+//!
+//! Everything here needs the `std` feature, which is on by default.
+
+#![no_std]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "std")]
+mod codec;
+#[cfg(feature = "std")]
+mod document;
+#[cfg(feature = "std")]
+mod error;
+
+#[cfg(feature = "std")]
+pub use codec::*;
+#[cfg(feature = "std")]
+pub use document::*;
+#[cfg(feature = "std")]
+pub use error::*;
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+use crate::Error;
+
+pub const ABI_VERSION: &str = "eosio::abi/1.2";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AbiStruct {
+    pub base: String,
+    /// `(name, type)` pairs in serialization order.
+    pub fields: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AbiAction {
+    /// Name of the struct holding the action's parameters.
+    pub ty: String,
+    pub ricardian_contract: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiTable {
+    /// Name of the row struct.
+    pub ty: String,
+    pub index_type: String,
+    pub key_names: Vec<String>,
+    pub key_types: Vec<String>,
+}
+
+/// An ABI document. Every section is keyed by name, so the generated JSON
+/// does not depend on the order in which the sources were read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Abi {
+    /// `new_type_name -> type`
+    pub types: BTreeMap<String, String>,
+    pub structs: BTreeMap<String, AbiStruct>,
+    pub actions: BTreeMap<String, AbiAction>,
+    pub tables: BTreeMap<String, AbiTable>,
+    /// `id -> body`
+    pub ricardian_clauses: BTreeMap<String, String>,
+    /// `name -> alternative types`, in tag order.
+    pub variants: BTreeMap<String, Vec<String>>,
+    /// `action name -> result type`
+    pub action_results: BTreeMap<String, String>,
+}
+
+impl Abi {
+    /// Adds a struct, failing if a different struct already has its name.
+    pub fn add_struct(&mut self, name: String, def: AbiStruct) -> Result<(), Error> {
+        match self.structs.get(&name) {
+            Some(existing) if *existing != def => Err(Error::Duplicate {
+                kind: "struct",
+                name,
+            }),
+            Some(_) => Ok(()),
+            None => {
+                self.structs.insert(name, def);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let types: Vec<_> = self
+            .types
+            .iter()
+            .map(|(name, ty)| json!({ "new_type_name": name, "type": ty }))
+            .collect();
+        let structs: Vec<_> = self
+            .structs
+            .iter()
+            .map(|(name, def)| {
+                let fields: Vec<_> = def
+                    .fields
+                    .iter()
+                    .map(|(name, ty)| json!({ "name": name, "type": ty }))
+                    .collect();
+                json!({ "name": name, "base": def.base, "fields": fields })
+            })
+            .collect();
+        let actions: Vec<_> = self
+            .actions
+            .iter()
+            .map(|(name, action)| {
+                json!({
+                    "name": name,
+                    "type": action.ty,
+                    "ricardian_contract": action.ricardian_contract,
+                })
+            })
+            .collect();
+        let tables: Vec<_> = self
+            .tables
+            .iter()
+            .map(|(name, table)| {
+                json!({
+                    "name": name,
+                    "type": table.ty,
+                    "index_type": table.index_type,
+                    "key_names": table.key_names,
+                    "key_types": table.key_types,
+                })
+            })
+            .collect();
+        let ricardian_clauses: Vec<_> = self
+            .ricardian_clauses
+            .iter()
+            .map(|(id, body)| json!({ "id": id, "body": body }))
+            .collect();
+        let variants: Vec<_> = self
+            .variants
+            .iter()
+            .map(|(name, types)| json!({ "name": name, "types": types }))
+            .collect();
+        let action_results: Vec<_> = self
+            .action_results
+            .iter()
+            .map(|(name, ty)| json!({ "name": name, "result_type": ty }))
+            .collect();
+
+        json!({
+            "____comment": "This file was generated. DO NOT EDIT ",
+            "version": ABI_VERSION,
+            "types": types,
+            "structs": structs,
+            "actions": actions,
+            "tables": tables,
+            "ricardian_clauses": ricardian_clauses,
+            "error_messages": [],
+            "abi_extensions": [],
+            "variants": variants,
+            "action_results": action_results,
+        })
+    }
+
+    /// Pretty-printed JSON, terminated by a newline.
+    pub fn to_json_string(&self) -> String {
+        let mut out = serde_json::to_string_pretty(&self.to_json()).expect("ABI is valid JSON");
+        out.push('\n');
+        out
+    }
+}
+
+/// Checks that `name` is a valid account name: at most 12 characters from
+/// `a-z`, `1-5` and `.`, not ending in `.` (a 13th character may only be one
+/// of `a-j` or `1-5`).
+pub fn validate_name(name: &str) -> Result<(), &'static str> {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() || bytes.len() > 13 {
+        return Err("names are 1 to 13 characters long");
+    }
+    if bytes[bytes.len() - 1] == b'.' {
+        return Err("names cannot end with '.'");
+    }
+    for (i, &c) in bytes.iter().enumerate() {
+        let valid = if i == 12 {
+            (b'a'..=b'j').contains(&c) || (b'1'..=b'5').contains(&c)
+        } else {
+            c.is_ascii_lowercase() || (b'1'..=b'5').contains(&c) || c == b'.'
+        };
+        if !valid {
+            return Err("names may only contain a-z, 1-5 and '.'");
+        }
+    }
+    Ok(())
+}
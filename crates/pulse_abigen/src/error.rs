@@ -0,0 +1,82 @@
+use std::{fmt, io, path::PathBuf};
+
+/// Why an ABI could not be generated.
+#[derive(Debug)]
+pub enum Error {
+    /// A source file could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// A source file is not valid Rust.
+    Parse { path: PathBuf, source: syn::Error },
+    /// A `mod foo;` declaration without a matching `foo.rs` or `foo/mod.rs`.
+    MissingModule { module: String, declared_in: PathBuf },
+    /// A Rust type that has no ABI representation.
+    UnsupportedType {
+        ty: String,
+        context: String,
+        reason: &'static str,
+    },
+    /// A type that is neither built in nor defined anywhere in the crate.
+    UnknownType { ty: String, context: String },
+    /// A type name defined by more than one item of the crate.
+    AmbiguousType { ty: String, files: Vec<PathBuf> },
+    /// Two actions, tables or structs ended up with the same ABI name.
+    Duplicate { kind: &'static str, name: String },
+    /// An action or table name that is not a valid account name.
+    InvalidName {
+        name: String,
+        context: String,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "failed to read {}: {source}", path.display()),
+            Self::Parse { path, source } => {
+                write!(f, "failed to parse {}: {source}", path.display())
+            }
+            Self::MissingModule {
+                module,
+                declared_in,
+            } => write!(
+                f,
+                "module `{module}` declared in {} has no source file",
+                declared_in.display()
+            ),
+            Self::UnsupportedType {
+                ty,
+                context,
+                reason,
+            } => write!(f, "unsupported type `{ty}` in {context}: {reason}"),
+            Self::UnknownType { ty, context } => write!(
+                f,
+                "unknown type `{ty}` in {context}: it is not an ABI builtin and is not \
+                 defined in this crate"
+            ),
+            Self::AmbiguousType { ty, files } => {
+                write!(f, "type `{ty}` is defined more than once:")?;
+                for file in files {
+                    write!(f, " {}", file.display())?;
+                }
+                Ok(())
+            }
+            Self::Duplicate { kind, name } => write!(f, "duplicate {kind} `{name}`"),
+            Self::InvalidName {
+                name,
+                context,
+                reason,
+            } => write!(f, "invalid name `{name}` for {context}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,607 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use quote::ToTokens;
+use syn::{
+    Attribute, Expr, Fields, FnArg, GenericArgument, ImplItem, Item, Lit, Pat, PathArguments,
+    ReturnType, Type, TypePath, UseTree,
+};
+
+use crate::{
+    abi::validate_name, well_known, Abi, AbiAction, AbiStruct, AbiTable, Error, SourceItem,
+};
+
+/// Builds the ABI of a crate from its items, as returned by
+/// [`read_crate`](crate::read_crate).
+///
+/// Actions come from `#[action]` methods of `#[contract]` impls (and
+/// top-level `#[action]` functions), tables from `MultiIndexDefinition` and
+/// `SingletonDefinition` consts, falling back to `#[table]` structs that no
+/// const refers to. Only types reachable from those end up in the ABI.
+pub fn generate_abi(items: &[SourceItem]) -> Result<Abi, Error> {
+    let mut generator = Generator {
+        index: Index::new(items),
+        abi: Abi::default(),
+        in_progress: BTreeSet::new(),
+        aliases_in_progress: Vec::new(),
+    };
+    generator.tables(items)?;
+    generator.actions(items)?;
+    Ok(generator.abi)
+}
+
+/// Crate items that type names can resolve to.
+struct Index<'a> {
+    /// Structs and enums by name.
+    definitions: BTreeMap<String, Vec<&'a SourceItem>>,
+    /// `type A = B;` and `use path::B as A;`
+    aliases: BTreeMap<String, Type>,
+}
+
+impl<'a> Index<'a> {
+    fn new(items: &'a [SourceItem]) -> Self {
+        let mut definitions: BTreeMap<String, Vec<&SourceItem>> = BTreeMap::new();
+        let mut aliases = BTreeMap::new();
+        for source in items {
+            match &source.item {
+                Item::Struct(s) => definitions.entry(s.ident.to_string()).or_default().push(source),
+                Item::Enum(e) => definitions.entry(e.ident.to_string()).or_default().push(source),
+                Item::Type(t) if t.generics.params.is_empty() => {
+                    aliases.insert(t.ident.to_string(), (*t.ty).clone());
+                }
+                Item::Use(u) => {
+                    let mut renames = Vec::new();
+                    use_renames(&u.tree, &mut renames);
+                    for (alias, target) in renames {
+                        if let Ok(ty) = syn::parse_str::<Type>(&target) {
+                            aliases.insert(alias, ty);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self {
+            definitions,
+            aliases,
+        }
+    }
+}
+
+fn use_renames(tree: &UseTree, out: &mut Vec<(String, String)>) {
+    match tree {
+        UseTree::Path(path) => use_renames(&path.tree, out),
+        UseTree::Rename(rename) if rename.rename != "_" => {
+            out.push((rename.rename.to_string(), rename.ident.to_string()));
+        }
+        UseTree::Group(group) => group.items.iter().for_each(|t| use_renames(t, out)),
+        _ => {}
+    }
+}
+
+struct Generator<'a> {
+    index: Index<'a>,
+    abi: Abi,
+    /// Structs being described, so recursive types terminate.
+    in_progress: BTreeSet<String>,
+    aliases_in_progress: Vec<String>,
+}
+
+impl Generator<'_> {
+    fn tables(&mut self, items: &[SourceItem]) -> Result<(), Error> {
+        let mut rows_with_definition = BTreeSet::new();
+        for source in items {
+            let Item::Const(item) = &source.item else {
+                continue;
+            };
+            let Some(row) = table_row_type(&item.ty) else {
+                continue;
+            };
+            let context = format!("table `{}` ({})", item.ident, source.file.display());
+            let Some(name) = table_name(&item.expr) else {
+                continue;
+            };
+            if let Type::Path(TypePath { path, .. }) = row {
+                if let Some(last) = path.segments.last() {
+                    rows_with_definition.insert(last.ident.to_string());
+                }
+            }
+            let ty = self.abi_type(row, &context)?;
+            self.add_table(name, ty, &context)?;
+        }
+
+        for source in items {
+            let Item::Struct(item) = &source.item else {
+                continue;
+            };
+            let ident = item.ident.to_string();
+            if !has_attr(&item.attrs, "table") || rows_with_definition.contains(&ident) {
+                continue;
+            }
+            let context = format!("table struct `{ident}` ({})", source.file.display());
+            let ty = self.definition(source)?;
+            self.add_table(ident.to_lowercase(), ty, &context)?;
+        }
+        Ok(())
+    }
+
+    fn add_table(&mut self, name: String, ty: String, context: &str) -> Result<(), Error> {
+        validate_name(&name).map_err(|reason| Error::InvalidName {
+            name: name.clone(),
+            context: context.to_string(),
+            reason,
+        })?;
+        let table = AbiTable {
+            ty,
+            index_type: "i64".to_string(),
+            key_names: Vec::new(),
+            key_types: Vec::new(),
+        };
+        if self.abi.tables.insert(name.clone(), table).is_some() {
+            return Err(Error::Duplicate { kind: "table", name });
+        }
+        Ok(())
+    }
+
+    fn actions(&mut self, items: &[SourceItem]) -> Result<(), Error> {
+        for source in items {
+            match &source.item {
+                Item::Impl(item) if has_attr(&item.attrs, "contract") => {
+                    for impl_item in &item.items {
+                        if let ImplItem::Method(method) = impl_item {
+                            if has_attr(&method.attrs, "action") {
+                                self.action(&method.attrs, &method.sig, &source.file)?;
+                            }
+                        }
+                    }
+                }
+                Item::Fn(item) if has_attr(&item.attrs, "action") => {
+                    self.action(&item.attrs, &item.sig, &source.file)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn action(&mut self, attrs: &[Attribute], sig: &syn::Signature, file: &Path) -> Result<(), Error> {
+        let name = action_name(attrs).unwrap_or_else(|| sig.ident.to_string());
+        let context = format!("action `{name}` ({})", file.display());
+        validate_name(&name).map_err(|reason| Error::InvalidName {
+            name: name.clone(),
+            context: context.clone(),
+            reason,
+        })?;
+
+        let mut fields = Vec::new();
+        for arg in &sig.inputs {
+            let FnArg::Typed(arg) = arg else {
+                continue;
+            };
+            let param = match &*arg.pat {
+                Pat::Ident(ident) => ident.ident.to_string(),
+                _ => format!("arg{}", fields.len()),
+            };
+            let ty = self.abi_type(&arg.ty, &format!("parameter `{param}` of {context}"))?;
+            fields.push((param, ty));
+        }
+
+        self.abi.add_struct(
+            name.clone(),
+            AbiStruct {
+                base: String::new(),
+                fields,
+            },
+        )?;
+        if let ReturnType::Type(_, ty) = &sig.output {
+            if !matches!(&**ty, Type::Tuple(t) if t.elems.is_empty()) {
+                let result = self.abi_type(ty, &format!("return value of {context}"))?;
+                self.abi.action_results.insert(name.clone(), result);
+            }
+        }
+        let action = AbiAction {
+            ty: name.clone(),
+            ricardian_contract: String::new(),
+        };
+        if self.abi.actions.insert(name.clone(), action).is_some() {
+            return Err(Error::Duplicate { kind: "action", name });
+        }
+        Ok(())
+    }
+
+    /// The ABI name of `ty`, adding any structs, variants or types it needs.
+    fn abi_type(&mut self, ty: &Type, context: &str) -> Result<String, Error> {
+        match ty {
+            Type::Reference(r) => match &*r.elem {
+                Type::Slice(slice) => self.sequence(&slice.elem, context),
+                elem => self.abi_type(elem, context),
+            },
+            Type::Slice(slice) => self.sequence(&slice.elem, context),
+            Type::Paren(inner) => self.abi_type(&inner.elem, context),
+            Type::Group(inner) => self.abi_type(&inner.elem, context),
+            Type::Tuple(tuple) if tuple.elems.len() == 2 => {
+                let first = self.abi_type(&tuple.elems[0], context)?;
+                let second = self.abi_type(&tuple.elems[1], context)?;
+                self.pair(&first, &second)
+            }
+            Type::Tuple(_) => Err(unsupported(
+                ty,
+                context,
+                "only two-element tuples can be described, as `pair_<A>_<B>` structs",
+            )),
+            Type::Array(_) => Err(unsupported(
+                ty,
+                context,
+                "fixed-size arrays are not serializable; use `FixedBytes<N>` or `Vec<T>`",
+            )),
+            Type::Path(path) if path.qself.is_none() => self.path_type(path, ty, context),
+            _ => Err(unsupported(
+                ty,
+                context,
+                "only named types, references, slices and pairs have an ABI representation",
+            )),
+        }
+    }
+
+    fn path_type(&mut self, path: &TypePath, ty: &Type, context: &str) -> Result<String, Error> {
+        let segment = path.path.segments.last().expect("paths have a segment");
+        let name = segment.ident.to_string();
+        let args: Vec<&GenericArgument> = match &segment.arguments {
+            PathArguments::AngleBracketed(args) => args.args.iter().collect(),
+            _ => Vec::new(),
+        };
+        let type_arg = |i: usize| match args.get(i) {
+            Some(GenericArgument::Type(arg)) => Ok(arg),
+            _ => Err(unsupported(ty, context, "missing type argument")),
+        };
+
+        if let Some(builtin) = builtin(&name) {
+            return Ok(builtin.to_string());
+        }
+        match name.as_str() {
+            "Vec" | "VecDeque" | "BTreeSet" => return self.sequence(type_arg(0)?, context),
+            "Option" => return Ok(format!("{}?", self.abi_type(type_arg(0)?, context)?)),
+            "BinaryExtension" => return Ok(format!("{}$", self.abi_type(type_arg(0)?, context)?)),
+            "Ignore" => return self.abi_type(type_arg(0)?, context),
+            "BTreeMap" | "HashMap" => {
+                let key = self.abi_type(type_arg(0)?, context)?;
+                let value = self.abi_type(type_arg(1)?, context)?;
+                return Ok(format!("{}[]", self.pair(&key, &value)?));
+            }
+            "FixedBytes" => {
+                let size = match args.first() {
+                    Some(GenericArgument::Const(Expr::Lit(lit))) => match &lit.lit {
+                        Lit::Int(int) => int.base10_parse::<usize>().ok(),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                return match size {
+                    Some(20) => Ok("checksum160".to_string()),
+                    Some(32) => Ok("checksum256".to_string()),
+                    Some(64) => Ok("checksum512".to_string()),
+                    _ => Err(unsupported(
+                        ty,
+                        context,
+                        "only `FixedBytes<20>`, `FixedBytes<32>` and `FixedBytes<64>` map to ABI checksums",
+                    )),
+                };
+            }
+            _ => {}
+        }
+
+        if let Some(target) = self.index.aliases.get(&name).cloned() {
+            if !self.aliases_in_progress.contains(&name) {
+                self.aliases_in_progress.push(name);
+                let resolved = self.abi_type(&target, context);
+                self.aliases_in_progress.pop();
+                return resolved;
+            }
+        }
+        if let Some(definitions) = self.index.definitions.get(&name).cloned() {
+            if definitions.len() > 1 {
+                return Err(Error::AmbiguousType {
+                    ty: name,
+                    files: definitions.iter().map(|d| d.file.clone()).collect(),
+                });
+            }
+            return self.definition(definitions[0]);
+        }
+        if let Some(known) = well_known::by_rust_name(&name) {
+            return Ok(well_known::inject(&mut self.abi, known));
+        }
+        Err(Error::UnknownType {
+            ty: type_string(ty),
+            context: context.to_string(),
+        })
+    }
+
+    /// `T[]`, or `bytes` for a sequence of `u8`.
+    fn sequence(&mut self, elem: &Type, context: &str) -> Result<String, Error> {
+        let elem = self.abi_type(elem, context)?;
+        Ok(if elem == "uint8" {
+            "bytes".to_string()
+        } else {
+            format!("{elem}[]")
+        })
+    }
+
+    fn pair(&mut self, first: &str, second: &str) -> Result<String, Error> {
+        let name = format!("pair_{}_{}", sanitize(first), sanitize(second));
+        self.abi.add_struct(
+            name.clone(),
+            AbiStruct {
+                base: String::new(),
+                fields: vec![
+                    ("key".to_string(), first.to_string()),
+                    ("value".to_string(), second.to_string()),
+                ],
+            },
+        )?;
+        Ok(name)
+    }
+
+    /// Describes a struct or enum of the crate, returning its ABI name.
+    fn definition(&mut self, source: &SourceItem) -> Result<String, Error> {
+        let (ident, generics) = match &source.item {
+            Item::Struct(item) => (&item.ident, &item.generics),
+            Item::Enum(item) => (&item.ident, &item.generics),
+            _ => unreachable!("only structs and enums are indexed"),
+        };
+        let name = ident.to_string();
+        if self.abi.structs.contains_key(&name)
+            || self.abi.variants.contains_key(&name)
+            || !self.in_progress.insert(name.clone())
+        {
+            return Ok(name);
+        }
+        let owner = format!("`{name}` ({})", source.file.display());
+        if !generics.params.is_empty() {
+            return Err(Error::UnsupportedType {
+                ty: name,
+                context: source.file.display().to_string(),
+                reason: "generic types cannot be described in an ABI",
+            });
+        }
+
+        match &source.item {
+            Item::Struct(item) => {
+                let fields = self.fields(&item.fields, &owner)?;
+                self.abi.add_struct(
+                    name.clone(),
+                    AbiStruct {
+                        base: String::new(),
+                        fields,
+                    },
+                )?;
+            }
+            Item::Enum(item) => {
+                // Alternatives are tagged by position, matching the derives.
+                let mut types = Vec::new();
+                for variant in &item.variants {
+                    match &variant.fields {
+                        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                            let context = format!("variant `{}` of {owner}", variant.ident);
+                            types.push(self.abi_type(&fields.unnamed[0].ty, &context)?);
+                        }
+                        fields => {
+                            let struct_name = format!("{name}{}", variant.ident);
+                            let owner = format!("variant `{}` of {owner}", variant.ident);
+                            let fields = self.fields(fields, &owner)?;
+                            self.abi.add_struct(
+                                struct_name.clone(),
+                                AbiStruct {
+                                    base: String::new(),
+                                    fields,
+                                },
+                            )?;
+                            types.push(struct_name);
+                        }
+                    }
+                }
+                self.abi.variants.insert(name.clone(), types);
+            }
+            _ => unreachable!(),
+        }
+        self.in_progress.remove(&name);
+        Ok(name)
+    }
+
+    /// The fields as the derives lay them out, following their
+    /// `#[pulse(skip)]`, `#[pulse(default)]` and `#[pulse(rename = "...")]`.
+    fn fields(&mut self, fields: &Fields, owner: &str) -> Result<Vec<(String, String)>, Error> {
+        let mut out = Vec::new();
+        for (i, field) in fields.iter().enumerate() {
+            let options = FieldOptions::of(&field.attrs);
+            if options.skip {
+                continue;
+            }
+            let name = options
+                .rename
+                .or_else(|| field.ident.as_ref().map(|ident| ident.to_string()))
+                .unwrap_or_else(|| format!("field_{i}"));
+            let mut ty = self.abi_type(&field.ty, &format!("field `{name}` of {owner}"))?;
+            if options.default {
+                ty.push('$');
+            }
+            out.push((name, ty));
+        }
+        Ok(out)
+    }
+}
+
+fn builtin(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "bool" => "bool",
+        "i8" => "int8",
+        "i16" => "int16",
+        "i32" => "int32",
+        "i64" => "int64",
+        "i128" => "int128",
+        "u8" => "uint8",
+        "u16" => "uint16",
+        "u32" => "uint32",
+        "u64" => "uint64",
+        "u128" => "uint128",
+        "f32" => "float32",
+        "f64" => "float64",
+        "VarInt32" => "varint32",
+        "VarUint32" => "varuint32",
+        "String" | "str" => "string",
+        "Name" => "name",
+        "Asset" => "asset",
+        "Symbol" => "symbol",
+        "SymbolCode" => "symbol_code",
+        "ExtendedAsset" => "extended_asset",
+        "ExtendedSymbol" => "extended_symbol",
+        "Microseconds" => "int64",
+        "TimePoint" => "time_point",
+        "TimePointSec" => "time_point_sec",
+        "BlockTimestamp" => "block_timestamp_type",
+        "Checksum160" => "checksum160",
+        "Checksum256" => "checksum256",
+        "Checksum512" => "checksum512",
+        "PublicKey" => "public_key",
+        "Signature" => "signature",
+        "Bytes" => "bytes",
+        _ => return None,
+    })
+}
+
+/// For `MultiIndexDefinition<T>` and `SingletonDefinition<T>`, the row type `T`.
+fn table_row_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "MultiIndexDefinition" && segment.ident != "SingletonDefinition" {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// The first `name!("...")`, `name_raw!("...")` or string literal in a
+/// table definition's initializer.
+fn table_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Macro(mac) => {
+            let last = mac.mac.path.segments.last()?;
+            if last.ident == "name" || last.ident == "name_raw" {
+                syn::parse2::<syn::LitStr>(mac.mac.tokens.clone())
+                    .ok()
+                    .map(|s| s.value())
+            } else {
+                None
+            }
+        }
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Str(s) => Some(s.value()),
+            _ => None,
+        },
+        Expr::Call(call) => table_name(&call.func).or_else(|| call.args.iter().find_map(table_name)),
+        Expr::MethodCall(call) => {
+            table_name(&call.receiver).or_else(|| call.args.iter().find_map(table_name))
+        }
+        Expr::Field(field) => table_name(&field.base),
+        Expr::Paren(paren) => table_name(&paren.expr),
+        Expr::Cast(cast) => table_name(&cast.expr),
+        _ => None,
+    }
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .any(|a| a.path.segments.last().is_some_and(|s| s.ident == name))
+}
+
+/// The `#[pulse(...)]` options of a field that change its ABI description.
+#[derive(Default)]
+struct FieldOptions {
+    skip: bool,
+    default: bool,
+    rename: Option<String>,
+}
+
+impl FieldOptions {
+    fn of(attrs: &[Attribute]) -> Self {
+        let mut options = Self::default();
+        let nested = attrs
+            .iter()
+            .filter(|a| a.path.is_ident("pulse"))
+            .filter_map(|a| match a.parse_meta() {
+                Ok(syn::Meta::List(list)) => Some(list.nested),
+                _ => None,
+            })
+            .flatten();
+        for nested in nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") => {
+                    options.skip = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("default") => {
+                    options.default = true;
+                }
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    if let Lit::Str(s) = nv.lit {
+                        options.rename = Some(s.value());
+                    }
+                }
+                _ => {}
+            }
+        }
+        options
+    }
+}
+
+/// `#[action(name = "...")]`
+fn action_name(attrs: &[Attribute]) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|a| a.path.segments.last().is_some_and(|s| s.ident == "action"))
+        .filter_map(|a| a.parse_meta().ok())
+        .find_map(|meta| match meta {
+            syn::Meta::List(list) => list.nested.into_iter().find_map(|nested| match nested {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                    match nv.lit {
+                        Lit::Str(s) => Some(s.value()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }),
+            _ => None,
+        })
+}
+
+/// Turns an ABI type into something usable inside a struct name.
+fn sanitize(ty: &str) -> String {
+    let mut out = String::with_capacity(ty.len());
+    for c in ty.chars() {
+        let c = if c.is_ascii_alphanumeric() { c } else { '_' };
+        if c != '_' || !out.ends_with('_') {
+            out.push(c);
+        }
+    }
+    out.trim_matches('_').to_string()
+}
+
+fn type_string(ty: &Type) -> String {
+    ty.to_token_stream().to_string().replace(' ', "")
+}
+
+fn unsupported(ty: &Type, context: &str, reason: &'static str) -> Error {
+    Error::UnsupportedType {
+        ty: type_string(ty),
+        context: context.to_string(),
+        reason,
+    }
+}
@@ -0,0 +1,257 @@
+//! ABI generation for pulse contracts.
+//!
+//! Reads a contract crate's sources, starting at `src/lib.rs` and following
+//! its modules, and describes its `#[contract]` actions and its tables as an
+//! `eosio::abi/1.2` document. The output is sorted by name, so it only
+//! changes when the contract does.
+//!
+//! A contract's build script is a single call:
+//!
+//! ```ignore
+//! fn main() {
+//!     pulse_abigen::build();
+//! }
+//! ```
+//!
+//! Ricardian clauses are read from `ricardian/<id>.md` next to `Cargo.toml`.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+mod abi;
+pub use abi::*;
+
+mod error;
+pub use error::*;
+
+mod generate;
+pub use generate::*;
+
+mod source;
+pub use source::*;
+
+mod well_known;
+
+/// Generates the ABI of the contract crate at `manifest_dir`.
+pub fn generate(manifest_dir: &Path) -> Result<Abi, Error> {
+    let items = read_crate(&manifest_dir.join("src").join("lib.rs"))?;
+    let mut abi = generate_abi(&items)?;
+    abi.ricardian_clauses = read_ricardian_clauses(&manifest_dir.join("ricardian"))?;
+    Ok(abi)
+}
+
+/// Entry point for build scripts: writes `abi.json` next to `Cargo.toml`
+/// and exposes its path to the crate as `ABI_PATH`.
+///
+/// The file is only rewritten when its contents change. Errors are reported
+/// to cargo and fail the build.
+pub fn build() {
+    let manifest_dir = PathBuf::from(
+        env::var_os("CARGO_MANIFEST_DIR").expect("pulse_abigen::build runs from a build script"),
+    );
+    if let Err(e) = write_abi(&manifest_dir) {
+        eprintln!("error: failed to generate the ABI: {e}");
+        process::exit(1);
+    }
+}
+
+fn write_abi(manifest_dir: &Path) -> Result<(), Error> {
+    for dir in ["src", "ricardian"] {
+        if manifest_dir.join(dir).exists() {
+            println!("cargo:rerun-if-changed={dir}");
+        }
+    }
+
+    let json = generate(manifest_dir)?.to_json_string();
+    let path = manifest_dir.join("abi.json");
+    if fs::read_to_string(&path).ok().as_deref() != Some(json.as_str()) {
+        fs::write(&path, json).map_err(|source| Error::Io {
+            path: path.clone(),
+            source,
+        })?;
+    }
+    println!("cargo:rustc-env=ABI_PATH={}", path.display());
+    Ok(())
+}
+
+/// `id -> body` for every `*.md` file of `dir`, if it exists.
+fn read_ricardian_clauses(
+    dir: &Path,
+) -> Result<std::collections::BTreeMap<String, String>, Error> {
+    let mut clauses = std::collections::BTreeMap::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(clauses),
+    };
+    for entry in entries {
+        let path = entry
+            .map_err(|source| Error::Io {
+                path: dir.to_path_buf(),
+                source,
+            })?
+            .path();
+        if path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        let body = fs::read_to_string(&path).map_err(|source| Error::Io {
+            path: path.clone(),
+            source,
+        })?;
+        let id = path.file_stem().unwrap().to_string_lossy().into_owned();
+        clauses.insert(id, body.trim_end().to_string());
+    }
+    Ok(clauses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_crate(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("pulse_abigen_{}_{name}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (path, code) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, code).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_generate() {
+        let dir = write_crate(
+            "generate",
+            &[
+                (
+                    "src/lib.rs",
+                    r#"
+                    mod tables;
+                    use tables::*;
+
+                    type Memo = String;
+
+                    #[contract]
+                    impl Token {
+                        #[action]
+                        fn transfer(&self, from: Name, to: Name, quantity: Asset, memo: Memo) {}
+
+                        #[action(name = "setowners")]
+                        fn set_owners(&self, owners: Vec<(Name, u8)>, data: &[u8]) {}
+
+                        #[action]
+                        fn balance(&self, owner: Name) -> Asset {}
+                    }
+
+                    #[cfg(test)]
+                    mod tests;
+                    "#,
+                ),
+                (
+                    "src/tables.rs",
+                    r#"
+                    #[table(primary_key = 1)]
+                    pub struct Account {
+                        pub balance: Asset,
+                        pub state: State,
+                    }
+
+                    pub enum State {
+                        Open,
+                        Frozen(u32),
+                    }
+
+                    pub const ACCOUNTS: MultiIndexDefinition<Account> =
+                        MultiIndexDefinition::new(name!("accounts"));
+                    "#,
+                ),
+                ("ricardian/Terms.md", "Be nice.\n"),
+            ],
+        );
+        let abi = generate(&dir).unwrap();
+
+        assert_eq!(
+            abi.actions.keys().collect::<Vec<_>>(),
+            ["balance", "setowners", "transfer"]
+        );
+        assert_eq!(abi.action_results.len(), 1);
+        assert_eq!(abi.action_results["balance"], "asset");
+        assert_eq!(
+            abi.structs["transfer"].fields[3],
+            ("memo".to_string(), "string".to_string())
+        );
+        assert_eq!(
+            abi.structs["setowners"].fields,
+            [
+                ("owners".to_string(), "pair_name_uint8[]".to_string()),
+                ("data".to_string(), "bytes".to_string()),
+            ]
+        );
+        assert_eq!(abi.tables["accounts"].ty, "Account");
+        assert_eq!(abi.variants["State"], ["StateOpen", "uint32"]);
+        assert_eq!(abi.ricardian_clauses["Terms"], "Be nice.");
+
+        // Generation is deterministic.
+        assert_eq!(abi.to_json_string(), generate(&dir).unwrap().to_json_string());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_field_options() {
+        let dir = write_crate(
+            "field_options",
+            &[(
+                "src/lib.rs",
+                r#"
+                #[table(primary_key = 1)]
+                pub struct Row {
+                    #[pulse(rename = "owner")]
+                    pub account: Name,
+                    #[pulse(skip)]
+                    pub cached: u64,
+                    #[pulse(default)]
+                    pub flags: u8,
+                    pub extra: BinaryExtension<u64>,
+                }
+
+                pub const ROWS: MultiIndexDefinition<Row> = MultiIndexDefinition::new(name!("rows"));
+                "#,
+            )],
+        );
+        let abi = generate(&dir).unwrap();
+        let field = |name: &str, ty: &str| (name.to_string(), ty.to_string());
+        assert_eq!(
+            abi.structs["Row"].fields,
+            [
+                field("owner", "name"),
+                field("flags", "uint8$"),
+                field("extra", "uint64$"),
+            ]
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_type() {
+        let dir = write_crate(
+            "unknown",
+            &[(
+                "src/lib.rs",
+                r#"
+                #[contract]
+                impl Contract {
+                    #[action]
+                    fn act(&self, value: Mystery) {}
+                }
+                "#,
+            )],
+        );
+        let err = generate(&dir).unwrap_err();
+        assert!(matches!(&err, Error::UnknownType { ty, .. } if ty == "Mystery"));
+        assert!(err.to_string().contains("parameter `value` of action `act`"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
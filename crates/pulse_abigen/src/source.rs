@@ -0,0 +1,118 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use syn::{Attribute, Item, ItemMod, Lit, Meta};
+
+use crate::Error;
+
+/// A top-level item of some module, with the file it was read from.
+pub struct SourceItem {
+    pub item: Item,
+    pub file: PathBuf,
+}
+
+/// Reads `root` (usually `src/lib.rs`) and every module reachable from it,
+/// inline or out of line, flattening their items into one list.
+///
+/// Modules behind `#[cfg(test)]` are skipped.
+pub fn read_crate(root: &Path) -> Result<Vec<SourceItem>, Error> {
+    let mut out = Vec::new();
+    let dir = root.parent().unwrap_or_else(|| Path::new("."));
+    read_file(root, dir, &mut out)?;
+    Ok(out)
+}
+
+/// Every file read by [`read_crate`], for `cargo:rerun-if-changed`.
+pub fn source_files(items: &[SourceItem]) -> Vec<&Path> {
+    let mut files: Vec<&Path> = items.iter().map(|i| i.file.as_path()).collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+// `dir` is where the out-of-line children of this file live.
+fn read_file(path: &Path, dir: &Path, out: &mut Vec<SourceItem>) -> Result<(), Error> {
+    let code = fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let file = syn::parse_file(&code).map_err(|source| Error::Parse {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    read_items(file.items, path, dir, out)
+}
+
+fn read_items(
+    items: Vec<Item>,
+    file: &Path,
+    dir: &Path,
+    out: &mut Vec<SourceItem>,
+) -> Result<(), Error> {
+    for item in items {
+        match item {
+            Item::Mod(module) => read_module(module, file, dir, out)?,
+            item => out.push(SourceItem {
+                item,
+                file: file.to_path_buf(),
+            }),
+        }
+    }
+    Ok(())
+}
+
+fn read_module(
+    module: ItemMod,
+    file: &Path,
+    dir: &Path,
+    out: &mut Vec<SourceItem>,
+) -> Result<(), Error> {
+    if is_cfg_test(&module.attrs) {
+        return Ok(());
+    }
+    let name = module.ident.to_string();
+    let path_attr = path_attr(&module.attrs);
+
+    if let Some((_, items)) = module.content {
+        let child_dir = dir.join(path_attr.unwrap_or(name));
+        return read_items(items, file, &child_dir, out);
+    }
+
+    let path = match path_attr {
+        Some(path) => file.parent().unwrap_or(dir).join(path),
+        None => {
+            let flat = dir.join(format!("{name}.rs"));
+            if flat.exists() {
+                flat
+            } else {
+                dir.join(&name).join("mod.rs")
+            }
+        }
+    };
+    if !path.exists() {
+        return Err(Error::MissingModule {
+            module: name,
+            declared_in: file.to_path_buf(),
+        });
+    }
+    read_file(&path, &dir.join(&name), out)
+}
+
+fn is_cfg_test(attrs: &[Attribute]) -> bool {
+    attrs.iter().filter(|a| a.path.is_ident("cfg")).any(|a| {
+        let cfg: String = a.tokens.to_string().split_whitespace().collect();
+        cfg == "(test)" || cfg.starts_with("(all(test,")
+    })
+}
+
+fn path_attr(attrs: &[Attribute]) -> Option<String> {
+    attrs.iter().find_map(|a| match a.parse_meta() {
+        Ok(Meta::NameValue(nv)) if nv.path.is_ident("path") => match nv.lit {
+            Lit::Str(s) => Some(s.value()),
+            _ => None,
+        },
+        _ => None,
+    })
+}
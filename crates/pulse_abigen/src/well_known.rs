@@ -0,0 +1,103 @@
+//! Types provided by `pulse_cdt`, which contracts name but do not define.
+//!
+//! Their definitions come from the types' own `AbiType` impls, so the build
+//! script and `pulse_cdt::abi::generate` describe them the same way.
+
+use pulse_cdt::{
+    abi::{self, AbiType},
+    contracts::{
+        Action, Authority, BlockchainParameters, KeyWeight, PermissionLevel, PermissionLevelWeight,
+    },
+    core::{
+        BlockHeader, BlockSigningAuthority, ProducerAuthority, ProducerKey, ProducerSchedule,
+        Transaction, TransactionHeader,
+    },
+};
+
+use crate::{Abi, AbiStruct};
+
+pub struct WellKnown {
+    /// Rust type name that maps to this definition.
+    pub rust: &'static str,
+    /// Adds the type's definitions, returning its ABI name.
+    pub describe: fn(&mut abi::Abi) -> String,
+}
+
+pub const WELL_KNOWN: &[WellKnown] = &[
+    WellKnown {
+        rust: "PermissionLevel",
+        describe: PermissionLevel::abi_type,
+    },
+    WellKnown {
+        rust: "KeyWeight",
+        describe: KeyWeight::abi_type,
+    },
+    WellKnown {
+        rust: "PermissionLevelWeight",
+        describe: PermissionLevelWeight::abi_type,
+    },
+    WellKnown {
+        rust: "Authority",
+        describe: Authority::abi_type,
+    },
+    WellKnown {
+        rust: "BlockSigningAuthority",
+        describe: BlockSigningAuthority::abi_type,
+    },
+    WellKnown {
+        rust: "ProducerKey",
+        describe: ProducerKey::abi_type,
+    },
+    WellKnown {
+        rust: "ProducerAuthority",
+        describe: ProducerAuthority::abi_type,
+    },
+    WellKnown {
+        rust: "ProducerSchedule",
+        describe: ProducerSchedule::abi_type,
+    },
+    WellKnown {
+        rust: "BlockchainParameters",
+        describe: BlockchainParameters::abi_type,
+    },
+    WellKnown {
+        rust: "BlockHeader",
+        describe: BlockHeader::abi_type,
+    },
+    WellKnown {
+        rust: "TransactionHeader",
+        describe: TransactionHeader::abi_type,
+    },
+    WellKnown {
+        rust: "Transaction",
+        describe: Transaction::abi_type,
+    },
+    WellKnown {
+        rust: "Action",
+        describe: Action::abi_type,
+    },
+];
+
+pub fn by_rust_name(name: &str) -> Option<&'static WellKnown> {
+    WELL_KNOWN.iter().find(|w| w.rust == name)
+}
+
+/// Adds `known` and everything it is made of to `abi`, returning the ABI
+/// type name to refer to it by.
+pub fn inject(abi: &mut Abi, known: &WellKnown) -> String {
+    let mut described = abi::Abi::default();
+    let name = (known.describe)(&mut described);
+    for (name, def) in described.structs {
+        abi.structs.entry(name).or_insert_with(|| AbiStruct {
+            base: def.base,
+            fields: def.fields,
+        });
+    }
+    for (name, ty) in described.types {
+        abi.types.entry(name).or_insert(ty);
+    }
+    for (name, types) in described.variants {
+        abi.variants.entry(name).or_insert(types);
+    }
+    name
+}
@@ -3,6 +3,8 @@ pub const SYMBOL_CODE_MAX_LEN: usize = 7;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ParseSymbolCodeError {
+    /// The symbol is empty.
+    Empty,
     /// The symbol is too long. Symbols must be 7 characters or less.
     TooLong,
     /// The symbol contains an invalid character. Symbols can only contain
@@ -15,6 +17,9 @@ pub const fn symbol_code_from_bytes(bytes: &[u8]) -> Result<u64, ParseSymbolCode
     let mut value: u64 = 0;
 
     // length check first (you used ExactSizeIterator before)
+    if bytes.is_empty() {
+        return Err(ParseSymbolCodeError::Empty);
+    }
     if bytes.len() > SYMBOL_CODE_MAX_LEN {
         return Err(ParseSymbolCodeError::TooLong);
     }
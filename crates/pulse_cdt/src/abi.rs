@@ -0,0 +1,45 @@
+//! ABI descriptions built from the contract's own types.
+//!
+//! Every type deriving `Write` implements [`AbiType`], and `#[contract]`
+//! implements [`AbiContract`] from its actions and the tables listed in
+//! `#[contract(tables(...))]`, so a native test can produce the ABI with
+//! [`generate`]:
+//!
+//! ```ignore
+//! let abi = pulse_cdt::abi::generate::<TokenContract>();
+//! std::fs::write("abi.json", abi.to_json()).unwrap();
+//! ```
+
+pub use pulse_serialization::abi::*;
+
+use crate::core::{MultiIndexDefinition, SingletonDefinition, Table};
+
+/// Describes the actions and tables of a contract. Implemented by
+/// `#[contract]`.
+pub trait AbiContract {
+    fn describe(abi: &mut Abi);
+}
+
+/// The ABI of the contract `C`.
+pub fn generate<C: AbiContract>() -> Abi {
+    let mut abi = Abi::default();
+    C::describe(&mut abi);
+    abi
+}
+
+/// A table definition a contract can list in `#[contract(tables(...))]`.
+pub trait AbiTableDefinition {
+    fn add_to_abi(&self, abi: &mut Abi);
+}
+
+impl<T: Table + AbiType> AbiTableDefinition for MultiIndexDefinition<T> {
+    fn add_to_abi(&self, abi: &mut Abi) {
+        abi.add_table::<T>(&self.name().to_string());
+    }
+}
+
+impl<T: Table + AbiType> AbiTableDefinition for SingletonDefinition<T> {
+    fn add_to_abi(&self, abi: &mut Abi) {
+        abi.add_table::<T>(&self.name().to_string());
+    }
+}
@@ -1,10 +1,15 @@
+use alloc::string::String;
 use alloc::vec;
 use alloc::vec::Vec;
 use pulse_proc_macro::NumBytes;
-use pulse_serialization::{Read, Write};
+use pulse_serialization::{PathSegment, Read, ReadBorrowed, ReadError, Write};
 
-use crate::{contracts::PermissionLevel, core::Name};
+use crate::{
+    contracts::PermissionLevel,
+    core::{check, write_decimal, Name},
+};
 
+#[cfg(not(feature = "testing"))]
 mod action_impl {
     extern "C" {
         #[link_name = "action_data_size"]
@@ -33,24 +38,93 @@ mod action_impl {
 
         #[link_name = "send_inline"]
         pub fn send_inline(msg: *mut crate::c_void, len: usize);
+
+        #[link_name = "set_action_return_value"]
+        pub fn set_action_return_value(data: *mut crate::c_void, len: usize);
     }
 }
 
+#[cfg(feature = "testing")]
+use crate::testing::host as action_impl;
+
 #[inline]
 pub fn action_data_size() -> u32 {
     unsafe { action_impl::action_data_size() }
 }
 
-#[inline]
-pub fn read_action_data<T: Read>() -> T {
+/// Copies the current action's data, for reading it with
+/// [`ReadBorrowed`](crate::ReadBorrowed).
+pub fn action_data() -> Vec<u8> {
     let num_bytes = action_data_size();
     let mut bytes = vec![0_u8; num_bytes as usize];
     let ptr: *mut crate::c_void = &mut bytes[..] as *mut _ as *mut crate::c_void;
     unsafe {
         action_impl::read_action_data(ptr, num_bytes);
     }
-    let mut pos = 0;
-    T::read(&bytes, &mut pos).unwrap()
+    bytes
+}
+
+/// Reads the current action's data as a `T`, failing the transaction with
+/// the [`ReadError`](crate::ReadError) if it doesn't decode.
+#[inline]
+pub fn read_action_data<T: Read>() -> T {
+    match T::unpack(action_data()) {
+        Ok(value) => value,
+        Err(e) => {
+            check(false, &read_error_message("", &e));
+            unreachable!()
+        }
+    }
+}
+
+/// Reads the argument `arg` of `action` at `pos` in its `data`, as
+/// `#[contract]` does for its actions. The argument may borrow from `data`.
+///
+/// A failure names the argument, e.g.
+/// `transfer.quantity: not enough bytes at offset 24`.
+#[inline]
+pub fn read_action_arg<'a, T: ReadBorrowed<'a>>(
+    data: &'a [u8],
+    pos: &mut usize,
+    action: &str,
+    arg: &'static str,
+) -> T {
+    match T::read_borrowed(data, pos) {
+        Ok(value) => value,
+        Err(e) => {
+            check(false, &read_error_message(action, &e.in_field(arg)));
+            unreachable!()
+        }
+    }
+}
+
+/// `prefix` followed by what `ReadError`'s `Display` writes, put together
+/// without `core::fmt` so that it stays out of contract binaries.
+fn read_error_message(prefix: &str, e: &ReadError) -> String {
+    let mut buf = [0_u8; 20];
+    let mut message = String::from(prefix);
+    for (i, segment) in e.path().iter().enumerate() {
+        match segment {
+            PathSegment::Field(field) => {
+                if i > 0 || !prefix.is_empty() {
+                    message.push('.');
+                }
+                message.push_str(field);
+            }
+            PathSegment::Index(index) => {
+                message.push('[');
+                message.push_str(write_decimal(*index as u64, &mut buf));
+                message.push(']');
+            }
+        }
+    }
+    if !e.path().is_empty() {
+        message.push_str(": ");
+    }
+    message.push_str(e.kind().as_str());
+    message.push_str(" at offset ");
+    message.push_str(write_decimal(e.offset() as u64, &mut buf));
+    message
 }
 
 #[inline]
@@ -89,8 +163,17 @@ pub fn send_inline(data: &Vec<u8>) {
     unsafe { action_impl::send_inline(data.as_ptr() as *mut _, data.len()) };
 }
 
+/// Sets the serialized value the current action returns to its caller,
+/// recorded in the action's receipt. `#[action]` methods returning a value
+/// call this with it.
+#[inline]
+pub fn set_action_return_value(data: &[u8]) {
+    unsafe { action_impl::set_action_return_value(data.as_ptr() as *mut _, data.len()) };
+}
+
 #[derive(Clone, Debug, Default, Read, Write, NumBytes, PartialEq, Eq)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi_name = "action")]
+#[cfg_attr(feature = "serde", pulse(serde))]
 pub struct Action {
     /// Name of the account the action is intended for
     pub account: Name,
@@ -129,3 +212,118 @@ pub trait ActionFn: Clone {
     /// TODO docs.
     fn call(self);
 }
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+    use crate::{action, contract, name, testing::TestChain};
+
+    #[derive(Default)]
+    struct Calculator;
+
+    #[derive(crate::ReadBorrowed, Write, NumBytes)]
+    struct Note<'a> {
+        author: Name,
+        text: &'a str,
+    }
+
+    #[contract]
+    impl Calculator {
+        #[action]
+        fn add(a: u64, b: u64) -> u64 {
+            a + b
+        }
+
+        #[action]
+        fn measure<'a>(text: &'a str, note: Note<'a>, data: &[u8]) -> u64 {
+            check(note.author == name!("alice"), "wrong author");
+            (text.len() + note.text.len() + data.len()) as u64
+        }
+
+        #[action]
+        fn relay(a: u64, b: u64) {
+            actions::add(a, b).send(get_self(), vec![PermissionLevel::new(get_self(), name!("active"))]);
+        }
+    }
+
+    fn call(action: Name, data: (u64, u64)) -> Action {
+        Action::new(
+            vec![PermissionLevel::new(name!("calc"), name!("active"))],
+            name!("calc"),
+            action,
+            data.pack().unwrap(),
+        )
+    }
+
+    #[test]
+    fn action_builders_pack_their_arguments() {
+        let action = actions::add(40, 2).to_action(name!("calc"), vec![]);
+        assert_eq!(action, Action::new(vec![], name!("calc"), name!("add"), (40_u64, 2_u64).pack().unwrap()));
+    }
+
+    #[test]
+    fn action_results_are_returned() {
+        let mut chain = TestChain::new();
+        chain.deploy(name!("calc"), apply);
+
+        let trace = chain.push_action(call(name!("add"), (2, 3))).unwrap();
+        assert_eq!(trace.action_traces[0].return_value, 5_u64.pack().unwrap());
+
+        let trace = chain.push_action(call(name!("relay"), (40, 2))).unwrap();
+        assert!(trace.action_traces[0].return_value.is_empty());
+        assert_eq!(trace.return_value::<u64>(name!("calc"), name!("add")), Some(42));
+
+        let abi = crate::abi::generate::<Calculator>();
+        assert_eq!(abi.action_results.get("add").map(|t| t.to_string()), Some("uint64".to_string()));
+        assert!(!abi.action_results.contains_key("relay"));
+    }
+
+    #[test]
+    fn arguments_can_borrow_the_action_data() {
+        let mut chain = TestChain::new();
+        chain.deploy(name!("calc"), apply);
+
+        let note = Note {
+            author: name!("alice"),
+            text: "hi",
+        };
+        let action = actions::measure("hello", note, &[1, 2, 3]).to_action(
+            name!("calc"),
+            vec![PermissionLevel::new(name!("calc"), name!("active"))],
+        );
+        let trace = chain.push_action(action).unwrap();
+        assert_eq!(trace.action_traces[0].return_value, 10_u64.pack().unwrap());
+
+        let abi = crate::abi::generate::<Calculator>();
+        let fields: Vec<_> = abi.structs["measure"].fields.iter().map(|(_, ty)| ty.as_str()).collect();
+        assert_eq!(fields, ["string", "Note", "bytes"]);
+    }
+
+    #[test]
+    fn undecodable_arguments_are_named() {
+        let mut chain = TestChain::new();
+        chain.deploy(name!("calc"), apply);
+
+        let mut action = call(name!("add"), (2, 3));
+        action.data.truncate(12);
+        let err = chain.push_action(action).unwrap_err();
+        assert_eq!(err.message, "add.b: not enough bytes at offset 8");
+    }
+
+    #[test]
+    fn read_error_messages_read_like_display() {
+        let e = ReadError::new(pulse_serialization::ReadErrorKind::Overflow, "u64", 24)
+            .in_index(10)
+            .in_field("rows");
+        assert_eq!(read_error_message("", &e), e.to_string());
+        assert_eq!(read_error_message("store", &e), alloc::format!("store.{e}"));
+
+        let e = e.reading("u64", 3);
+        assert_eq!(
+            read_error_message("", &e),
+            "number out of range at offset 3"
+        );
+    }
+}
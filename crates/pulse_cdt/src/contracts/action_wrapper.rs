@@ -41,3 +41,50 @@ where
         action.send();
     }
 }
+
+/// An action whose data has already been packed, waiting to be addressed to
+/// the account running its contract.
+///
+/// `#[contract]` generates an `actions` module with one function per action
+/// returning these, taking the same parameters as the action itself:
+///
+/// ```ignore
+/// pulse_token::actions::transfer(from, to, quantity, memo)
+///     .send(name!("pulse.token"), vec![PermissionLevel::new(from, name!("active"))]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlineAction {
+    name: Name,
+    data: Vec<u8>,
+}
+
+impl InlineAction {
+    pub fn new<T: Write>(name: Name, data: T) -> Self {
+        Self {
+            name,
+            data: data.pack().expect("failed to serialize action data"),
+        }
+    }
+
+    /// An action taking no parameters.
+    pub fn without_data(name: Name) -> Self {
+        Self {
+            name,
+            data: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub const fn name(&self) -> Name {
+        self.name
+    }
+
+    pub fn to_action(self, account: Name, authorization: Vec<PermissionLevel>) -> Action {
+        Action::new(authorization, account, self.name, self.data)
+    }
+
+    /// Sends the action inline to `account`, authorized by `authorization`.
+    pub fn send(self, account: Name, authorization: Vec<PermissionLevel>) {
+        self.to_action(account, authorization).send();
+    }
+}
@@ -4,7 +4,8 @@ use pulse_proc_macro::{NumBytes, Read, Write};
 use crate::core::{Name, PublicKey};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Read, Write, NumBytes)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi_name = "authority")]
+#[cfg_attr(feature = "serde", pulse(serde))]
 pub struct Authority {
     pub threshold: u32,
     pub keys: Vec<KeyWeight>,
@@ -12,21 +13,24 @@ pub struct Authority {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi_name = "key_weight")]
+#[cfg_attr(feature = "serde", pulse(serde))]
 pub struct KeyWeight {
     pub key: PublicKey,
     pub weight: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Read, Write, NumBytes)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi_name = "permission_level_weight")]
+#[cfg_attr(feature = "serde", pulse(serde))]
 pub struct PermissionLevelWeight {
     pub permission: PermissionLevel,
     pub weight: u16,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Read, Write, NumBytes)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi_name = "permission_level")]
+#[cfg_attr(feature = "serde", pulse(serde))]
 pub struct PermissionLevel {
     pub actor: Name,
     pub permission: Name,
@@ -1,8 +1,10 @@
+use alloc::{vec, vec::Vec};
 use pulse_serialization::Write;
 
-use crate::core::{Checksum160, Checksum256, Checksum512, PublicKey, Signature};
+use crate::core::{check, Checksum160, Checksum256, Checksum512, FixedBytes, PublicKey, Signature};
 
-mod action_impl {
+#[cfg(not(any(feature = "native", not(target_arch = "wasm32"))))]
+mod hash_impl {
     extern "C" {
         #[link_name = "assert_sha1"]
         pub fn assert_sha1(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void);
@@ -32,81 +34,173 @@ mod action_impl {
         pub fn recover_key(
             digest: *mut crate::c_void,
             sig: *mut crate::c_void,
+            sig_len: usize,
             pubkey: *mut crate::c_void,
-        ) -> usize;
+            pubkey_len: usize,
+        ) -> i32;
 
         #[link_name = "assert_recover_key"]
         pub fn assert_recover_key(
             digest: *mut crate::c_void,
             sig: *mut crate::c_void,
+            sig_len: usize,
+            pubkey: *mut crate::c_void,
+            pubkey_len: usize,
+        );
+    }
+}
+
+#[cfg(any(feature = "native", not(target_arch = "wasm32")))]
+use crate::native as hash_impl;
+
+#[cfg(not(feature = "testing"))]
+mod action_impl {
+    extern "C" {
+        #[link_name = "sha3"]
+        pub fn sha3(
+            data: *mut crate::c_void,
+            data_len: u32,
+            hash: *mut crate::c_void,
+            hash_len: u32,
+            keccak: i32,
+        );
+
+        #[link_name = "blake2_f"]
+        pub fn blake2_f(
+            rounds: u32,
+            state: *mut crate::c_void,
+            state_len: u32,
+            msg: *mut crate::c_void,
+            msg_len: u32,
+            t0: *mut crate::c_void,
+            t0_len: u32,
+            t1: *mut crate::c_void,
+            t1_len: u32,
+            last: i32,
+            result: *mut crate::c_void,
+            result_len: u32,
+        ) -> i32;
+
+        #[link_name = "k1_recover"]
+        pub fn k1_recover(
+            sig: *mut crate::c_void,
+            sig_len: u32,
+            dig: *mut crate::c_void,
+            dig_len: u32,
             pubkey: *mut crate::c_void,
-        ) -> usize;
+            pubkey_len: u32,
+        ) -> i32;
+
+        #[link_name = "alt_bn128_add"]
+        pub fn alt_bn128_add(
+            op1: *mut crate::c_void,
+            op1_len: u32,
+            op2: *mut crate::c_void,
+            op2_len: u32,
+            result: *mut crate::c_void,
+            result_len: u32,
+        ) -> i32;
+
+        #[link_name = "alt_bn128_mul"]
+        pub fn alt_bn128_mul(
+            g1: *mut crate::c_void,
+            g1_len: u32,
+            scalar: *mut crate::c_void,
+            scalar_len: u32,
+            result: *mut crate::c_void,
+            result_len: u32,
+        ) -> i32;
+
+        #[link_name = "alt_bn128_pair"]
+        pub fn alt_bn128_pair(pairs: *mut crate::c_void, pairs_len: u32) -> i32;
+
+        #[link_name = "mod_exp"]
+        pub fn mod_exp(
+            base: *mut crate::c_void,
+            base_len: u32,
+            exp: *mut crate::c_void,
+            exp_len: u32,
+            modulus: *mut crate::c_void,
+            modulus_len: u32,
+            result: *mut crate::c_void,
+            result_len: u32,
+        ) -> i32;
     }
 }
 
+#[cfg(feature = "testing")]
+use crate::testing::host as action_impl;
+
 #[inline]
 pub fn assert_sha1(msg: &[u8], len: u32, hash: Checksum160) {
-    unsafe { action_impl::assert_sha1(msg.as_ptr() as *mut _, len, hash.0.as_ptr() as *mut _) };
+    unsafe { hash_impl::assert_sha1(msg.as_ptr() as *mut _, len, hash.0.as_ptr() as *mut _) };
 }
 
 #[inline]
 pub fn assert_ripemd160(msg: &[u8], len: u32, hash: Checksum160) {
-    unsafe {
-        action_impl::assert_ripemd160(msg.as_ptr() as *mut _, len, hash.0.as_ptr() as *mut _)
-    };
+    unsafe { hash_impl::assert_ripemd160(msg.as_ptr() as *mut _, len, hash.0.as_ptr() as *mut _) };
 }
 
 #[inline]
 pub fn assert_sha256(msg: &[u8], len: u32, hash: Checksum256) {
-    unsafe { action_impl::assert_sha256(msg.as_ptr() as *mut _, len, hash.0.as_ptr() as *mut _) };
+    unsafe { hash_impl::assert_sha256(msg.as_ptr() as *mut _, len, hash.0.as_ptr() as *mut _) };
 }
 
 #[inline]
 pub fn assert_sha512(msg: &[u8], len: u32, hash: Checksum512) {
-    unsafe { action_impl::assert_sha512(msg.as_ptr() as *mut _, len, hash.0.as_ptr() as *mut _) };
+    unsafe { hash_impl::assert_sha512(msg.as_ptr() as *mut _, len, hash.0.as_ptr() as *mut _) };
 }
 
 #[inline]
 pub fn sha1(msg: &[u8], len: u32) -> Checksum160 {
     let mut hash = Checksum160::default();
-    unsafe { action_impl::sha1(msg.as_ptr() as *mut _, len, &mut hash as *mut _ as *mut _) };
+    unsafe { hash_impl::sha1(msg.as_ptr() as *mut _, len, &mut hash as *mut _ as *mut _) };
     hash
 }
 
 #[inline]
 pub fn ripemd160(msg: &[u8], len: u32) -> Checksum160 {
     let mut hash = Checksum160::default();
-    unsafe { action_impl::ripemd160(msg.as_ptr() as *mut _, len, &mut hash as *mut _ as *mut _) };
+    unsafe { hash_impl::ripemd160(msg.as_ptr() as *mut _, len, &mut hash as *mut _ as *mut _) };
     hash
 }
 
 #[inline]
 pub fn sha256(msg: &[u8], len: u32) -> Checksum256 {
     let mut hash = Checksum256::default();
-    unsafe { action_impl::sha256(msg.as_ptr() as *mut _, len, &mut hash as *mut _ as *mut _) };
+    unsafe { hash_impl::sha256(msg.as_ptr() as *mut _, len, &mut hash as *mut _ as *mut _) };
     hash
 }
 
 #[inline]
 pub fn sha512(msg: &[u8], len: u32) -> Checksum512 {
     let mut hash = Checksum512::default();
-    unsafe { action_impl::sha512(msg.as_ptr() as *mut _, len, &mut hash as *mut _ as *mut _) };
+    unsafe { hash_impl::sha512(msg.as_ptr() as *mut _, len, &mut hash as *mut _ as *mut _) };
     hash
 }
 
-#[inline]
+/// Recovers the key that made `sig` over `digest`.
 pub fn recover_key(digest: &Checksum256, sig: &Signature) -> PublicKey {
     let sig_data = sig.pack().expect("failed to serialize signature");
-    let pubkey_data = &[0u8; 33];
-    unsafe {
-        action_impl::recover_key(
+    let recover = |pubkey_data: &mut [u8]| unsafe {
+        hash_impl::recover_key(
             digest.0.as_ptr() as *mut _,
             sig_data.as_ptr() as *mut _,
-            pubkey_data.as_ptr() as *mut _,
-        )
+            sig_data.len(),
+            pubkey_data.as_mut_ptr() as *mut _,
+            pubkey_data.len(),
+        ) as usize
     };
+    // The host returns the key's full size and copies what fits, so a key
+    // larger than the usual K1 or R1 one takes a second call.
+    let mut pubkey_data = vec![0_u8; 34];
+    let size = recover(&mut pubkey_data);
+    if size > pubkey_data.len() {
+        pubkey_data.resize(size, 0);
+        recover(&mut pubkey_data);
+    }
 
-    return PublicKey::new(&pubkey_data[..33]);
+    PublicKey::new(&pubkey_data[..size])
 }
 
 #[inline]
@@ -114,10 +208,305 @@ pub fn assert_recover_key(digest: &Checksum256, sig: &Signature, pubkey: &Public
     let sig_data = sig.pack().expect("failed to serialize signature");
     let pubkey_data = pubkey.pack().expect("failed to serialize public key");
     unsafe {
-        action_impl::assert_recover_key(
+        hash_impl::assert_recover_key(
             digest.0.as_ptr() as *mut _,
             sig_data.as_ptr() as *mut _,
+            sig_data.len(),
             pubkey_data.as_ptr() as *mut _,
+            pubkey_data.len(),
+        )
+    };
+}
+
+/// The host rejected the input of one of the extended crypto functions,
+/// e.g. a point that isn't on the curve or a malformed signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CryptoError;
+
+/// A point on the alt_bn128 G1 curve, `x` then `y` as 32-byte big-endian
+/// numbers; all zeros is the point at infinity.
+pub type G1Point = [u8; 64];
+
+/// A point on the alt_bn128 G2 curve, `(x.imaginary, x.real, y.imaginary,
+/// y.real)` as 32-byte big-endian numbers, the EIP-197 order.
+pub type G2Point = [u8; 128];
+
+/// An uncompressed secp256k1 key: `0x04`, then `x` and `y`.
+pub type UncompressedKey = FixedBytes<65>;
+
+/// What the host functions return when they reject their input.
+const CRYPTO_ERROR: i32 = -1;
+
+fn host_result<T>(status: i32, value: T) -> Result<T, CryptoError> {
+    if status == CRYPTO_ERROR {
+        Err(CryptoError)
+    } else {
+        Ok(value)
+    }
+}
+
+fn hash_sha3(data: &[u8], keccak: bool) -> Checksum256 {
+    let mut hash = Checksum256::default();
+    unsafe {
+        action_impl::sha3(
+            data.as_ptr() as *mut _,
+            data.len() as u32,
+            hash.0.as_mut_ptr() as *mut _,
+            hash.0.len() as u32,
+            i32::from(keccak),
+        )
+    };
+    hash
+}
+
+/// The NIST SHA3-256 hash of `data`.
+#[inline]
+pub fn sha3(data: &[u8]) -> Checksum256 {
+    hash_sha3(data, false)
+}
+
+/// The Keccak-256 hash of `data`, as used by Ethereum.
+#[inline]
+pub fn keccak(data: &[u8]) -> Checksum256 {
+    hash_sha3(data, true)
+}
+
+#[inline]
+pub fn assert_sha3(data: &[u8], hash: &Checksum256) {
+    check(
+        sha3(data) == *hash,
+        "SHA3 hash of `data` does not match given `hash`",
+    );
+}
+
+#[inline]
+pub fn assert_keccak(data: &[u8], hash: &Checksum256) {
+    check(
+        keccak(data) == *hash,
+        "Keccak hash of `data` does not match given `hash`",
+    );
+}
+
+/// Runs `rounds` rounds of the BLAKE2b compression function `F` over
+/// `state`, as specified by EIP-152. `t0` and `t1` are the little-endian
+/// offset counters and `last` flags the final block.
+pub fn blake2_f(
+    rounds: u32,
+    state: &[u8; 64],
+    message: &[u8; 128],
+    t0: &[u8; 8],
+    t1: &[u8; 8],
+    last: bool,
+) -> Result<[u8; 64], CryptoError> {
+    let mut result = [0_u8; 64];
+    let status = unsafe {
+        action_impl::blake2_f(
+            rounds,
+            state.as_ptr() as *mut _,
+            state.len() as u32,
+            message.as_ptr() as *mut _,
+            message.len() as u32,
+            t0.as_ptr() as *mut _,
+            t0.len() as u32,
+            t1.as_ptr() as *mut _,
+            t1.len() as u32,
+            i32::from(last),
+            result.as_mut_ptr() as *mut _,
+            result.len() as u32,
+        )
+    };
+    host_result(status, result)
+}
+
+/// Recovers the uncompressed secp256k1 key that made `sig` over `digest`.
+///
+/// `sig` is `v`, `r` then `s`, where `v` is 27 or 28, plus 4 when the
+/// signer's key is compressed.
+pub fn k1_recover(sig: &[u8; 65], digest: &Checksum256) -> Result<UncompressedKey, CryptoError> {
+    let mut pubkey = UncompressedKey::default();
+    let status = unsafe {
+        action_impl::k1_recover(
+            sig.as_ptr() as *mut _,
+            sig.len() as u32,
+            digest.0.as_ptr() as *mut _,
+            digest.0.len() as u32,
+            pubkey.0.as_mut_ptr() as *mut _,
+            pubkey.0.len() as u32,
+        )
+    };
+    host_result(status, pubkey)
+}
+
+/// The Ethereum address of `pubkey`: the last 20 bytes of the Keccak-256
+/// of its coordinates.
+pub fn eth_address(pubkey: &UncompressedKey) -> Checksum160 {
+    let hash = keccak(&pubkey.0[1..]);
+    let mut address = Checksum160::default();
+    address.0.copy_from_slice(&hash.0[12..]);
+    address
+}
+
+/// The Ethereum address that made `sig` over `digest`.
+#[inline]
+pub fn recover_eth_address(
+    sig: &[u8; 65],
+    digest: &Checksum256,
+) -> Result<Checksum160, CryptoError> {
+    k1_recover(sig, digest).map(|pubkey| eth_address(&pubkey))
+}
+
+/// Adds two alt_bn128 G1 points.
+pub fn alt_bn128_add(a: &G1Point, b: &G1Point) -> Result<G1Point, CryptoError> {
+    let mut result = [0_u8; 64];
+    let status = unsafe {
+        action_impl::alt_bn128_add(
+            a.as_ptr() as *mut _,
+            a.len() as u32,
+            b.as_ptr() as *mut _,
+            b.len() as u32,
+            result.as_mut_ptr() as *mut _,
+            result.len() as u32,
+        )
+    };
+    host_result(status, result)
+}
+
+/// Multiplies an alt_bn128 G1 point by a 32-byte big-endian scalar.
+pub fn alt_bn128_mul(point: &G1Point, scalar: &[u8; 32]) -> Result<G1Point, CryptoError> {
+    let mut result = [0_u8; 64];
+    let status = unsafe {
+        action_impl::alt_bn128_mul(
+            point.as_ptr() as *mut _,
+            point.len() as u32,
+            scalar.as_ptr() as *mut _,
+            scalar.len() as u32,
+            result.as_mut_ptr() as *mut _,
+            result.len() as u32,
         )
     };
+    host_result(status, result)
+}
+
+/// Whether the product of the pairings of `pairs` is one, the check behind
+/// zk-SNARK verifiers. No pairs at all pass.
+pub fn alt_bn128_pair(pairs: &[(G1Point, G2Point)]) -> Result<bool, CryptoError> {
+    let mut data = Vec::with_capacity(pairs.len() * 192);
+    for (g1, g2) in pairs {
+        data.extend_from_slice(g1);
+        data.extend_from_slice(g2);
+    }
+    let status =
+        unsafe { action_impl::alt_bn128_pair(data.as_mut_ptr() as *mut _, data.len() as u32) };
+    host_result(status, status == 0)
+}
+
+/// `base` to the power of `exponent`, modulo `modulus`, all big-endian.
+///
+/// The result has as many bytes as `modulus`, which must not be empty.
+pub fn mod_exp(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let mut result = vec![0_u8; modulus.len()];
+    let status = unsafe {
+        action_impl::mod_exp(
+            base.as_ptr() as *mut _,
+            base.len() as u32,
+            exponent.as_ptr() as *mut _,
+            exponent.len() as u32,
+            modulus.as_ptr() as *mut _,
+            modulus.len() as u32,
+            result.as_mut_ptr() as *mut _,
+            result.len() as u32,
+        )
+    };
+    host_result(status, result)
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+
+    fn hex<const N: usize>(text: &str) -> [u8; N] {
+        let mut out = [0_u8; N];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn sha3_and_keccak() {
+        assert_eq!(
+            sha3(b"").0,
+            hex::<32>("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a")
+        );
+        assert_eq!(
+            keccak(b"").0,
+            hex::<32>("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_keccak(b"", &keccak(b""));
+    }
+
+    #[test]
+    fn blake2_f_matches_eip152() {
+        let state = hex::<64>(concat!(
+            "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5",
+            "d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+        ));
+        let mut message = [0_u8; 128];
+        message[..3].copy_from_slice(b"abc");
+        let t0 = 3_u64.to_le_bytes();
+        let result = blake2_f(12, &state, &message, &t0, &[0; 8], true).unwrap();
+        assert_eq!(
+            result,
+            hex::<64>(concat!(
+                "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1",
+                "7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+            ))
+        );
+    }
+
+    #[test]
+    fn recovers_eth_address() {
+        use k256::ecdsa::SigningKey;
+
+        // The address of private key 1.
+        let key = SigningKey::from_slice(&[[0; 31].as_slice(), &[1]].concat()).unwrap();
+        let digest = keccak(b"hello");
+        let (signature, recovery_id) = key.sign_prehash_recoverable(&digest.0).unwrap();
+        let mut sig = [0_u8; 65];
+        sig[0] = 27 + recovery_id.to_byte();
+        sig[1..].copy_from_slice(&signature.to_bytes());
+
+        assert_eq!(
+            recover_eth_address(&sig, &digest).unwrap().0,
+            hex::<20>("7e5f4552091a69125d5dfcb7b8c2659029395bdf")
+        );
+        sig[0] = 0;
+        assert_eq!(k1_recover(&sig, &digest), Err(CryptoError));
+    }
+
+    #[test]
+    fn alt_bn128() {
+        let mut generator = [0_u8; 64];
+        generator[31] = 1;
+        generator[63] = 2;
+        let mut two = [0_u8; 32];
+        two[31] = 2;
+        assert_eq!(
+            alt_bn128_add(&generator, &generator),
+            alt_bn128_mul(&generator, &two)
+        );
+        assert_eq!(alt_bn128_add(&generator, &[0; 64]), Ok(generator));
+
+        let mut off_curve = generator;
+        off_curve[63] = 3;
+        assert_eq!(alt_bn128_add(&generator, &off_curve), Err(CryptoError));
+        assert_eq!(alt_bn128_pair(&[]), Ok(true));
+        assert_eq!(alt_bn128_pair(&[(off_curve, [0; 128])]), Err(CryptoError));
+    }
+
+    #[test]
+    fn mod_exp_pads_to_modulus() {
+        assert_eq!(mod_exp(&[3], &[5], &[0, 7]), Ok(vec![0, 5]));
+        assert_eq!(mod_exp(&[3], &[5], &[]), Err(CryptoError));
+    }
 }
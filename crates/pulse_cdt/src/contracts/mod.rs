@@ -13,6 +13,9 @@ pub use crypto::*;
 mod multi_index;
 pub use multi_index::*;
 
+mod print;
+pub use print::*;
+
 mod priviliged;
 pub use priviliged::*;
 
@@ -1,9 +1,10 @@
 use crate::core::Name;
 
+#[cfg(not(feature = "testing"))]
 mod database_impl {
     unsafe extern "C" {
         #[link_name = "db_get_i64"]
-        pub fn db_get_i64(iterator: i32, data: *const crate::c_void, len: u32) -> i32;
+        pub fn db_get_i64(iterator: i32, data: *mut crate::c_void, len: u32) -> i32;
 
         #[link_name = "db_remove_i64"]
         pub fn db_remove_i64(iterator: i32);
@@ -102,12 +103,277 @@ mod database_impl {
 
         #[link_name = "db_idx64_previous"]
         pub unsafe fn db_idx64_previous(iterator: i32, primary: *mut u64) -> i32;
+
+        #[link_name = "db_idx128_store"]
+        pub unsafe fn db_idx128_store(
+            scope: u64,
+            table: u64,
+            payer: u64,
+            id: u64,
+            secondary: *const u128,
+        ) -> i32;
+
+        #[link_name = "db_idx128_update"]
+        pub unsafe fn db_idx128_update(
+            iterator: i32,
+            payer: u64,
+            secondary: *const u128,
+        );
+
+        #[link_name = "db_idx128_remove"]
+        pub unsafe fn db_idx128_remove(iterator: i32);
+
+        #[link_name = "db_idx128_find_secondary"]
+        pub unsafe fn db_idx128_find_secondary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *const u128,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx128_find_primary"]
+        pub unsafe fn db_idx128_find_primary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut u128,
+            primary: u64,
+        ) -> i32;
+
+        #[link_name = "db_idx128_lowerbound"]
+        pub unsafe fn db_idx128_lowerbound(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut u128,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx128_upperbound"]
+        pub unsafe fn db_idx128_upperbound(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut u128,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx128_end"]
+        pub unsafe fn db_idx128_end(code: u64, scope: u64, table: u64) -> i32;
+
+        #[link_name = "db_idx128_next"]
+        pub unsafe fn db_idx128_next(iterator: i32, primary: *mut u64) -> i32;
+
+        #[link_name = "db_idx128_previous"]
+        pub unsafe fn db_idx128_previous(iterator: i32, primary: *mut u64) -> i32;
+
+        #[link_name = "db_idx256_store"]
+        pub unsafe fn db_idx256_store(
+            scope: u64,
+            table: u64,
+            payer: u64,
+            id: u64,
+            data: *const u128,
+            data_len: u32,
+        ) -> i32;
+
+        #[link_name = "db_idx256_update"]
+        pub unsafe fn db_idx256_update(
+            iterator: i32,
+            payer: u64,
+            data: *const u128,
+            data_len: u32,
+        );
+
+        #[link_name = "db_idx256_remove"]
+        pub unsafe fn db_idx256_remove(iterator: i32);
+
+        #[link_name = "db_idx256_find_secondary"]
+        pub unsafe fn db_idx256_find_secondary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            data: *const u128,
+            data_len: u32,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx256_find_primary"]
+        pub unsafe fn db_idx256_find_primary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            data: *mut u128,
+            data_len: u32,
+            primary: u64,
+        ) -> i32;
+
+        #[link_name = "db_idx256_lowerbound"]
+        pub unsafe fn db_idx256_lowerbound(
+            code: u64,
+            scope: u64,
+            table: u64,
+            data: *mut u128,
+            data_len: u32,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx256_upperbound"]
+        pub unsafe fn db_idx256_upperbound(
+            code: u64,
+            scope: u64,
+            table: u64,
+            data: *mut u128,
+            data_len: u32,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx256_end"]
+        pub unsafe fn db_idx256_end(code: u64, scope: u64, table: u64) -> i32;
+
+        #[link_name = "db_idx256_next"]
+        pub unsafe fn db_idx256_next(iterator: i32, primary: *mut u64) -> i32;
+
+        #[link_name = "db_idx256_previous"]
+        pub unsafe fn db_idx256_previous(iterator: i32, primary: *mut u64) -> i32;
+
+        #[link_name = "db_idx_double_store"]
+        pub unsafe fn db_idx_double_store(
+            scope: u64,
+            table: u64,
+            payer: u64,
+            id: u64,
+            secondary: *const f64,
+        ) -> i32;
+
+        #[link_name = "db_idx_double_update"]
+        pub unsafe fn db_idx_double_update(
+            iterator: i32,
+            payer: u64,
+            secondary: *const f64,
+        );
+
+        #[link_name = "db_idx_double_remove"]
+        pub unsafe fn db_idx_double_remove(iterator: i32);
+
+        #[link_name = "db_idx_double_find_secondary"]
+        pub unsafe fn db_idx_double_find_secondary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *const f64,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx_double_find_primary"]
+        pub unsafe fn db_idx_double_find_primary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut f64,
+            primary: u64,
+        ) -> i32;
+
+        #[link_name = "db_idx_double_lowerbound"]
+        pub unsafe fn db_idx_double_lowerbound(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut f64,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx_double_upperbound"]
+        pub unsafe fn db_idx_double_upperbound(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut f64,
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx_double_end"]
+        pub unsafe fn db_idx_double_end(code: u64, scope: u64, table: u64) -> i32;
+
+        #[link_name = "db_idx_double_next"]
+        pub unsafe fn db_idx_double_next(iterator: i32, primary: *mut u64) -> i32;
+
+        #[link_name = "db_idx_double_previous"]
+        pub unsafe fn db_idx_double_previous(iterator: i32, primary: *mut u64) -> i32;
+
+        #[link_name = "db_idx_long_double_store"]
+        pub unsafe fn db_idx_long_double_store(
+            scope: u64,
+            table: u64,
+            payer: u64,
+            id: u64,
+            secondary: *const [u8; 16],
+        ) -> i32;
+
+        #[link_name = "db_idx_long_double_update"]
+        pub unsafe fn db_idx_long_double_update(
+            iterator: i32,
+            payer: u64,
+            secondary: *const [u8; 16],
+        );
+
+        #[link_name = "db_idx_long_double_remove"]
+        pub unsafe fn db_idx_long_double_remove(iterator: i32);
+
+        #[link_name = "db_idx_long_double_find_secondary"]
+        pub unsafe fn db_idx_long_double_find_secondary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *const [u8; 16],
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx_long_double_find_primary"]
+        pub unsafe fn db_idx_long_double_find_primary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut [u8; 16],
+            primary: u64,
+        ) -> i32;
+
+        #[link_name = "db_idx_long_double_lowerbound"]
+        pub unsafe fn db_idx_long_double_lowerbound(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut [u8; 16],
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx_long_double_upperbound"]
+        pub unsafe fn db_idx_long_double_upperbound(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *mut [u8; 16],
+            primary: *mut u64,
+        ) -> i32;
+
+        #[link_name = "db_idx_long_double_end"]
+        pub unsafe fn db_idx_long_double_end(code: u64, scope: u64, table: u64) -> i32;
+
+        #[link_name = "db_idx_long_double_next"]
+        pub unsafe fn db_idx_long_double_next(iterator: i32, primary: *mut u64) -> i32;
+
+        #[link_name = "db_idx_long_double_previous"]
+        pub unsafe fn db_idx_long_double_previous(iterator: i32, primary: *mut u64) -> i32;
     }
 }
 
+#[cfg(feature = "testing")]
+use crate::testing::host as database_impl;
+
 #[inline]
-pub fn db_get_i64(iterator: i32, data: &[u8], len: u32) -> i32 {
-    unsafe { database_impl::db_get_i64(iterator, data.as_ptr() as *const crate::c_void, len) }
+pub fn db_get_i64(iterator: i32, data: &mut [u8], len: u32) -> i32 {
+    unsafe { database_impl::db_get_i64(iterator, data.as_mut_ptr() as *mut crate::c_void, len) }
 }
 
 #[inline]
@@ -285,4 +551,494 @@ pub fn db_idx64_next(iterator: i32, primary: *mut u64) -> i32 {
 #[inline]
 pub fn db_idx64_previous(iterator: i32, primary: *mut u64) -> i32 {
     unsafe { database_impl::db_idx64_previous(iterator, primary) }
+}
+
+// Stores a secondary index of type `u128`.
+#[inline]
+pub fn db_idx128_store(
+    scope: u64,
+    table: Name,
+    payer: Name,
+    id: u64,
+    secondary: &u128,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx128_store(
+            scope,
+            table.raw(),
+            payer.raw(),
+            id,
+            secondary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx128_update(iterator: i32, payer: Name, secondary: &u128) {
+    unsafe { database_impl::db_idx128_update(iterator, payer.raw(), secondary) }
+}
+
+#[inline]
+pub fn db_idx128_remove(iterator: i32) {
+    unsafe { database_impl::db_idx128_remove(iterator) }
+}
+
+#[inline]
+pub fn db_idx128_find_secondary(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &u128,
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx128_find_secondary(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx128_find_primary(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut u128,
+    primary: u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx128_find_primary(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx128_lowerbound(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut u128,
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx128_lowerbound(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx128_upperbound(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut u128,
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx128_upperbound(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx128_end(code: Name, scope: u64, table: Name) -> i32 {
+    unsafe { database_impl::db_idx128_end(code.raw(), scope, table.raw()) }
+}
+
+#[inline]
+pub fn db_idx128_next(iterator: i32, primary: &mut u64) -> i32 {
+    unsafe { database_impl::db_idx128_next(iterator, primary) }
+}
+
+#[inline]
+pub fn db_idx128_previous(iterator: i32, primary: &mut u64) -> i32 {
+    unsafe { database_impl::db_idx128_previous(iterator, primary) }
+}
+
+// Stores a secondary index of 256 bits, as two 128-bit words.
+#[inline]
+pub fn db_idx256_store(
+    scope: u64,
+    table: Name,
+    payer: Name,
+    id: u64,
+    data: &[u128; 2],
+) -> i32 {
+    unsafe {
+        database_impl::db_idx256_store(
+            scope,
+            table.raw(),
+            payer.raw(),
+            id,
+            data.as_ptr(),
+            2,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx256_update(iterator: i32, payer: Name, data: &[u128; 2]) {
+    unsafe { database_impl::db_idx256_update(iterator, payer.raw(), data.as_ptr(), 2) }
+}
+
+#[inline]
+pub fn db_idx256_remove(iterator: i32) {
+    unsafe { database_impl::db_idx256_remove(iterator) }
+}
+
+#[inline]
+pub fn db_idx256_find_secondary(
+    code: Name,
+    scope: u64,
+    table: Name,
+    data: &[u128; 2],
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx256_find_secondary(
+            code.raw(),
+            scope,
+            table.raw(),
+            data.as_ptr(),
+            2,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx256_find_primary(
+    code: Name,
+    scope: u64,
+    table: Name,
+    data: &mut [u128; 2],
+    primary: u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx256_find_primary(
+            code.raw(),
+            scope,
+            table.raw(),
+            data.as_mut_ptr(),
+            2,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx256_lowerbound(
+    code: Name,
+    scope: u64,
+    table: Name,
+    data: &mut [u128; 2],
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx256_lowerbound(
+            code.raw(),
+            scope,
+            table.raw(),
+            data.as_mut_ptr(),
+            2,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx256_upperbound(
+    code: Name,
+    scope: u64,
+    table: Name,
+    data: &mut [u128; 2],
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx256_upperbound(
+            code.raw(),
+            scope,
+            table.raw(),
+            data.as_mut_ptr(),
+            2,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx256_end(code: Name, scope: u64, table: Name) -> i32 {
+    unsafe { database_impl::db_idx256_end(code.raw(), scope, table.raw()) }
+}
+
+#[inline]
+pub fn db_idx256_next(iterator: i32, primary: &mut u64) -> i32 {
+    unsafe { database_impl::db_idx256_next(iterator, primary) }
+}
+
+#[inline]
+pub fn db_idx256_previous(iterator: i32, primary: &mut u64) -> i32 {
+    unsafe { database_impl::db_idx256_previous(iterator, primary) }
+}
+
+// Stores a secondary index of type `f64`.
+#[inline]
+pub fn db_idx_double_store(
+    scope: u64,
+    table: Name,
+    payer: Name,
+    id: u64,
+    secondary: &f64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_double_store(
+            scope,
+            table.raw(),
+            payer.raw(),
+            id,
+            secondary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_double_update(iterator: i32, payer: Name, secondary: &f64) {
+    unsafe { database_impl::db_idx_double_update(iterator, payer.raw(), secondary) }
+}
+
+#[inline]
+pub fn db_idx_double_remove(iterator: i32) {
+    unsafe { database_impl::db_idx_double_remove(iterator) }
+}
+
+#[inline]
+pub fn db_idx_double_find_secondary(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &f64,
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_double_find_secondary(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_double_find_primary(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut f64,
+    primary: u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_double_find_primary(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_double_lowerbound(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut f64,
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_double_lowerbound(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_double_upperbound(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut f64,
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_double_upperbound(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_double_end(code: Name, scope: u64, table: Name) -> i32 {
+    unsafe { database_impl::db_idx_double_end(code.raw(), scope, table.raw()) }
+}
+
+#[inline]
+pub fn db_idx_double_next(iterator: i32, primary: &mut u64) -> i32 {
+    unsafe { database_impl::db_idx_double_next(iterator, primary) }
+}
+
+#[inline]
+pub fn db_idx_double_previous(iterator: i32, primary: &mut u64) -> i32 {
+    unsafe { database_impl::db_idx_double_previous(iterator, primary) }
+}
+
+// Stores a secondary index of type `long double`, given as its IEEE
+// binary128 bits in little-endian order.
+#[inline]
+pub fn db_idx_long_double_store(
+    scope: u64,
+    table: Name,
+    payer: Name,
+    id: u64,
+    secondary: &[u8; 16],
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_long_double_store(
+            scope,
+            table.raw(),
+            payer.raw(),
+            id,
+            secondary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_long_double_update(iterator: i32, payer: Name, secondary: &[u8; 16]) {
+    unsafe { database_impl::db_idx_long_double_update(iterator, payer.raw(), secondary) }
+}
+
+#[inline]
+pub fn db_idx_long_double_remove(iterator: i32) {
+    unsafe { database_impl::db_idx_long_double_remove(iterator) }
+}
+
+#[inline]
+pub fn db_idx_long_double_find_secondary(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &[u8; 16],
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_long_double_find_secondary(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_long_double_find_primary(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut [u8; 16],
+    primary: u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_long_double_find_primary(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_long_double_lowerbound(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut [u8; 16],
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_long_double_lowerbound(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_long_double_upperbound(
+    code: Name,
+    scope: u64,
+    table: Name,
+    secondary: &mut [u8; 16],
+    primary: &mut u64,
+) -> i32 {
+    unsafe {
+        database_impl::db_idx_long_double_upperbound(
+            code.raw(),
+            scope,
+            table.raw(),
+            secondary,
+            primary,
+        )
+    }
+}
+
+#[inline]
+pub fn db_idx_long_double_end(code: Name, scope: u64, table: Name) -> i32 {
+    unsafe { database_impl::db_idx_long_double_end(code.raw(), scope, table.raw()) }
+}
+
+#[inline]
+pub fn db_idx_long_double_next(iterator: i32, primary: &mut u64) -> i32 {
+    unsafe { database_impl::db_idx_long_double_next(iterator, primary) }
+}
+
+#[inline]
+pub fn db_idx_long_double_previous(iterator: i32, primary: &mut u64) -> i32 {
+    unsafe { database_impl::db_idx_long_double_previous(iterator, primary) }
 }
\ No newline at end of file
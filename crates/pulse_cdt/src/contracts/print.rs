@@ -0,0 +1,301 @@
+use alloc::string::String;
+use core::ffi::CStr;
+
+use crate::core::{
+    Asset, ExtendedAsset, ExtendedSymbol, FixedBytes, Name, Symbol, SymbolCode, TimePoint,
+};
+
+#[cfg(not(feature = "testing"))]
+mod print_impl {
+    extern "C" {
+        #[link_name = "prints"]
+        pub fn prints(cstr: *const crate::c_char);
+
+        #[link_name = "prints_l"]
+        pub fn prints_l(cstr: *const crate::c_char, len: u32);
+
+        #[link_name = "printi"]
+        pub fn printi(value: i64);
+
+        #[link_name = "printui"]
+        pub fn printui(value: u64);
+
+        #[link_name = "printn"]
+        pub fn printn(name: u64);
+
+        #[link_name = "printhex"]
+        pub fn printhex(data: *const crate::c_void, datalen: u32);
+
+        #[link_name = "printdf"]
+        pub fn printdf(value: f64);
+    }
+}
+
+#[cfg(feature = "testing")]
+use crate::testing::host as print_impl;
+
+/// Prints a null-terminated string to the console.
+#[inline]
+pub fn prints(cstr: &CStr) {
+    unsafe { print_impl::prints(cstr.as_ptr()) }
+}
+
+/// Prints a string slice to the console.
+#[inline]
+pub fn prints_l(s: &str) {
+    print_bytes(s.as_bytes());
+}
+
+#[inline]
+pub fn printi(value: i64) {
+    unsafe { print_impl::printi(value) }
+}
+
+#[inline]
+pub fn printui(value: u64) {
+    unsafe { print_impl::printui(value) }
+}
+
+/// Prints `name` in its string form, e.g. `pulse.token`.
+#[inline]
+pub fn printn(name: Name) {
+    unsafe { print_impl::printn(name.raw()) }
+}
+
+/// Prints `data` as lowercase hex.
+#[inline]
+pub fn printhex(data: &[u8]) {
+    unsafe { print_impl::printhex(data.as_ptr() as *const crate::c_void, data.len() as u32) }
+}
+
+#[inline]
+pub fn printdf(value: f64) {
+    unsafe { print_impl::printdf(value) }
+}
+
+// Callers only hand over ASCII or slices of valid UTF-8.
+#[inline]
+fn print_bytes(bytes: &[u8]) {
+    unsafe { print_impl::prints_l(bytes.as_ptr() as *const crate::c_char, bytes.len() as u32) }
+}
+
+/// Writes `value` into `buf` as decimal digits, zero-padded on the left to
+/// the length of `buf`.
+fn write_padded(buf: &mut [u8], mut value: u64) {
+    for digit in buf.iter_mut().rev() {
+        *digit = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+}
+
+/// A value that can be written to the console, used by [`print!`](crate::print)
+/// and [`println!`](crate::println).
+///
+/// Implementations go straight to the print intrinsics, so printing does not
+/// pull `core::fmt` into the contract.
+pub trait Printable {
+    fn print(&self);
+}
+
+impl<T: Printable + ?Sized> Printable for &T {
+    #[inline]
+    fn print(&self) {
+        (**self).print();
+    }
+}
+
+impl Printable for str {
+    #[inline]
+    fn print(&self) {
+        prints_l(self);
+    }
+}
+
+impl Printable for String {
+    #[inline]
+    fn print(&self) {
+        prints_l(self);
+    }
+}
+
+impl Printable for CStr {
+    #[inline]
+    fn print(&self) {
+        prints(self);
+    }
+}
+
+impl Printable for char {
+    #[inline]
+    fn print(&self) {
+        let mut buf = [0_u8; 4];
+        prints_l(self.encode_utf8(&mut buf));
+    }
+}
+
+impl Printable for bool {
+    #[inline]
+    fn print(&self) {
+        prints_l(if *self { "true" } else { "false" });
+    }
+}
+
+macro_rules! impl_printable_int {
+    ($print:ident as $wide:ty: $($ty:ty),*) => {
+        $(
+            impl Printable for $ty {
+                #[inline]
+                fn print(&self) {
+                    $print(*self as $wide);
+                }
+            }
+        )*
+    };
+}
+
+impl_printable_int!(printui as u64: u8, u16, u32, u64, usize);
+impl_printable_int!(printi as i64: i8, i16, i32, i64, isize);
+
+impl Printable for f32 {
+    #[inline]
+    fn print(&self) {
+        printdf(f64::from(*self));
+    }
+}
+
+impl Printable for f64 {
+    #[inline]
+    fn print(&self) {
+        printdf(*self);
+    }
+}
+
+impl Printable for Name {
+    #[inline]
+    fn print(&self) {
+        printn(*self);
+    }
+}
+
+impl Printable for SymbolCode {
+    fn print(&self) {
+        self.with_str(prints_l);
+    }
+}
+
+/// Prints as `<precision>,<code>`, e.g. `4,PULSE`.
+impl Printable for Symbol {
+    fn print(&self) {
+        self.write_parts(prints_l);
+    }
+}
+
+/// Prints the amount with the symbol's precision followed by its code,
+/// e.g. `-1.0000 PULSE`.
+impl Printable for Asset {
+    fn print(&self) {
+        self.write_parts(prints_l);
+    }
+}
+
+/// Prints as `<precision>,<code>@<contract>`, e.g. `4,PULSE@pulse.token`.
+impl Printable for ExtendedSymbol {
+    fn print(&self) {
+        self.write_parts(prints_l);
+    }
+}
+
+/// Prints as `<amount> <code>@<contract>`, e.g. `1.0000 PULSE@pulse.token`.
+impl Printable for ExtendedAsset {
+    fn print(&self) {
+        self.write_parts(prints_l);
+    }
+}
+
+/// Prints as ISO 8601 in UTC with millisecond precision,
+/// e.g. `2020-01-01T00:00:00.000`.
+impl Printable for TimePoint {
+    fn print(&self) {
+        let micros = self.time_since_epoch().count();
+        let days = micros.div_euclid(86_400_000_000);
+        let millis_of_day = (micros.rem_euclid(86_400_000_000) / 1_000) as u64;
+
+        // Civil date from days since 1970-01-01 (proleptic Gregorian).
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        let mut buf = *b"0000-00-00T00:00:00.000";
+        write_padded(&mut buf[0..4], year as u64);
+        write_padded(&mut buf[5..7], month as u64);
+        write_padded(&mut buf[8..10], day as u64);
+        write_padded(&mut buf[11..13], millis_of_day / 3_600_000);
+        write_padded(&mut buf[14..16], millis_of_day / 60_000 % 60);
+        write_padded(&mut buf[17..19], millis_of_day / 1_000 % 60);
+        write_padded(&mut buf[20..23], millis_of_day % 1_000);
+        print_bytes(&buf);
+    }
+}
+
+/// Prints the bytes as lowercase hex, covering the checksum types.
+impl<const N: usize> Printable for FixedBytes<N> {
+    #[inline]
+    fn print(&self) {
+        printhex(&self.0);
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use alloc::vec;
+    use std::string::String;
+
+    use crate::{
+        contracts::{Action, PermissionLevel},
+        core::{Asset, Checksum256, FixedBytes, Microseconds, Symbol, SymbolCode, TimePoint},
+        name,
+        testing::TestChain,
+    };
+
+    fn apply(_receiver: u64, _code: u64, _action: u64) {
+        let symbol = Symbol::new_with_code(4, "PULSE".parse::<SymbolCode>().unwrap());
+        let hash: Checksum256 = FixedBytes([0xab; 32]);
+        crate::print!("{} sent {} ", name!("alice"), Asset::new(-12_345, symbol));
+        crate::println!(
+            "({}) at {}",
+            symbol,
+            TimePoint::new(Microseconds::new(1_577_836_800_123_000))
+        );
+        crate::print!("{{{}}} {} {} {} {}", -7_i32, 42_u8, true, 'x', &hash);
+    }
+
+    #[test]
+    fn console_is_captured_per_action() {
+        let mut chain = TestChain::new();
+        chain.deploy(name!("printer"), apply);
+        chain.create_account(name!("alice"));
+
+        let trace = chain
+            .push_action(Action::new(
+                vec![PermissionLevel::new(name!("alice"), name!("active"))],
+                name!("printer"),
+                name!("hi"),
+                vec![],
+            ))
+            .unwrap();
+
+        let mut expected = String::from(
+            "alice sent -1.2345 PULSE (4,PULSE) at 2020-01-01T00:00:00.123\n{-7} 42 true x ",
+        );
+        expected.push_str(&"ab".repeat(32));
+        assert_eq!(trace.action_traces.len(), 1);
+        assert_eq!(trace.action_traces[0].receiver, name!("printer"));
+        assert_eq!(trace.action_traces[0].console, expected);
+    }
+}
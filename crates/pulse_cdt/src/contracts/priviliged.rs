@@ -1,5 +1,10 @@
-use crate::core::Name;
+use alloc::{vec, vec::Vec};
+use pulse_proc_macro::{NumBytes, Read, Write};
+use pulse_serialization::{Read as _, Write as _};
 
+use crate::core::{Checksum256, Name, ProducerAuthority, ProducerKey};
+
+#[cfg(not(feature = "testing"))]
 mod priviliged_impl {
     extern "C" {
         #[link_name = "is_privileged"]
@@ -18,9 +23,40 @@ mod priviliged_impl {
 
         #[link_name = "set_resource_limits"]
         pub fn set_resource_limits(account: u64, ram_bytes: i64, net_weight: i64, cpu_weight: i64);
+
+        #[link_name = "get_active_producers"]
+        pub fn get_active_producers(producers: *mut u64, datalen: u32) -> u32;
+
+        #[link_name = "set_proposed_producers"]
+        pub fn set_proposed_producers(
+            producer_data: *mut crate::c_void,
+            producer_data_size: u32,
+        ) -> i64;
+
+        #[link_name = "set_proposed_producers_ex"]
+        pub fn set_proposed_producers_ex(
+            producer_data_format: u64,
+            producer_data: *mut crate::c_void,
+            producer_data_size: u32,
+        ) -> i64;
+
+        #[link_name = "get_blockchain_parameters_packed"]
+        pub fn get_blockchain_parameters_packed(data: *mut crate::c_void, datalen: u32) -> u32;
+
+        #[link_name = "set_blockchain_parameters_packed"]
+        pub fn set_blockchain_parameters_packed(data: *mut crate::c_void, datalen: u32);
+
+        #[link_name = "is_feature_activated"]
+        pub fn is_feature_activated(feature_digest: *const crate::c_void) -> bool;
+
+        #[link_name = "preactivate_feature"]
+        pub fn preactivate_feature(feature_digest: *const crate::c_void);
     }
 }
 
+#[cfg(feature = "testing")]
+use crate::testing::host as priviliged_impl;
+
 /// Checks whether the specified account has privileged status.
 ///
 /// # Parameters
@@ -89,3 +125,286 @@ pub fn set_resource_limits(account: Name, ram_bytes: i64, net_weight: i64, cpu_w
         priviliged_impl::set_resource_limits(account.raw(), ram_bytes, net_weight, cpu_weight)
     }
 }
+
+/// Retrieves the producers of the active schedule, in order.
+#[inline]
+pub fn get_active_producers() -> Vec<Name> {
+    let size = unsafe { priviliged_impl::get_active_producers(core::ptr::null_mut(), 0) };
+    let mut producers = vec![0_u64; size as usize / core::mem::size_of::<u64>()];
+    unsafe {
+        priviliged_impl::get_active_producers(
+            producers.as_mut_ptr(),
+            (producers.len() * core::mem::size_of::<u64>()) as u32,
+        )
+    };
+    producers.into_iter().map(Name::new).collect()
+}
+
+/// The `producer_data_format` of `set_proposed_producers_ex` for a packed
+/// `Vec<ProducerAuthority>`; format 0 is the same as `set_proposed_producers`.
+const PRODUCER_AUTHORITIES: u64 = 1;
+
+/// Proposes a new producer schedule, which becomes active once the block
+/// proposing it is irreversible.
+///
+/// # Parameters
+///
+/// - `producers`: The producers, in the order they take turns.
+///
+/// # Returns
+///
+/// - The version the schedule will have, or `None` if it wasn't proposed
+///   because it matches the active one.
+#[inline]
+pub fn set_proposed_producers(producers: &[ProducerKey]) -> Option<u64> {
+    let packed = producers.to_vec().pack().expect("failed to pack producers");
+    let version = unsafe {
+        priviliged_impl::set_proposed_producers(packed.as_ptr() as *mut _, packed.len() as u32)
+    };
+    u64::try_from(version).ok()
+}
+
+/// Like [`set_proposed_producers`], for producers signing blocks with a
+/// weighted set of keys.
+#[inline]
+pub fn set_proposed_producers_ex(producers: &[ProducerAuthority]) -> Option<u64> {
+    let packed = producers.to_vec().pack().expect("failed to pack producers");
+    let version = unsafe {
+        priviliged_impl::set_proposed_producers_ex(
+            PRODUCER_AUTHORITIES,
+            packed.as_ptr() as *mut _,
+            packed.len() as u32,
+        )
+    };
+    u64::try_from(version).ok()
+}
+
+/// Consensus limits on blocks and transactions, set by privileged contracts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, NumBytes, Read, Write)]
+#[pulse(crate_path = "pulse_serialization", abi_name = "blockchain_parameters")]
+#[cfg_attr(feature = "serde", pulse(serde))]
+pub struct BlockchainParameters {
+    /// The maximum net usage in instructions for a block.
+    pub max_block_net_usage: u64,
+    /// The target percent (1% == 100, 100% == 10,000) of maximum net usage;
+    /// exceeding this triggers congestion handling.
+    pub target_block_net_usage_pct: u32,
+    /// The maximum objectively measured net usage that the chain will allow
+    /// regardless of account limits.
+    pub max_transaction_net_usage: u32,
+    /// The base amount of net usage billed for a transaction to cover
+    /// incidentals.
+    pub base_per_transaction_net_usage: u32,
+    /// The amount of net usage leeway available whilst executing a
+    /// transaction (still checks against new limits without leeway at the
+    /// end of the transaction).
+    pub net_usage_leeway: u32,
+    /// The numerator for the discount on net usage of context-free data.
+    pub context_free_discount_net_usage_num: u32,
+    /// The denominator for the discount on net usage of context-free data.
+    pub context_free_discount_net_usage_den: u32,
+    /// The maximum billable cpu usage (in microseconds) for a block.
+    pub max_block_cpu_usage: u32,
+    /// The target percent (1% == 100, 100% == 10,000) of maximum cpu usage;
+    /// exceeding this triggers congestion handling.
+    pub target_block_cpu_usage_pct: u32,
+    /// The maximum billable cpu usage (in microseconds) that the chain will
+    /// allow regardless of account limits.
+    pub max_transaction_cpu_usage: u32,
+    /// The minimum billable cpu usage (in microseconds) that the chain
+    /// requires.
+    pub min_transaction_cpu_usage: u32,
+    /// Maximum lifetime of a transaction, in seconds.
+    pub max_transaction_lifetime: u32,
+    /// The number of seconds after the time a deferred transaction can first
+    /// execute until it expires.
+    pub deferred_trx_expiration_window: u32,
+    /// The maximum number of seconds that can be imposed as a delay
+    /// requirement by authorization checks.
+    pub max_transaction_delay: u32,
+    /// Maximum size of an inline action, in bytes.
+    pub max_inline_action_size: u32,
+    /// Maximum depth of inline actions.
+    pub max_inline_action_depth: u16,
+    /// Maximum authority depth.
+    pub max_authority_depth: u16,
+}
+
+/// Retrieves the current blockchain parameters.
+#[inline]
+pub fn get_blockchain_parameters() -> BlockchainParameters {
+    let size =
+        unsafe { priviliged_impl::get_blockchain_parameters_packed(core::ptr::null_mut(), 0) };
+    let mut packed = vec![0_u8; size as usize];
+    unsafe {
+        priviliged_impl::get_blockchain_parameters_packed(packed.as_mut_ptr() as *mut _, size)
+    };
+    BlockchainParameters::unpack(&packed).expect("failed to read blockchain parameters")
+}
+
+/// Sets the blockchain parameters.
+///
+/// # Parameters
+///
+/// - `params`: The new parameters, all of which are replaced.
+#[inline]
+pub fn set_blockchain_parameters(params: &BlockchainParameters) {
+    let packed = params.pack().expect("failed to pack blockchain parameters");
+    unsafe {
+        priviliged_impl::set_blockchain_parameters_packed(
+            packed.as_ptr() as *mut _,
+            packed.len() as u32,
+        )
+    }
+}
+
+/// Checks whether a protocol feature is active.
+///
+/// # Parameters
+///
+/// - `feature_digest`: The digest of the feature's specification.
+#[inline]
+pub fn is_feature_activated(feature_digest: &Checksum256) -> bool {
+    unsafe { priviliged_impl::is_feature_activated(feature_digest.0.as_ptr() as *const _) }
+}
+
+/// Pre-activates a protocol feature, which the next block then activates.
+/// Fails if the feature is unknown, already pre-activated or active.
+///
+/// # Parameters
+///
+/// - `feature_digest`: The digest of the feature's specification.
+#[inline]
+pub fn preactivate_feature(feature_digest: &Checksum256) {
+    unsafe { priviliged_impl::preactivate_feature(feature_digest.0.as_ptr() as *const _) }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::{
+        contracts::{Action, PermissionLevel},
+        core::{check, PublicKey},
+        name,
+        testing::TestChain,
+    };
+
+    fn producer(name: Name) -> ProducerKey {
+        ProducerKey {
+            producer_name: name,
+            block_signing_key: PublicKey::default(),
+        }
+    }
+
+    #[test]
+    fn producer_schedule() {
+        let mut chain = TestChain::new();
+        chain.create_account(name!("alice"));
+        chain.create_account(name!("bob"));
+        assert_eq!(get_active_producers(), vec![name!("pulse")]);
+
+        let producers = [producer(name!("alice")), producer(name!("bob"))];
+        assert_eq!(set_proposed_producers(&producers), Some(1));
+        assert_eq!(chain.proposed_producers().map(|p| p.len()), Some(2));
+        assert!(chain.activate_proposed_producers());
+        assert_eq!(get_active_producers(), vec![name!("alice"), name!("bob")]);
+
+        // Proposing the active schedule again is a no-op.
+        let authorities: Vec<_> = producers.into_iter().map(ProducerAuthority::from).collect();
+        assert_eq!(set_proposed_producers_ex(&authorities), None);
+        assert_eq!(set_proposed_producers_ex(&authorities[..1]), Some(2));
+    }
+
+    #[test]
+    fn large_producer_schedule() {
+        let mut chain = TestChain::new();
+        let names: Vec<_> = (1..=30)
+            .map(|i| Name::new(name!("prod").raw() + i))
+            .collect();
+        for &name in &names {
+            chain.create_account(name);
+        }
+        let producers: Vec<_> = names.iter().copied().map(producer).collect();
+        assert_eq!(set_proposed_producers(&producers), Some(1));
+        assert!(chain.activate_proposed_producers());
+        assert_eq!(get_active_producers(), names);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate producer name in producer schedule")]
+    fn duplicate_producers() {
+        let mut chain = TestChain::new();
+        chain.create_account(name!("alice"));
+        set_proposed_producers(&[producer(name!("alice")), producer(name!("alice"))]);
+    }
+
+    #[test]
+    fn blockchain_parameters() {
+        let chain = TestChain::new();
+        let mut params = get_blockchain_parameters();
+        assert_eq!(params.max_inline_action_depth, 4);
+
+        params.max_transaction_lifetime = 600;
+        set_blockchain_parameters(&params);
+        assert_eq!(chain.blockchain_parameters(), params);
+        assert_eq!(get_blockchain_parameters(), params);
+    }
+
+    #[test]
+    fn feature_activation() {
+        let mut chain = TestChain::new();
+        let feature = Checksum256::new([1; 32]);
+        assert!(!is_feature_activated(&feature));
+
+        preactivate_feature(&feature);
+        assert_eq!(chain.preactivated_features(), vec![feature]);
+        assert!(!is_feature_activated(&feature));
+        chain.activate_preactivated_features();
+        assert!(is_feature_activated(&feature));
+
+        chain.set_feature_activated(feature, false);
+        assert!(!chain.is_feature_activated(feature));
+    }
+
+    #[test]
+    #[should_panic(expected = "protocol feature is already activated")]
+    fn preactivate_active_feature() {
+        let mut chain = TestChain::new();
+        let feature = Checksum256::new([1; 32]);
+        chain.set_feature_activated(feature, true);
+        preactivate_feature(&feature);
+    }
+
+    fn apply_then_fail(_receiver: u64, _code: u64, _action: u64) {
+        set_privileged(name!("alice"), true);
+        set_resource_limits(name!("alice"), 1024, 1, 1);
+        set_proposed_producers(&[producer(name!("alice"))]);
+        let mut params = get_blockchain_parameters();
+        params.max_transaction_lifetime = 600;
+        set_blockchain_parameters(&params);
+        preactivate_feature(&Checksum256::new([1; 32]));
+        check(false, "rolled back");
+    }
+
+    #[test]
+    fn failed_transactions_keep_no_changes() {
+        let mut chain = TestChain::new();
+        chain.create_account(name!("alice"));
+        chain.deploy(name!("admin"), apply_then_fail);
+        let params = chain.blockchain_parameters();
+        let limits = get_resource_limits(name!("alice"));
+
+        let action = Action::new(
+            vec![PermissionLevel::new(name!("admin"), name!("active"))],
+            name!("admin"),
+            name!("run"),
+            vec![],
+        );
+        assert!(chain.push_action(action).is_err());
+        assert!(!is_privileged(name!("alice")));
+        assert_eq!(get_resource_limits(name!("alice")), limits);
+        assert_eq!(chain.proposed_producers(), None);
+        assert_eq!(chain.blockchain_parameters(), params);
+        assert!(chain.preactivated_features().is_empty());
+    }
+}
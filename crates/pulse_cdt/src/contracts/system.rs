@@ -1,5 +1,6 @@
 use crate::core::{BlockTimestamp, Microseconds, TimePoint};
 
+#[cfg(not(feature = "testing"))]
 mod system_impl {
     extern "C" {
         #[link_name = "current_time"]
@@ -7,6 +8,9 @@ mod system_impl {
     }
 }
 
+#[cfg(feature = "testing")]
+use crate::testing::host as system_impl;
+
 #[inline]
 pub fn current_time() -> u64 {
     unsafe { system_impl::current_time() }
@@ -1,11 +1,14 @@
-use alloc::{collections::btree_set::BTreeSet, vec::Vec};
-use pulse_serialization::Write;
+use alloc::{collections::btree_set::BTreeSet, vec, vec::Vec};
+use pulse_serialization::{Read, Write};
 
 use crate::{
-    contracts::PermissionLevel,
-    core::{PublicKey, Transaction},
+    contracts::{Action, PermissionLevel},
+    core::{
+        Microseconds, Name, PublicKey, TimePoint, TimePointSec, Transaction, TransactionHeader,
+    },
 };
 
+#[cfg(not(feature = "testing"))]
 mod transaction_impl {
     extern "C" {
         #[link_name = "check_transaction_authorization"]
@@ -17,9 +20,53 @@ mod transaction_impl {
             perms_msg: *mut crate::c_void,
             perms_len: usize,
         ) -> u32;
+
+        #[link_name = "read_transaction"]
+        pub fn read_transaction(buffer: *mut crate::c_void, size: usize) -> usize;
+
+        #[link_name = "transaction_size"]
+        pub fn transaction_size() -> usize;
+
+        #[link_name = "tapos_block_num"]
+        pub fn tapos_block_num() -> i32;
+
+        #[link_name = "tapos_block_prefix"]
+        pub fn tapos_block_prefix() -> i32;
+
+        #[link_name = "expiration"]
+        pub fn expiration() -> u32;
+
+        #[link_name = "get_action"]
+        pub fn get_action(
+            action_type: u32,
+            index: u32,
+            buffer: *mut crate::c_void,
+            size: usize,
+        ) -> i32;
+
+        #[link_name = "get_context_free_data"]
+        pub fn get_context_free_data(index: u32, buffer: *mut crate::c_void, size: usize) -> i32;
+
+        #[link_name = "publication_time"]
+        pub fn publication_time() -> u64;
+
+        #[link_name = "send_deferred"]
+        pub fn send_deferred(
+            sender_id: *const u128,
+            payer: u64,
+            data: *mut crate::c_void,
+            len: usize,
+            replace_existing: u32,
+        );
+
+        #[link_name = "cancel_deferred"]
+        pub fn cancel_deferred(sender_id: *const u128) -> i32;
     }
 }
 
+#[cfg(feature = "testing")]
+use crate::testing::host as transaction_impl;
+
 #[inline]
 pub fn check_transaction_authorization(
     transaction: &Transaction,
@@ -51,3 +98,357 @@ pub fn check_transaction_authorization(
         )
     }
 }
+
+/// The packed transaction the current action is part of.
+fn read_packed_transaction() -> Vec<u8> {
+    let mut buffer = vec![0_u8; transaction_size()];
+    unsafe { transaction_impl::read_transaction(buffer.as_mut_ptr() as *mut _, buffer.len()) };
+    buffer
+}
+
+/// The transaction the current action is part of.
+#[inline]
+pub fn read_transaction() -> Transaction {
+    Transaction::unpack(read_packed_transaction()).expect("failed to read transaction")
+}
+
+/// The header of the transaction the current action is part of, without
+/// reading its actions.
+#[inline]
+pub fn read_transaction_header() -> TransactionHeader {
+    TransactionHeader::unpack(read_packed_transaction()).expect("failed to read transaction header")
+}
+
+/// The size of the packed transaction the current action is part of.
+#[inline]
+pub fn transaction_size() -> usize {
+    unsafe { transaction_impl::transaction_size() }
+}
+
+/// The `ref_block_num` of the current transaction.
+#[inline]
+pub fn tapos_block_num() -> u16 {
+    unsafe { transaction_impl::tapos_block_num() as u16 }
+}
+
+/// The `ref_block_prefix` of the current transaction.
+#[inline]
+pub fn tapos_block_prefix() -> u32 {
+    unsafe { transaction_impl::tapos_block_prefix() as u32 }
+}
+
+/// When the current transaction expires.
+#[inline]
+pub fn expiration() -> TimePointSec {
+    TimePointSec::new(unsafe { transaction_impl::expiration() })
+}
+
+/// When the current transaction was first included in a block.
+#[inline]
+pub fn publication_time() -> TimePoint {
+    TimePoint::new(Microseconds::new(
+        unsafe { transaction_impl::publication_time() } as i64,
+    ))
+}
+
+/// Which of the transaction's action lists `get_action` reads from.
+const CONTEXT_FREE_ACTION: u32 = 0;
+const ACTION: u32 = 1;
+
+fn read_action(action_type: u32, index: u32) -> Option<Action> {
+    let size =
+        unsafe { transaction_impl::get_action(action_type, index, core::ptr::null_mut(), 0) };
+    if size < 0 {
+        return None;
+    }
+    let mut buffer = vec![0_u8; size as usize];
+    unsafe {
+        transaction_impl::get_action(
+            action_type,
+            index,
+            buffer.as_mut_ptr() as *mut _,
+            buffer.len(),
+        )
+    };
+    Some(Action::unpack(&buffer).expect("failed to read action"))
+}
+
+/// The `index`th action of the current transaction, as it was pushed;
+/// `None` past the last one. Inline actions are not included.
+#[inline]
+pub fn get_action(index: u32) -> Option<Action> {
+    read_action(ACTION, index)
+}
+
+/// The `index`th context-free action of the current transaction; `None`
+/// past the last one.
+#[inline]
+pub fn get_context_free_action(index: u32) -> Option<Action> {
+    read_action(CONTEXT_FREE_ACTION, index)
+}
+
+/// The `index`th piece of context-free data of the current transaction;
+/// `None` past the last one.
+pub fn get_context_free_data(index: u32) -> Option<Vec<u8>> {
+    let size = unsafe { transaction_impl::get_context_free_data(index, core::ptr::null_mut(), 0) };
+    if size < 0 {
+        return None;
+    }
+    let mut data = vec![0_u8; size as usize];
+    unsafe {
+        transaction_impl::get_context_free_data(index, data.as_mut_ptr() as *mut _, data.len())
+    };
+    Some(data)
+}
+
+/// Schedules `transaction` to run after its `delay_sec`, billing its RAM to
+/// `payer`. The current contract can later cancel it with `sender_id`;
+/// if a transaction with that id is still pending it is replaced when
+/// `replace_existing` is set, otherwise this aborts.
+///
+/// Should it fail, the contract is sent an `onerror` action from `pulse`
+/// holding an [`OnError`](crate::core::OnError).
+pub fn send_deferred(
+    sender_id: u128,
+    payer: Name,
+    transaction: &Transaction,
+    replace_existing: bool,
+) {
+    let packed = transaction.pack().expect("failed to pack transaction");
+    unsafe {
+        transaction_impl::send_deferred(
+            &sender_id,
+            payer.raw(),
+            packed.as_ptr() as *mut _,
+            packed.len(),
+            replace_existing as u32,
+        )
+    }
+}
+
+/// Cancels the deferred transaction the current contract sent with
+/// `sender_id`, returning whether there was one.
+#[inline]
+pub fn cancel_deferred(sender_id: u128) -> bool {
+    unsafe { transaction_impl::cancel_deferred(&sender_id) == 1 }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::{
+        action, contract,
+        contracts::{prints_l, InlineAction},
+        core::{check, Name, OnError},
+        name, on_notify,
+        testing::TestChain,
+        VarUint32,
+    };
+
+    #[derive(Default)]
+    struct Escrow;
+
+    #[contract]
+    impl Escrow {
+        #[action]
+        fn pay() {
+            let next = get_action(1).map(|action| action.name);
+            check(
+                next == Some(name!("confirm")),
+                "pay must be followed by confirm",
+            );
+        }
+
+        #[action]
+        fn confirm() {}
+
+        #[action]
+        fn expires() -> TimePointSec {
+            check(
+                read_transaction_header().expiration == expiration(),
+                "header mismatch",
+            );
+            expiration()
+        }
+
+        #[action]
+        fn proof() -> Vec<u8> {
+            check(
+                get_context_free_action(1).is_none(),
+                "only one context-free action",
+            );
+            get_context_free_data(0).unwrap_or_default()
+        }
+    }
+
+    fn call(action: Name) -> Action {
+        Action::new(
+            vec![PermissionLevel::new(name!("escrow"), name!("active"))],
+            name!("escrow"),
+            action,
+            vec![],
+        )
+    }
+
+    #[test]
+    fn actions_see_their_siblings() {
+        let mut chain = TestChain::new();
+        chain.deploy(name!("escrow"), apply);
+
+        chain
+            .push_transaction(vec![call(name!("pay")), call(name!("confirm"))])
+            .unwrap();
+        let err = chain.push_action(call(name!("pay"))).unwrap_err();
+        assert_eq!(err.message, "pay must be followed by confirm");
+    }
+
+    #[test]
+    fn header_and_context_free_data() {
+        let mut chain = TestChain::new();
+        chain.deploy(name!("escrow"), apply);
+
+        let trace = chain.push_action(call(name!("expires"))).unwrap();
+        assert_eq!(
+            trace.return_value::<TimePointSec>(name!("escrow"), name!("expires")),
+            Some(TimePointSec::from(chain.now()) + 30)
+        );
+
+        let mut transaction = Transaction {
+            header: TransactionHeader {
+                expiration: TimePointSec::new(0),
+                ref_block_num: 7,
+                ref_block_prefix: 0xdead_beef,
+                max_net_usage_words: VarUint32(0),
+                max_cpu_usage: 0,
+                delay_sec: VarUint32(0),
+            },
+            context_free_actions: vec![Action::new(
+                vec![],
+                name!("escrow"),
+                name!("proof"),
+                vec![],
+            )],
+            actions: vec![call(name!("confirm"))],
+            transaction_extensions: vec![],
+        };
+        let trace = chain
+            .push_full_transaction(transaction.clone(), vec![vec![1, 2, 3]])
+            .unwrap();
+        assert_eq!(
+            trace.return_value::<Vec<u8>>(name!("escrow"), name!("proof")),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(trace.action_traces.len(), 2);
+
+        // Only context-free actions may read the context-free data.
+        let err = chain.push_action(call(name!("proof"))).unwrap_err();
+        assert_eq!(
+            err.message,
+            "this API may only be called from context_free apply"
+        );
+
+        transaction
+            .context_free_actions
+            .push(transaction.context_free_actions[0].clone());
+        let err = chain
+            .push_full_transaction(transaction, vec![])
+            .unwrap_err();
+        assert_eq!(err.message, "only one context-free action");
+    }
+
+    // `#[contract]` defines `apply` and `actions`, so each contract needs a
+    // module of its own.
+    mod deferred {
+        use super::*;
+
+        #[derive(Default)]
+        struct Scheduler;
+
+        #[contract]
+        impl Scheduler {
+            #[action]
+            fn schedule(id: u64, fail: bool) {
+                let work = actions::work(fail).to_action(
+                    get_self(),
+                    vec![PermissionLevel::new(get_self(), name!("active"))],
+                );
+                let transaction = Transaction {
+                    header: read_transaction_header(),
+                    context_free_actions: vec![],
+                    actions: vec![work],
+                    transaction_extensions: vec![],
+                };
+                transaction.send_deferred(u128::from(id), get_self(), false);
+            }
+
+            #[action]
+            fn work(fail: bool) {
+                check(!fail, "work failed");
+            }
+
+            #[action]
+            fn cancel(id: u64) {
+                check(cancel_deferred(u128::from(id)), "nothing to cancel");
+            }
+
+            #[on_notify("pulse::onerror")]
+            fn on_error(error: OnError) {
+                let failed = error.unpack_sent_trx().actions[0].name;
+                prints_l(&alloc::format!(
+                    "{} failed {}",
+                    error.sender_id,
+                    failed.to_string()
+                ));
+            }
+        }
+
+        #[test]
+        fn deferred_transactions() {
+            let mut chain = TestChain::new();
+            chain.deploy(name!("scheduler"), apply);
+            let send = |action: InlineAction| {
+                action.to_action(
+                    name!("scheduler"),
+                    vec![PermissionLevel::new(name!("scheduler"), name!("active"))],
+                )
+            };
+
+            chain
+                .push_action(send(actions::schedule(1, false)))
+                .unwrap();
+            chain.push_action(send(actions::schedule(2, true))).unwrap();
+            chain
+                .push_action(send(actions::schedule(3, false)))
+                .unwrap();
+            let err = chain
+                .push_action(send(actions::schedule(3, false)))
+                .unwrap_err();
+            assert_eq!(
+                err.message,
+                "deferred transaction with the same sender_id and payer already exists"
+            );
+            chain.push_action(send(actions::cancel(3))).unwrap();
+            assert!(chain.push_action(send(actions::cancel(3))).is_err());
+            assert_eq!(
+                chain.deferred_transactions(),
+                vec![(name!("scheduler"), 1), (name!("scheduler"), 2)]
+            );
+
+            let traces = chain.run_deferred();
+            assert!(traces[0].result.is_ok() && traces[0].onerror.is_none());
+            assert_eq!(
+                traces[1].result.as_ref().unwrap_err().message,
+                "work failed"
+            );
+            let onerror = traces[1].onerror.as_ref().unwrap().as_ref().unwrap();
+            assert_eq!(onerror.console(), "2 failed work");
+            assert!(chain.deferred_transactions().is_empty());
+
+            // Only `pulse` may send `onerror`.
+            let forged = Action::new(vec![], name!("scheduler"), name!("onerror"), vec![]);
+            assert!(chain.push_action(forged).is_err());
+        }
+    }
+}
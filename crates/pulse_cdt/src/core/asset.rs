@@ -1,13 +1,68 @@
-use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
-
+use alloc::vec::Vec;
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use pulse_bytes::ParseSymbolCodeError;
 use pulse_proc_macro::{NumBytes, Read, Write};
 
-use super::{check::check, symbol::Symbol};
+use super::{
+    check::check,
+    name::Name,
+    symbol::{ExtendedSymbol, Symbol, MAX_PRECISION},
+    symbol_code::SymbolCode,
+};
 
 pub const MAX_ASSET_AMOUNT: i64 = (1i64 << 62) - 1;
 
+/// How a division that isn't exact is rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward negative infinity.
+    Floor,
+    /// Toward positive infinity.
+    Ceil,
+    /// To the nearest integer, ties going to the even one.
+    HalfEven,
+}
+
+impl Rounding {
+    /// `numerator / denominator` rounded this way, `None` if `denominator`
+    /// is zero or the quotient overflows.
+    fn div(self, numerator: i128, denominator: i128) -> Option<i128> {
+        let quotient = numerator.checked_div(denominator)?;
+        let remainder = numerator % denominator;
+        if remainder == 0 {
+            return Some(quotient);
+        }
+        // The direction away from the truncated quotient, toward the exact one.
+        let away = if (remainder < 0) == (denominator < 0) {
+            1
+        } else {
+            -1
+        };
+        let round_away = match self {
+            Self::Floor => away < 0,
+            Self::Ceil => away > 0,
+            Self::HalfEven => match (remainder.unsigned_abs() * 2).cmp(&denominator.unsigned_abs())
+            {
+                core::cmp::Ordering::Less => false,
+                core::cmp::Ordering::Greater => true,
+                core::cmp::Ordering::Equal => quotient % 2 != 0,
+            },
+        };
+        Some(if round_away {
+            quotient + away
+        } else {
+            quotient
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, NumBytes, Read, Write, Default)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi = "asset")]
 pub struct Asset {
     /// The amount of the asset
     pub amount: i64,
@@ -42,10 +97,450 @@ impl Asset {
 
     #[inline(always)]
     fn is_amount_within_range(&self) -> bool {
-        self.amount >= i64::MIN && self.amount <= i64::MAX
+        -MAX_ASSET_AMOUNT <= self.amount && self.amount <= MAX_ASSET_AMOUNT
+    }
+
+    /// `amount` in this asset's symbol, if within `MAX_ASSET_AMOUNT`.
+    #[inline(always)]
+    fn with_amount(&self, amount: i128) -> Option<Self> {
+        if (-i128::from(MAX_ASSET_AMOUNT)..=i128::from(MAX_ASSET_AMOUNT)).contains(&amount) {
+            Some(Self {
+                amount: amount as i64,
+                symbol: self.symbol,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Multiplies the amount by `factor`, `None` if the result is out of
+    /// range.
+    #[must_use]
+    pub fn checked_mul(&self, factor: i64) -> Option<Self> {
+        self.with_amount(i128::from(self.amount) * i128::from(factor))
+    }
+
+    /// Divides the amount by `divisor`, `None` if `divisor` is zero.
+    #[must_use]
+    pub fn checked_div(&self, divisor: i64, rounding: Rounding) -> Option<Self> {
+        self.checked_mul_ratio(1, divisor, rounding)
+    }
+
+    /// Multiplies the amount by `numerator / denominator` without
+    /// intermediate overflow, e.g. `fee = quantity.checked_mul_ratio(5, 1000,
+    /// Rounding::Ceil)` for 0.5%. `None` if `denominator` is zero or the
+    /// result is out of range.
+    #[must_use]
+    pub fn checked_mul_ratio(
+        &self,
+        numerator: i64,
+        denominator: i64,
+        rounding: Rounding,
+    ) -> Option<Self> {
+        let product = i128::from(self.amount) * i128::from(numerator);
+        self.with_amount(rounding.div(product, i128::from(denominator))?)
+    }
+
+    /// Like [`checked_mul_ratio`](Self::checked_mul_ratio), aborting the
+    /// action on division by zero or overflow.
+    pub fn mul_ratio(&self, numerator: i64, denominator: i64, rounding: Rounding) -> Self {
+        check(denominator != 0, "division by zero");
+        match self.checked_mul_ratio(numerator, denominator, rounding) {
+            Some(result) => result,
+            None => {
+                check(false, "multiplication overflow");
+                unreachable!()
+            }
+        }
+    }
+
+    /// The same quantity with `precision` decimals, e.g. `1.2345 XPR` as
+    /// `1.23 XPR` with two decimals. `None` if `precision` is larger than
+    /// [`MAX_PRECISION`] or the result is out of range.
+    #[must_use]
+    pub fn checked_to_precision(&self, precision: u8, rounding: Rounding) -> Option<Self> {
+        if precision > MAX_PRECISION {
+            return None;
+        }
+        let current = self.symbol.precision();
+        let amount = i128::from(self.amount);
+        let amount = if precision >= current {
+            // At most 18 + 19 digits, well within an `i128`.
+            amount * 10_i128.checked_pow(u32::from(precision - current))?
+        } else {
+            let scale = 10_i128.checked_pow(u32::from(current - precision))?;
+            rounding.div(amount, scale)?
+        };
+        Self::new(0, Symbol::new_with_code(precision, self.symbol.code())).with_amount(amount)
+    }
+
+    /// Splits the asset into `numerator / denominator` of it and the rest,
+    /// the first part being rounded down. The two parts always add up to the
+    /// asset. `None` if `denominator` is zero or the part is out of range.
+    #[must_use]
+    pub fn checked_split(&self, numerator: i64, denominator: i64) -> Option<(Self, Self)> {
+        let part = self.checked_mul_ratio(numerator, denominator, Rounding::Floor)?;
+        let rest = self.with_amount(i128::from(self.amount) - i128::from(part.amount))?;
+        Some((part, rest))
+    }
+
+    /// Splits the asset into parts proportional to `weights`. Each part is
+    /// rounded down and what is left is handed out one unit at a time from the
+    /// first part on, so the parts always add up to the asset. `None` if the
+    /// weights add up to zero.
+    #[must_use]
+    pub fn split_proportionally(&self, weights: &[u64]) -> Option<Vec<Self>> {
+        let total: u128 = weights.iter().map(|&w| u128::from(w)).sum();
+        if total == 0 {
+            return None;
+        }
+        let magnitude = u128::from(self.amount.unsigned_abs());
+        let sign = self.amount.signum();
+
+        let mut parts: Vec<u64> = weights
+            .iter()
+            // A part is at most `magnitude`, so the product fits in a `u128`
+            // and the quotient in a `u64`.
+            .map(|&w| (magnitude * u128::from(w) / total) as u64)
+            .collect();
+        let mut left = magnitude as u64 - parts.iter().sum::<u64>();
+        for (part, &weight) in parts.iter_mut().zip(weights) {
+            if left == 0 {
+                break;
+            }
+            if weight > 0 {
+                *part += 1;
+                left -= 1;
+            }
+        }
+
+        Some(
+            parts
+                .into_iter()
+                .map(|part| Self {
+                    amount: sign * part as i64,
+                    symbol: self.symbol,
+                })
+                .collect(),
+        )
+    }
+
+    /// Parses `value` like [`FromStr`] does, additionally requiring it to be
+    /// in `symbol`, e.g. so that `1.00 XPR` is rejected for `4,XPR`.
+    pub fn from_str_with_symbol(value: &str, symbol: Symbol) -> Result<Self, ParseAssetError> {
+        let asset = value.parse::<Self>()?;
+        if asset.symbol.code() != symbol.code() {
+            return Err(ParseAssetError::SymbolMismatch);
+        }
+        if asset.symbol.precision() != symbol.precision() {
+            return Err(ParseAssetError::PrecisionMismatch {
+                expected: symbol.precision(),
+                found: asset.symbol.precision(),
+            });
+        }
+        Ok(asset)
+    }
+
+    /// Writes the text form of the asset, e.g. `-12.3400 XPR`, in pieces.
+    ///
+    /// Shared by [`Display`](fmt::Display) and `Printable`, so that neither
+    /// relies on `core::fmt`'s number formatting.
+    pub(crate) fn write_parts(&self, mut out: impl FnMut(&str)) {
+        const ZEROS: &str = "000000000000000000000000000000000000000000000000000000000000";
+
+        let mut buf = [0_u8; 20];
+        let digits = write_decimal(self.amount.unsigned_abs(), &mut buf);
+        let precision = usize::from(self.symbol.precision());
+
+        if self.amount < 0 {
+            out("-");
+        }
+        if digits.len() > precision {
+            let (integer, fraction) = digits.split_at(digits.len() - precision);
+            out(integer);
+            if precision > 0 {
+                out(".");
+                out(fraction);
+            }
+        } else {
+            out("0.");
+            let mut padding = precision - digits.len();
+            while padding > 0 {
+                let n = padding.min(ZEROS.len());
+                out(&ZEROS[..n]);
+                padding -= n;
+            }
+            out(digits);
+        }
+        out(" ");
+        self.symbol.code().with_str(out);
+    }
+}
+
+/// Writes the amount with the symbol's precision followed by its code, e.g.
+/// `-12.3400 XPR`.
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_parts(f, |out| self.write_parts(out))
+    }
+}
+
+/// A quantity along with the contract that issues its token, e.g. a deposit
+/// to a contract accepting tokens from several token contracts.
+///
+/// Arithmetic and comparisons require both operands to come from the same
+/// contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, NumBytes, Read, Write, Default)]
+#[pulse(crate_path = "pulse_serialization", abi = "extended_asset")]
+#[cfg_attr(feature = "serde", pulse(serde))]
+pub struct ExtendedAsset {
+    /// The quantity of the asset
+    pub quantity: Asset,
+    /// The token contract issuing the asset
+    pub contract: Name,
+}
+
+impl ExtendedAsset {
+    #[inline(always)]
+    pub const fn new(quantity: Asset, contract: Name) -> Self {
+        Self { quantity, contract }
+    }
+
+    #[inline(always)]
+    pub fn zero(symbol: ExtendedSymbol) -> Self {
+        Self {
+            quantity: Asset::zero(symbol.sym),
+            contract: symbol.contract,
+        }
+    }
+
+    #[inline(always)]
+    #[must_use]
+    pub const fn get_extended_symbol(&self) -> ExtendedSymbol {
+        ExtendedSymbol::new(self.quantity.symbol, self.contract)
+    }
+
+    #[inline(always)]
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        self.quantity.is_valid()
+    }
+
+    /// Writes the text form of the asset, e.g. `1.0000 XPR@eosio.token`,
+    /// in pieces.
+    pub(crate) fn write_parts(&self, mut out: impl FnMut(&str)) {
+        self.quantity.write_parts(&mut out);
+        out("@");
+        self.contract.with_str(out);
+    }
+}
+
+impl From<(Asset, Name)> for ExtendedAsset {
+    #[inline(always)]
+    fn from((quantity, contract): (Asset, Name)) -> Self {
+        Self::new(quantity, contract)
+    }
+}
+
+impl From<ExtendedAsset> for Asset {
+    #[inline(always)]
+    fn from(asset: ExtendedAsset) -> Self {
+        asset.quantity
+    }
+}
+
+/// Writes `<amount> <code>@<contract>`, e.g. `1.0000 XPR@eosio.token`.
+impl fmt::Display for ExtendedAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_parts(f, |out| self.write_parts(out))
+    }
+}
+
+impl Add for ExtendedAsset {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, other: Self) -> Self::Output {
+        let mut result = self;
+        result += other;
+        result
+    }
+}
+
+impl AddAssign for ExtendedAsset {
+    #[inline(always)]
+    fn add_assign(&mut self, other: Self) {
+        check(
+            self.contract == other.contract,
+            "attempt to add extended assets with different contracts",
+        );
+        self.quantity += other.quantity;
+    }
+}
+
+impl Sub for ExtendedAsset {
+    type Output = Self;
+
+    #[inline(always)]
+    fn sub(self, other: Self) -> Self::Output {
+        let mut result = self;
+        result -= other;
+        result
+    }
+}
+
+impl SubAssign for ExtendedAsset {
+    #[inline(always)]
+    fn sub_assign(&mut self, other: Self) {
+        check(
+            self.contract == other.contract,
+            "attempt to subtract extended assets with different contracts",
+        );
+        self.quantity -= other.quantity;
+    }
+}
+
+impl Neg for ExtendedAsset {
+    type Output = Self;
+
+    #[inline(always)]
+    fn neg(self) -> Self::Output {
+        Self {
+            quantity: -self.quantity,
+            contract: self.contract,
+        }
     }
 }
 
+impl PartialOrd for ExtendedAsset {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        if self.contract != other.contract {
+            return None;
+        }
+
+        self.quantity.partial_cmp(&other.quantity)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ParseAssetError {
+    /// The string is not of the form `<amount> <code>`, the amount being an
+    /// optionally negative decimal number, e.g. `-12.3400 XPR`.
+    BadFormat,
+    /// The amount has more than [`MAX_PRECISION`] decimals.
+    PrecisionTooHigh,
+    /// The amount's magnitude is larger than [`MAX_ASSET_AMOUNT`].
+    Overflow,
+    /// The code is not a valid symbol code.
+    BadCode(ParseSymbolCodeError),
+    /// The asset has another code than the expected symbol.
+    SymbolMismatch,
+    /// The amount has another number of decimals than the expected symbol.
+    PrecisionMismatch { expected: u8, found: u8 },
+}
+
+impl From<ParseSymbolCodeError> for ParseAssetError {
+    #[inline]
+    fn from(e: ParseSymbolCodeError) -> Self {
+        Self::BadCode(e)
+    }
+}
+
+/// Parses `<amount> <code>`, e.g. `-12.3400 XPR`. The number of decimals of
+/// the amount is the precision of the symbol.
+impl FromStr for Asset {
+    type Err = ParseAssetError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (amount, code) = value.split_once(' ').ok_or(ParseAssetError::BadFormat)?;
+
+        let (negative, amount) = match amount.strip_prefix('-') {
+            Some(amount) => (true, amount),
+            None => (false, amount),
+        };
+        let (integer, fraction) = match amount.split_once('.') {
+            Some((integer, fraction)) if !fraction.is_empty() => (integer, fraction),
+            Some(_) => return Err(ParseAssetError::BadFormat),
+            None => (amount, ""),
+        };
+        if integer.is_empty() {
+            return Err(ParseAssetError::BadFormat);
+        }
+        if fraction.len() > usize::from(MAX_PRECISION) {
+            return Err(ParseAssetError::PrecisionTooHigh);
+        }
+
+        let mut magnitude: u64 = 0;
+        for c in integer.bytes().chain(fraction.bytes()) {
+            if !c.is_ascii_digit() {
+                return Err(ParseAssetError::BadFormat);
+            }
+            magnitude = magnitude
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(u64::from(c - b'0')))
+                .ok_or(ParseAssetError::Overflow)?;
+        }
+        if magnitude > MAX_ASSET_AMOUNT as u64 {
+            return Err(ParseAssetError::Overflow);
+        }
+
+        let amount = if negative {
+            -(magnitude as i64)
+        } else {
+            magnitude as i64
+        };
+        let code = code.parse::<SymbolCode>()?;
+        let symbol = Symbol::new_with_code(fraction.len() as u8, code);
+        Ok(Self { amount, symbol })
+    }
+}
+
+/// Writes the pieces `write_parts` gives its callback to `f`, for the
+/// `Display` impls of the types that print themselves in pieces.
+pub(crate) fn fmt_parts(
+    f: &mut fmt::Formatter<'_>,
+    write_parts: impl FnOnce(&mut dyn FnMut(&str)),
+) -> fmt::Result {
+    let mut result = Ok(());
+    write_parts(&mut |s| {
+        if result.is_ok() {
+            result = f.write_str(s);
+        }
+    });
+    result
+}
+
+/// Writes `value` as decimal digits at the end of `buf`, returning them.
+pub(crate) fn write_decimal(mut value: u64, buf: &mut [u8; 20]) -> &str {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    // Only ASCII digits were written.
+    core::str::from_utf8(&buf[start..]).unwrap_or_default()
+}
+
+/// Parses a non-empty string of decimal digits, `None` if it has anything
+/// else or doesn't fit a `u64`.
+pub(crate) fn parse_decimal(value: &str) -> Option<u64> {
+    if value.is_empty() {
+        return None;
+    }
+    value.bytes().try_fold(0_u64, |n, c| {
+        if c.is_ascii_digit() {
+            n.checked_mul(10)?.checked_add(u64::from(c - b'0'))
+        } else {
+            None
+        }
+    })
+}
+
 impl Add for Asset {
     type Output = Self;
 
@@ -75,6 +570,8 @@ impl AddAssign for Asset {
                 check(false, "addition overflow");
             }
         }
+        check(-MAX_ASSET_AMOUNT <= self.amount, "addition underflow");
+        check(self.amount <= MAX_ASSET_AMOUNT, "addition overflow");
     }
 }
 
@@ -107,33 +604,28 @@ impl SubAssign for Asset {
                 check(false, "subtraction overflow");
             }
         }
+        check(-MAX_ASSET_AMOUNT <= self.amount, "subtraction underflow");
+        check(self.amount <= MAX_ASSET_AMOUNT, "subtraction overflow");
     }
 }
 
-impl Mul for Asset {
+impl Mul<i64> for Asset {
     type Output = Self;
 
     #[inline(always)]
-    fn mul(self, other: Self) -> Self::Output {
+    fn mul(self, factor: i64) -> Self::Output {
         let mut result = self;
-        result *= other;
-        return result;
+        result *= factor;
+        result
     }
 }
 
-impl MulAssign for Asset {
+impl MulAssign<i64> for Asset {
     #[inline(always)]
-    fn mul_assign(&mut self, other: Self) {
-        check(
-            self.symbol == other.symbol,
-            "attempt to multiply asset with different symbol",
-        );
-
-        let result = self.amount.checked_mul(other.amount);
-
-        match result {
-            Some(value) => {
-                self.amount = value;
+    fn mul_assign(&mut self, factor: i64) {
+        match self.checked_mul(factor) {
+            Some(result) => {
+                *self = result;
             }
             None => {
                 check(false, "multiplication overflow");
@@ -142,27 +634,24 @@ impl MulAssign for Asset {
     }
 }
 
-impl Div for Asset {
+impl Div<i64> for Asset {
     type Output = Self;
 
+    /// Divides the amount, rounding toward zero.
     #[inline(always)]
-    fn div(self, other: Self) -> Self::Output {
+    fn div(self, divisor: i64) -> Self::Output {
         let mut result = self;
-        result /= other;
-        return result;
+        result /= divisor;
+        result
     }
 }
 
-impl DivAssign for Asset {
+impl DivAssign<i64> for Asset {
     #[inline(always)]
-    fn div_assign(&mut self, other: Self) {
-        check(
-            self.symbol == other.symbol,
-            "attempt to divide asset with different symbol",
-        );
-        check(other.amount != 0, "division by zero");
+    fn div_assign(&mut self, divisor: i64) {
+        check(divisor != 0, "division by zero");
 
-        let result = self.amount.checked_div(other.amount);
+        let result = self.amount.checked_div(divisor);
 
         match result {
             Some(value) => {
@@ -197,3 +686,277 @@ impl PartialOrd for Asset {
         self.amount.partial_cmp(&other.amount)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use pulse_serialization::{Read, Write};
+
+    use super::*;
+
+    fn xpr(precision: u8) -> Symbol {
+        Symbol::new_with_code(precision, "XPR".parse().unwrap())
+    }
+
+    #[test]
+    fn format_and_parse() {
+        for (amount, precision, text) in [
+            (-123_400, 4, "-12.3400 XPR"),
+            (10_000, 4, "1.0000 XPR"),
+            (5, 4, "0.0005 XPR"),
+            (0, 0, "0 XPR"),
+            (-7, 0, "-7 XPR"),
+            (MAX_ASSET_AMOUNT, 18, "4.611686018427387903 XPR"),
+        ] {
+            let asset = Asset::new(amount, xpr(precision));
+            assert_eq!(asset.to_string(), text);
+            assert_eq!(text.parse::<Asset>(), Ok(asset));
+        }
+
+        assert_eq!(xpr(4).to_string(), "4,XPR");
+        assert_eq!("4,XPR".parse::<Symbol>(), Ok(xpr(4)));
+        assert_eq!(xpr(4).code().to_string(), "XPR");
+    }
+
+    #[test]
+    fn format_precision_beyond_amount_digits() {
+        let asset = Asset::new(-1, Symbol::new_with_code(70, "XPR".parse().unwrap()));
+        let text = asset.to_string();
+        assert_eq!(text.len(), "-0.".len() + 70 + " XPR".len());
+        assert!(text.starts_with("-0.000"));
+        assert!(text.ends_with("01 XPR"));
+    }
+
+    #[test]
+    fn rounding() {
+        for (n, d, floor, ceil, half_even) in [
+            (7, 2, 3, 4, 4),
+            (5, 2, 2, 3, 2),
+            (-5, 2, -3, -2, -2),
+            (-7, 2, -4, -3, -4),
+            (7, -3, -3, -2, -2),
+            (-8, -3, 2, 3, 3),
+            (6, 3, 2, 2, 2),
+        ] {
+            assert_eq!(Rounding::Floor.div(n, d), Some(floor), "{n}/{d}");
+            assert_eq!(Rounding::Ceil.div(n, d), Some(ceil), "{n}/{d}");
+            assert_eq!(Rounding::HalfEven.div(n, d), Some(half_even), "{n}/{d}");
+        }
+        assert_eq!(Rounding::Floor.div(1, 0), None);
+    }
+
+    #[test]
+    fn scaling() {
+        let asset = Asset::new(1_2345, xpr(4));
+
+        // 0.5% fee, rounded up.
+        assert_eq!(
+            asset.checked_mul_ratio(5, 1000, Rounding::Ceil),
+            Some(Asset::new(62, xpr(4)))
+        );
+        assert_eq!(
+            asset.checked_mul_ratio(5, 1000, Rounding::Floor),
+            Some(Asset::new(61, xpr(4)))
+        );
+        // No intermediate overflow.
+        let max = Asset::new(MAX_ASSET_AMOUNT, xpr(4));
+        assert_eq!(
+            max.checked_mul_ratio(MAX_ASSET_AMOUNT, MAX_ASSET_AMOUNT, Rounding::Floor),
+            Some(max)
+        );
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!((-max).checked_mul(1), Some(-max));
+        assert_eq!(asset.checked_mul_ratio(1, 0, Rounding::Floor), None);
+        assert_eq!(
+            asset.checked_div(2, Rounding::HalfEven),
+            Some(Asset::new(6172, xpr(4)))
+        );
+        assert_eq!(asset.checked_mul(3), Some(Asset::new(3_7035, xpr(4))));
+
+        assert_eq!(
+            asset.checked_to_precision(2, Rounding::HalfEven),
+            Some(Asset::new(123, xpr(2)))
+        );
+        assert_eq!(
+            asset.checked_to_precision(2, Rounding::Ceil),
+            Some(Asset::new(124, xpr(2)))
+        );
+        assert_eq!(
+            asset.checked_to_precision(8, Rounding::Floor),
+            Some(Asset::new(1_2345_0000, xpr(8)))
+        );
+        assert_eq!(max.checked_to_precision(5, Rounding::Floor), None);
+        assert_eq!(asset.checked_to_precision(19, Rounding::Floor), None);
+    }
+
+    #[test]
+    fn splitting() {
+        let asset = Asset::new(100, xpr(4));
+        assert_eq!(
+            asset.checked_split(1, 3),
+            Some((Asset::new(33, xpr(4)), Asset::new(67, xpr(4))))
+        );
+
+        let parts = asset.split_proportionally(&[1, 1, 1]).unwrap();
+        let amounts: alloc::vec::Vec<i64> = parts.iter().map(|a| a.amount).collect();
+        assert_eq!(amounts, [34, 33, 33]);
+
+        let parts = Asset::new(-10, xpr(4))
+            .split_proportionally(&[0, 3, 1])
+            .unwrap();
+        let amounts: alloc::vec::Vec<i64> = parts.iter().map(|a| a.amount).collect();
+        assert_eq!(amounts, [0, -8, -2]);
+
+        let parts = Asset::new(MAX_ASSET_AMOUNT, xpr(4))
+            .split_proportionally(&[u64::MAX, u64::MAX])
+            .unwrap();
+        assert_eq!(parts[0].amount + parts[1].amount, MAX_ASSET_AMOUNT);
+
+        assert_eq!(asset.split_proportionally(&[0, 0]), None);
+        assert_eq!(asset.split_proportionally(&[]), None);
+    }
+
+    #[test]
+    fn amount_range() {
+        assert!(Asset::new(MAX_ASSET_AMOUNT, xpr(4)).is_valid());
+        assert!(Asset::new(-MAX_ASSET_AMOUNT, xpr(4)).is_valid());
+        assert!(!Asset::new(MAX_ASSET_AMOUNT + 1, xpr(4)).is_valid());
+        assert!(!Asset::new(i64::MIN, xpr(4)).is_valid());
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    #[should_panic(expected = "addition overflow")]
+    fn addition_is_bounded_by_max_amount() {
+        let _ = Asset::new(MAX_ASSET_AMOUNT, xpr(4)) + Asset::new(1, xpr(4));
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn scalar_operators() {
+        let asset = Asset::new(1_0001, xpr(4));
+        assert_eq!(asset * 3, Asset::new(3_0003, xpr(4)));
+        assert_eq!(asset / 2, Asset::new(5000, xpr(4)));
+        assert_eq!(-asset / 2, Asset::new(-5000, xpr(4)));
+        assert_eq!(
+            asset.mul_ratio(1, 200, Rounding::Ceil),
+            Asset::new(51, xpr(4))
+        );
+    }
+
+    #[test]
+    fn extended_asset() {
+        let contract = crate::name!("pulse.token");
+        let sym = ExtendedSymbol::new(xpr(4), contract);
+        let asset = ExtendedAsset::new(Asset::new(10_000, xpr(4)), contract);
+
+        assert_eq!(sym.to_string(), "4,XPR@pulse.token");
+        assert_eq!(asset.to_string(), "1.0000 XPR@pulse.token");
+        assert_eq!(asset.get_extended_symbol(), sym);
+        assert_eq!(ExtendedAsset::from((asset.quantity, contract)), asset);
+        assert_eq!(Asset::from(asset), asset.quantity);
+        assert_eq!(ExtendedAsset::zero(sym).quantity.amount, 0);
+
+        assert_eq!(
+            asset.pack().unwrap(),
+            (asset.quantity, contract).pack().unwrap()
+        );
+        assert_eq!(
+            ExtendedAsset::unpack(&asset.pack().unwrap()).unwrap(),
+            asset
+        );
+        assert_eq!(ExtendedSymbol::unpack(&sym.pack().unwrap()).unwrap(), sym);
+
+        let mut abi = crate::abi::Abi::default();
+        assert_eq!(
+            <ExtendedAsset as crate::abi::AbiType>::abi_type(&mut abi),
+            "extended_asset"
+        );
+        assert_eq!(
+            <ExtendedSymbol as crate::abi::AbiType>::abi_type(&mut abi),
+            "extended_symbol"
+        );
+
+        let other = ExtendedAsset::new(asset.quantity, Name::new(1));
+        assert_eq!(asset.partial_cmp(&other), None);
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    #[should_panic(expected = "attempt to add extended assets with different contracts")]
+    fn extended_asset_refuses_mixed_contracts() {
+        let asset = ExtendedAsset::new(Asset::new(1, xpr(4)), Name::new(1));
+        assert!(asset < asset + asset);
+        let _ = asset + ExtendedAsset::new(asset.quantity, Name::new(2));
+    }
+
+    #[test]
+    fn parse_errors() {
+        for text in [
+            "",
+            "1.0000",
+            " 1 XPR",
+            "+1 XPR",
+            ".5 XPR",
+            "1. XPR",
+            "1.2.3 XPR",
+            "1,0 XPR",
+        ] {
+            assert_eq!(
+                text.parse::<Asset>(),
+                Err(ParseAssetError::BadFormat),
+                "{text:?}"
+            );
+        }
+        assert_eq!(
+            "1.0000 XPr".parse::<Asset>(),
+            Err(ParseAssetError::BadCode(ParseSymbolCodeError::BadChar(
+                b'r'
+            )))
+        );
+        assert_eq!(
+            "1 ".parse::<Asset>(),
+            Err(ParseAssetError::BadCode(ParseSymbolCodeError::Empty))
+        );
+        assert_eq!(
+            "1.0000000000000000000 XPR".parse::<Asset>(),
+            Err(ParseAssetError::PrecisionTooHigh)
+        );
+        assert_eq!(
+            "4611686018427387904 XPR".parse::<Asset>(),
+            Err(ParseAssetError::Overflow)
+        );
+        assert_eq!(
+            "-99999999999999999999 XPR".parse::<Asset>(),
+            Err(ParseAssetError::Overflow)
+        );
+
+        assert_eq!(
+            Asset::from_str_with_symbol("1.00 XPR", xpr(4)),
+            Err(ParseAssetError::PrecisionMismatch {
+                expected: 4,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Asset::from_str_with_symbol("1.0000 ABC", xpr(4)),
+            Err(ParseAssetError::SymbolMismatch)
+        );
+        assert_eq!(
+            Asset::from_str_with_symbol("1.0000 XPR", xpr(4)),
+            Ok(Asset::new(10_000, xpr(4)))
+        );
+
+        use crate::core::ParseSymbolError;
+        assert_eq!("4XPR".parse::<Symbol>(), Err(ParseSymbolError::BadFormat));
+        assert_eq!(",XPR".parse::<Symbol>(), Err(ParseSymbolError::BadFormat));
+        assert_eq!(
+            "19,XPR".parse::<Symbol>(),
+            Err(ParseSymbolError::PrecisionTooHigh)
+        );
+        assert_eq!(
+            "4,TOOLONGX".parse::<Symbol>(),
+            Err(ParseSymbolError::BadCode(ParseSymbolCodeError::TooLong))
+        );
+    }
+}
@@ -1,6 +1,10 @@
-use alloc::{collections::btree_set::BTreeSet, vec::Vec};
+use alloc::{borrow::ToOwned, collections::btree_set::BTreeSet, string::String, vec, vec::Vec};
 use hashbrown::{hash_map::DefaultHashBuilder, HashSet};
 use pulse_proc_macro::{NumBytes, Read, Write};
+use pulse_serialization::{
+    abi::{Abi, AbiType},
+    WriteError,
+};
 
 use crate::{
     contracts::KeyWeight,
@@ -8,7 +12,8 @@ use crate::{
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, NumBytes, Read, Write)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi_name = "block_header")]
+#[cfg_attr(feature = "serde", pulse(serde))]
 pub struct BlockHeader {
     pub timestamp: BlockTimestamp,
     pub producer: Name,
@@ -18,7 +23,9 @@ pub struct BlockHeader {
     pub action_mroot: FixedBytes<32>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, NumBytes, Read, Write)]
+/// The node's `std::variant<block_signing_authority_v0>`, of which `variant`
+/// is the tag. v0 is the only alternative so far.
+#[derive(Debug, Clone, PartialEq, Eq, NumBytes, Read)]
 #[pulse(crate_path = "pulse_serialization")]
 pub struct BlockSigningAuthority {
     variant: u8,
@@ -64,3 +71,36 @@ impl BlockSigningAuthority {
         return true;
     }
 }
+
+impl pulse_serialization::Write for BlockSigningAuthority {
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.variant.write(bytes, pos)?;
+        self.threshold.write(bytes, pos)?;
+        self.keys.write(bytes, pos)
+    }
+}
+
+/// Described as the node does: `block_signing_authority` names the variant
+/// `variant_block_signing_authority_v0`.
+impl AbiType for BlockSigningAuthority {
+    fn abi_type(abi: &mut Abi) -> String {
+        let variant = abi.add_variant_with("variant_block_signing_authority_v0", |abi| {
+            [abi.add_struct_with("block_signing_authority_v0", |abi| {
+                [
+                    ("threshold", u32::abi_type(abi)),
+                    ("keys", Vec::<KeyWeight>::abi_type(abi)),
+                ]
+            })]
+        });
+        abi.types
+            .insert("block_signing_authority".to_owned(), variant);
+        "block_signing_authority".to_owned()
+    }
+}
+
+impl From<PublicKey> for BlockSigningAuthority {
+    /// A single key with weight and threshold 1.
+    fn from(key: PublicKey) -> Self {
+        Self::new(1, vec![KeyWeight { key, weight: 1 }])
+    }
+}
@@ -1,3 +1,4 @@
+#[cfg(not(feature = "testing"))]
 mod assert_impl {
     extern "C" {
         #[link_name = "pulse_assert"]
@@ -5,6 +6,9 @@ mod assert_impl {
     }
 }
 
+#[cfg(feature = "testing")]
+use crate::testing::host as assert_impl;
+
 #[inline]
 pub fn check(pred: bool, msg: &str) {
     if !pred {
@@ -1,4 +1,8 @@
-use pulse_serialization::{NumBytes, Read, ReadError, Write, WriteError};
+use alloc::{borrow::ToOwned, string::String};
+use pulse_serialization::{
+    abi::{Abi, AbiType},
+    NumBytes, Read, ReadError, ReadErrorKind, Write, WriteError,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct FixedBytes<const N: usize>(pub [u8; N]);
@@ -23,11 +27,13 @@ impl<const N: usize> NumBytes for FixedBytes<N> {
 
 impl<const N: usize> Read for FixedBytes<N> {
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        if bytes.len() < *pos + N {
-            return Err(ReadError::ParseError);
-        }
+        let start = *pos;
+        let value = start
+            .checked_add(N)
+            .and_then(|end| bytes.get(start..end))
+            .ok_or_else(|| ReadError::new(ReadErrorKind::NotEnoughBytes, "FixedBytes", start))?;
         let mut arr = [0u8; N];
-        arr.copy_from_slice(&bytes[*pos..*pos + N]);
+        arr.copy_from_slice(value);
         *pos += N;
         Ok(Self(arr))
     }
@@ -43,3 +49,15 @@ impl<const N: usize> Write for FixedBytes<N> {
         Ok(())
     }
 }
+
+/// Only the checksum sizes have an ABI type.
+impl<const N: usize> AbiType for FixedBytes<N> {
+    fn abi_type(_abi: &mut Abi) -> String {
+        match N {
+            20 => "checksum160".to_owned(),
+            32 => "checksum256".to_owned(),
+            64 => "checksum512".to_owned(),
+            _ => panic!("FixedBytes<{N}> has no ABI type, only 20, 32 and 64 bytes do"),
+        }
+    }
+}
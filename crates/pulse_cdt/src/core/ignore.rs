@@ -1,6 +1,10 @@
 use core::marker::PhantomData;
 
-use pulse_serialization::{NumBytes, Read};
+use alloc::string::String;
+use pulse_serialization::{
+    abi::{Abi, AbiType},
+    NumBytes, Read,
+};
 
 /// A type that can be used to ignore parameters in action handlers.
 /// Currently non-ignore types can not succeed an ignore type in a method definition, i.e. void foo(float, ignore<int>) is allowed and void foo(float, ignore<int>, int) is not allowed.
@@ -20,4 +24,12 @@ impl<T: NumBytes> Read for Ignore<T> {
     fn read(_bytes: &[u8], _pos: &mut usize) -> Result<Self, pulse_serialization::ReadError> {
         Ok(Ignore { _data: PhantomData })
     }
-}
\ No newline at end of file
+}
+
+/// The ABI still describes the ignored parameter, so callers know to send it.
+impl<T: NumBytes + AbiType> AbiType for Ignore<T> {
+    #[inline]
+    fn abi_type(abi: &mut Abi) -> String {
+        T::abi_type(abi)
+    }
+}
@@ -1,6 +1,8 @@
 mod asset;
 pub use asset::*;
 
+mod base58;
+
 mod block_header;
 pub use block_header::*;
 
@@ -22,9 +24,15 @@ pub use ignore::*;
 mod name;
 pub use name::*;
 
+mod producer_schedule;
+pub use producer_schedule::*;
+
 mod public_key;
 pub use public_key::*;
 
+#[cfg(feature = "serde")]
+mod serde_impls;
+
 mod signature;
 pub use signature::*;
 
@@ -40,6 +48,9 @@ pub use symbol_code::*;
 mod table_primary_index;
 pub use table_primary_index::*;
 
+mod table_secondary_index;
+pub use table_secondary_index::*;
+
 mod table;
 pub use table::*;
 
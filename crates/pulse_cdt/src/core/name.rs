@@ -9,7 +9,7 @@ use crate::core::check;
 #[derive(
     Debug, PartialEq, Eq, Clone, Copy, Default, Hash, PartialOrd, Ord, Read, NumBytes, Write,
 )]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi = "name")]
 pub struct Name(u64);
 
 impl Name {
@@ -31,6 +31,12 @@ impl Name {
         name_to_bytes(self.0)
     }
 
+    /// Calls `f` with the name's string form, without allocating.
+    pub(crate) fn with_str<R>(&self, f: impl FnOnce(&str) -> R) -> R {
+        let bytes = self.as_bytes();
+        f(str::from_utf8(&bytes).unwrap_or("").trim_end_matches('.'))
+    }
+
     pub fn to_string(&self) -> String {
         let bytes = self.as_bytes();
         let value = str::from_utf8(&bytes).map(|s| s.trim_end_matches('.'));
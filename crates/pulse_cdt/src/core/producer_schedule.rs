@@ -0,0 +1,42 @@
+use alloc::vec::Vec;
+use pulse_proc_macro::{NumBytes, Read, Write};
+
+use crate::core::{BlockSigningAuthority, Name, PublicKey};
+
+/// A producer and the single key it signs blocks with.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, NumBytes, Read, Write)]
+#[pulse(crate_path = "pulse_serialization", abi_name = "producer_key")]
+#[cfg_attr(feature = "serde", pulse(serde))]
+pub struct ProducerKey {
+    pub producer_name: Name,
+    pub block_signing_key: PublicKey,
+}
+
+/// A producer and the keys, with their threshold, it signs blocks with.
+#[derive(Debug, Clone, PartialEq, Eq, NumBytes, Read, Write)]
+#[pulse(crate_path = "pulse_serialization", abi_name = "producer_authority")]
+#[cfg_attr(feature = "serde", pulse(serde))]
+pub struct ProducerAuthority {
+    pub producer_name: Name,
+    pub authority: BlockSigningAuthority,
+}
+
+impl From<ProducerKey> for ProducerAuthority {
+    /// The authority of a single key with weight and threshold 1.
+    fn from(producer: ProducerKey) -> Self {
+        Self {
+            producer_name: producer.producer_name,
+            authority: BlockSigningAuthority::from(producer.block_signing_key),
+        }
+    }
+}
+
+/// The producers taking turns to produce blocks, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, NumBytes, Read, Write)]
+#[pulse(crate_path = "pulse_serialization", abi_name = "producer_schedule")]
+#[cfg_attr(feature = "serde", pulse(serde))]
+pub struct ProducerSchedule {
+    /// Incremented each time a new schedule becomes active.
+    pub version: u32,
+    pub producers: Vec<ProducerKey>,
+}
@@ -1,19 +1,275 @@
+use core::{fmt, str::FromStr};
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use pulse_serialization::{NumBytes, Read, Write};
 
-use crate::core::{check, FixedBytes};
+use crate::{
+    contracts::ripemd160,
+    core::{base58, check, FixedBytes},
+};
+
+/// A public key, in one of the curves and schemes the chain supports.
+///
+/// The text form is `PUB_<type>_<base58>`, e.g. `PUB_K1_...`; legacy
+/// `EOS...` strings are accepted for K1 keys.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Eq, Hash, Read, Write, NumBytes)]
+#[pulse(crate_path = "pulse_serialization", abi = "public_key")]
+pub enum PublicKey {
+    /// A compressed secp256k1 key
+    K1(FixedBytes<33>),
+    /// A compressed secp256r1 key
+    R1(FixedBytes<33>),
+    /// A secp256r1 key held by a WebAuthn authenticator
+    WebAuthn(WebAuthnPublicKey),
+}
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Eq, Hash, Read, Write, NumBytes, Default)]
+#[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Eq, Hash, Read, Write, NumBytes)]
 #[pulse(crate_path = "pulse_serialization")]
-pub struct PublicKey(pub FixedBytes<34>);
+pub struct WebAuthnPublicKey {
+    /// The compressed secp256r1 key
+    pub key: FixedBytes<33>,
+    /// What the authenticator must check about the user: 0 for nothing, 1
+    /// for their presence, 2 for their verification
+    pub user_presence: u8,
+    /// The relying party the key is scoped to
+    pub rpid: String,
+}
+
+/// Legacy K1 keys are written `EOS` followed by the key and a checksum.
+const LEGACY_PREFIX: &str = "EOS";
 
 impl PublicKey {
-    /// Create a new `PublicKey` from a byte slice
+    /// Creates a `PublicKey` from its binary form, the type's tag included.
     #[inline]
     pub fn new(slice: &[u8]) -> Self {
-        check(slice.len() == 34, "public key must be 34 bytes long");
-        let data: [u8; 34] = slice
-            .try_into()
-            .expect("slice length is guaranteed to be 34 bytes");
-        Self(FixedBytes::new(data))
+        let key = Self::unpack(slice);
+        check(key.is_ok(), "invalid public key");
+        key.unwrap()
+    }
+
+    /// Writes the key in the legacy `EOS...` form, `None` if it isn't a K1
+    /// key.
+    pub fn to_legacy_string(&self) -> Option<String> {
+        match self {
+            Self::K1(key) => {
+                let mut data = key.0.to_vec();
+                data.extend_from_slice(&checksum(&key.0, b"")[..]);
+                Some(format!("{LEGACY_PREFIX}{}", base58::encode(&data)))
+            }
+            _ => None,
+        }
+    }
+
+    /// The key type as it appears in the text form.
+    fn type_suffix(&self) -> &'static str {
+        match self {
+            Self::K1(_) => "K1",
+            Self::R1(_) => "R1",
+            Self::WebAuthn(_) => "WA",
+        }
+    }
+}
+
+impl Default for PublicKey {
+    #[inline]
+    fn default() -> Self {
+        Self::K1(FixedBytes::default())
+    }
+}
+
+/// Writes `PUB_<type>_<base58>`, e.g. `PUB_K1_...`.
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match self {
+            Self::K1(key) | Self::R1(key) => key.0.to_vec(),
+            Self::WebAuthn(key) => key.pack().map_err(|_| fmt::Error)?,
+        };
+        f.write_str(&to_key_string("PUB_", self.type_suffix(), &data))
+    }
+}
+
+/// Parses `PUB_K1_...`, `PUB_R1_...`, `PUB_WA_...` and legacy `EOS...`
+/// strings, checking their checksum.
+impl FromStr for PublicKey {
+    type Err = ParseKeyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if let Some(rest) = value.strip_prefix(LEGACY_PREFIX) {
+            let data = from_checked_base58(rest, b"")?;
+            return Ok(Self::K1(fixed_bytes(&data)?));
+        }
+        let (suffix, data) = from_key_string("PUB_", value)?;
+        match suffix {
+            "K1" => Ok(Self::K1(fixed_bytes(&data)?)),
+            "R1" => Ok(Self::R1(fixed_bytes(&data)?)),
+            "WA" => WebAuthnPublicKey::unpack(&data)
+                .map(Self::WebAuthn)
+                .map_err(|_| ParseKeyError::BadData),
+            _ => Err(ParseKeyError::UnknownType),
+        }
+    }
+}
+
+/// An error parsing a key or signature string.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ParseKeyError {
+    /// The string doesn't start with a known prefix, such as `PUB_` or
+    /// `SIG_`.
+    BadPrefix,
+    /// The type after the prefix, such as `K1`, is unknown.
+    UnknownType,
+    /// The payload isn't valid base58.
+    BadBase58,
+    /// The payload's checksum doesn't match.
+    BadChecksum,
+    /// The payload doesn't decode as the key or signature type.
+    BadData,
+}
+
+/// The first 4 bytes of the RIPEMD-160 of `data` followed by `suffix`.
+fn checksum(data: &[u8], suffix: &[u8]) -> [u8; 4] {
+    let mut message = data.to_vec();
+    message.extend_from_slice(suffix);
+    let hash = ripemd160(&message, message.len() as u32);
+    [hash.0[0], hash.0[1], hash.0[2], hash.0[3]]
+}
+
+/// `<prefix><suffix>_<base58 of data and its checksum>`.
+pub(crate) fn to_key_string(prefix: &str, suffix: &str, data: &[u8]) -> String {
+    let mut payload = data.to_vec();
+    payload.extend_from_slice(&checksum(data, suffix.as_bytes()));
+    let mut out = prefix.to_string();
+    out.push_str(suffix);
+    out.push('_');
+    out.push_str(&base58::encode(&payload));
+    out
+}
+
+/// The type suffixes of keys and signatures.
+const KEY_TYPES: [&str; 3] = ["K1", "R1", "WA"];
+
+/// The type suffix and data of `<prefix><suffix>_<base58>`. The suffix is
+/// matched before the checksum, which covers it, is checked.
+pub(crate) fn from_key_string<'a>(
+    prefix: &str,
+    value: &'a str,
+) -> Result<(&'a str, Vec<u8>), ParseKeyError> {
+    let rest = value.strip_prefix(prefix).ok_or(ParseKeyError::BadPrefix)?;
+    let (suffix, payload) = rest.split_once('_').ok_or(ParseKeyError::BadPrefix)?;
+    if !KEY_TYPES.contains(&suffix) {
+        return Err(ParseKeyError::UnknownType);
+    }
+    Ok((suffix, from_checked_base58(payload, suffix.as_bytes())?))
+}
+
+fn from_checked_base58(payload: &str, suffix: &[u8]) -> Result<Vec<u8>, ParseKeyError> {
+    let mut data = base58::decode(payload).ok_or(ParseKeyError::BadBase58)?;
+    if data.len() < 4 {
+        return Err(ParseKeyError::BadChecksum);
+    }
+    let expected = data.split_off(data.len() - 4);
+    if checksum(&data, suffix)[..] != expected[..] {
+        return Err(ParseKeyError::BadChecksum);
+    }
+    Ok(data)
+}
+
+pub(crate) fn fixed_bytes<const N: usize>(data: &[u8]) -> Result<FixedBytes<N>, ParseKeyError> {
+    data.try_into()
+        .map(FixedBytes::new)
+        .map_err(|_| ParseKeyError::BadData)
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+    use crate::core::{Signature, WebAuthnSignature};
+
+    const LEGACY: &str = "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV";
+    const K1: &str = "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63";
+
+    #[test]
+    fn key_strings() {
+        let key: PublicKey = LEGACY.parse().unwrap();
+        assert!(matches!(key, PublicKey::K1(_)));
+        assert_eq!(key.to_string(), K1);
+        assert_eq!(K1.parse::<PublicKey>(), Ok(key.clone()));
+        assert_eq!(key.to_legacy_string().as_deref(), Some(LEGACY));
+
+        // The tag and the 33 bytes of the key.
+        let packed = key.pack().unwrap();
+        assert_eq!(packed.len(), 34);
+        assert_eq!(packed[0], 0);
+        assert_eq!(PublicKey::new(&packed), key);
+
+        let PublicKey::K1(bytes) = key else {
+            unreachable!()
+        };
+        let r1 = PublicKey::R1(bytes);
+        assert!(r1.to_string().starts_with("PUB_R1_"));
+        assert_eq!(r1.to_string().parse::<PublicKey>(), Ok(r1.clone()));
+        assert_eq!(r1.to_legacy_string(), None);
+
+        let wa = PublicKey::WebAuthn(WebAuthnPublicKey {
+            key: bytes,
+            user_presence: 1,
+            rpid: "example.com".into(),
+        });
+        assert!(wa.to_string().starts_with("PUB_WA_"));
+        assert_eq!(wa.to_string().parse::<PublicKey>(), Ok(wa));
+    }
+
+    #[test]
+    fn signature_strings() {
+        let mut bytes = [0_u8; 65];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let sig = Signature::K1(FixedBytes(bytes));
+        let text = sig.to_string();
+        assert!(text.starts_with("SIG_K1_"));
+        assert_eq!(text.parse::<Signature>(), Ok(sig.clone()));
+        assert_eq!(sig.pack().unwrap().len(), 66);
+
+        let wa = Signature::WebAuthn(WebAuthnSignature {
+            compact_signature: FixedBytes(bytes),
+            auth_data: vec![1, 2, 3],
+            client_json: "{}".into(),
+        });
+        assert_eq!(wa.to_string().parse::<Signature>(), Ok(wa));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut corrupted = String::from(&K1[..K1.len() - 1]);
+        corrupted.push('4');
+        assert_eq!(
+            corrupted.parse::<PublicKey>(),
+            Err(ParseKeyError::BadChecksum)
+        );
+        assert_eq!(
+            "PUB_K1_0OIl".parse::<PublicKey>(),
+            Err(ParseKeyError::BadBase58)
+        );
+        assert_eq!(
+            K1.replace("PUB_", "SIG_").parse::<Signature>(),
+            Err(ParseKeyError::BadData)
+        );
+        assert_eq!(
+            K1.replace("K1", "X1").parse::<PublicKey>(),
+            Err(ParseKeyError::UnknownType)
+        );
+        assert_eq!(
+            K1.replace("K1", "R1").parse::<PublicKey>(),
+            Err(ParseKeyError::BadChecksum)
+        );
+        assert_eq!(K1.parse::<Signature>(), Err(ParseKeyError::BadPrefix));
+        assert_eq!("PUB_K1".parse::<PublicKey>(), Err(ParseKeyError::BadPrefix));
     }
 }
@@ -0,0 +1,415 @@
+//! serde impls for the types whose JSON form isn't a struct of their fields,
+//! written the way the node's API writes them.
+//!
+//! The structs built on them derive theirs with `#[pulse(serde)]`.
+
+use core::{fmt, marker::PhantomData, str::FromStr};
+
+use alloc::{string::String, vec::Vec};
+use pulse_name::name_from_bytes;
+use pulse_serialization::serde_support::{hex_array, hex_bytes};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    contracts::{Action, KeyWeight},
+    core::{
+        Asset, BlockSigningAuthority, BlockTimestamp, FixedBytes, Name, PublicKey, Signature,
+        Symbol, SymbolCode, TimePoint, TimePointSec, Transaction, TransactionHeader,
+    },
+};
+
+/// Accepts a string that `T` parses from.
+struct FromStrVisitor<T>(&'static str, PhantomData<T>);
+
+impl<T> Visitor<'_> for FromStrVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Debug,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        value
+            .parse()
+            .map_err(|e| E::custom(format_args!("invalid {} `{value}`: {e:?}", self.0)))
+    }
+}
+
+/// Serializes the type as its `Display` form and deserializes it with
+/// `FromStr`.
+macro_rules! serde_with_str {
+    ($($ty:ty => $expecting:literal,)*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(FromStrVisitor($expecting, PhantomData))
+            }
+        }
+    )*};
+}
+
+serde_with_str! {
+    SymbolCode => "a symbol code",
+    Symbol => "a symbol",
+    Asset => "an asset",
+    PublicKey => "a public key",
+    Signature => "a signature",
+    TimePoint => "a time point",
+    TimePointSec => "a time point",
+    BlockTimestamp => "a block timestamp",
+}
+
+impl Serialize for Name {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.with_str(|s| serializer.serialize_str(s))
+    }
+}
+
+impl<'de> Deserialize<'de> for Name {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NameVisitor;
+
+        impl Visitor<'_> for NameVisitor {
+            type Value = Name;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a name")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Name, E> {
+                let name = name_from_bytes(value.bytes())
+                    .map(Name::new)
+                    .map_err(|e| E::custom(format_args!("invalid name `{value}`: {e:?}")))?;
+                // Rejects names the node would write differently, e.g. with
+                // trailing dots.
+                if !name.with_str(|s| s == value) {
+                    return Err(E::custom(format_args!(
+                        "`{value}` is not a normalized name"
+                    )));
+                }
+                Ok(name)
+            }
+        }
+
+        deserializer.deserialize_str(NameVisitor)
+    }
+}
+
+/// Lowercase hex, as the node writes checksums.
+impl<const N: usize> Serialize for FixedBytes<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        hex_array::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for FixedBytes<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        hex_array::deserialize(deserializer).map(Self)
+    }
+}
+
+/// The node writes the header's fields inline, and extensions as
+/// `{"type": ..., "data": "<hex>"}`.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Transaction")]
+struct TransactionDef {
+    #[serde(flatten)]
+    header: TransactionHeader,
+    context_free_actions: Vec<Action>,
+    actions: Vec<Action>,
+    #[serde(with = "extensions")]
+    transaction_extensions: Vec<(u16, Vec<u8>)>,
+}
+
+impl Serialize for Transaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TransactionDef::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Transaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        TransactionDef::deserialize(deserializer)
+    }
+}
+
+/// The node writes the variant as `["block_signing_authority_v0", {...}]`.
+const BLOCK_SIGNING_AUTHORITY_V0: &str = "block_signing_authority_v0";
+
+impl Serialize for BlockSigningAuthority {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct V0<'a> {
+            threshold: u32,
+            keys: &'a [KeyWeight],
+        }
+
+        let v0 = V0 {
+            threshold: self.threshold,
+            keys: &self.keys,
+        };
+        (BLOCK_SIGNING_AUTHORITY_V0, v0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockSigningAuthority {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct V0 {
+            threshold: u32,
+            keys: Vec<KeyWeight>,
+        }
+
+        let (tag, v0) = <(String, V0)>::deserialize(deserializer)?;
+        if tag != BLOCK_SIGNING_AUTHORITY_V0 {
+            return Err(de::Error::unknown_variant(
+                &tag,
+                &[BLOCK_SIGNING_AUTHORITY_V0],
+            ));
+        }
+        Ok(Self::new(v0.threshold, v0.keys))
+    }
+}
+
+mod extensions {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct ExtensionRef<'a> {
+        #[serde(rename = "type")]
+        ty: u16,
+        #[serde(with = "hex_bytes")]
+        data: &'a Vec<u8>,
+    }
+
+    #[derive(Deserialize)]
+    struct Extension {
+        #[serde(rename = "type")]
+        ty: u16,
+        #[serde(with = "hex_bytes")]
+        data: Vec<u8>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        extensions: &[(u16, Vec<u8>)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            extensions
+                .iter()
+                .map(|(ty, data)| ExtensionRef { ty: *ty, data }),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(u16, Vec<u8>)>, D::Error> {
+        let extensions = Vec::<Extension>::deserialize(deserializer)?;
+        Ok(extensions.into_iter().map(|e| (e.ty, e.data)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    use serde_json::{from_value, json, to_value, Value};
+
+    use super::*;
+    use crate::{
+        contracts::{Authority, KeyWeight, PermissionLevel, PermissionLevelWeight},
+        core::{Checksum256, ExtendedAsset},
+        name, BinaryExtension, NumBytes, Read, VarUint32, Write,
+    };
+
+    fn round_trip<T>(value: T, expected: Value)
+    where
+        T: Serialize + for<'de> Deserialize<'de> + PartialEq + fmt::Debug,
+    {
+        assert_eq!(to_value(&value).unwrap(), expected);
+        assert_eq!(from_value::<T>(expected).unwrap(), value);
+    }
+
+    #[test]
+    fn builtins() {
+        round_trip(name!("pulse.token"), json!("pulse.token"));
+        round_trip("1.0000 XPR".parse::<Asset>().unwrap(), json!("1.0000 XPR"));
+        round_trip("4,XPR".parse::<Symbol>().unwrap(), json!("4,XPR"));
+        round_trip(
+            "2020-02-29T12:34:56.789".parse::<TimePoint>().unwrap(),
+            json!("2020-02-29T12:34:56.789"),
+        );
+        round_trip(TimePointSec::new(0), json!("1970-01-01T00:00:00"));
+        round_trip(BlockTimestamp::new(1), json!("2000-01-01T00:00:00.500"));
+        let mut digest = Checksum256::default();
+        digest.0[31] = 0xab;
+        round_trip(digest, json!(alloc::format!("{:0>64}", "ab")));
+        round_trip(
+            ExtendedAsset {
+                quantity: "1.0000 XPR".parse().unwrap(),
+                contract: name!("pulse.token"),
+            },
+            json!({"quantity": "1.0000 XPR", "contract": "pulse.token"}),
+        );
+
+        assert!(from_value::<Name>(json!("pulse.")).is_err());
+        assert!(from_value::<Asset>(json!("1.0000")).is_err());
+        assert!(from_value::<Checksum256>(json!("ab")).is_err());
+        assert!(from_value::<TimePointSec>(json!(0)).is_err());
+    }
+
+    #[test]
+    fn authority() {
+        let key: PublicKey = "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63"
+            .parse()
+            .unwrap();
+        let authority = Authority {
+            threshold: 1,
+            keys: vec![KeyWeight {
+                key: key.clone(),
+                weight: 1,
+            }],
+            accounts: vec![PermissionLevelWeight {
+                permission: PermissionLevel::new(name!("alice"), name!("active")),
+                weight: 1,
+            }],
+        };
+        round_trip(
+            authority,
+            json!({
+                "threshold": 1,
+                "keys": [{"key": key.to_string(), "weight": 1}],
+                "accounts": [{
+                    "permission": {"actor": "alice", "permission": "active"},
+                    "weight": 1,
+                }],
+            }),
+        );
+    }
+
+    #[test]
+    fn block_signing_authority() {
+        let key: PublicKey = "PUB_K1_6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5BoDq63"
+            .parse()
+            .unwrap();
+        round_trip(
+            BlockSigningAuthority::from(key.clone()),
+            json!([
+                "block_signing_authority_v0",
+                {"threshold": 1, "keys": [{"key": key.to_string(), "weight": 1}]},
+            ]),
+        );
+
+        assert!(from_value::<BlockSigningAuthority>(json!([
+            "block_signing_authority_v1",
+            {"threshold": 1, "keys": []},
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn transaction() {
+        let action = Action {
+            account: name!("pulse.token"),
+            name: name!("transfer"),
+            authorization: vec![PermissionLevel::new(name!("alice"), name!("active"))],
+            data: vec![0x01, 0xff],
+        };
+        let transaction = Transaction {
+            header: TransactionHeader {
+                expiration: TimePointSec::new(60),
+                ref_block_num: 2,
+                ref_block_prefix: 3,
+                max_net_usage_words: VarUint32(0),
+                max_cpu_usage: 0,
+                delay_sec: VarUint32(10),
+            },
+            context_free_actions: Vec::new(),
+            actions: vec![action],
+            transaction_extensions: vec![(1, vec![0xab])],
+        };
+        round_trip(
+            transaction,
+            json!({
+                "expiration": "1970-01-01T00:01:00",
+                "ref_block_num": 2,
+                "ref_block_prefix": 3,
+                "max_net_usage_words": 0,
+                "max_cpu_usage": 0,
+                "delay_sec": 10,
+                "context_free_actions": [],
+                "actions": [{
+                    "account": "pulse.token",
+                    "name": "transfer",
+                    "authorization": [{"actor": "alice", "permission": "active"}],
+                    "data": "01ff",
+                }],
+                "transaction_extensions": [{"type": 1, "data": "ab"}],
+            }),
+        );
+    }
+
+    #[test]
+    fn derived() {
+        #[derive(Debug, PartialEq, Read, Write, NumBytes)]
+        #[pulse(serde)]
+        struct Pair<T> {
+            first: T,
+            memo: Vec<u8>,
+        }
+
+        #[derive(Debug, PartialEq, Read, Write, NumBytes)]
+        #[pulse(serde)]
+        struct Wrapper(Name);
+
+        round_trip(
+            Pair {
+                first: Wrapper(name!("alice")),
+                memo: vec![0x0a],
+            },
+            json!({"first": "alice", "memo": "0a"}),
+        );
+    }
+
+    #[test]
+    fn field_options() {
+        #[derive(Debug, PartialEq, Read, Write, NumBytes)]
+        #[pulse(serde)]
+        struct Row {
+            #[pulse(rename = "owner")]
+            account: Name,
+            #[pulse(skip)]
+            cached: u64,
+            extra: BinaryExtension<u64>,
+        }
+
+        round_trip(
+            Row {
+                account: name!("alice"),
+                cached: 0,
+                extra: BinaryExtension::none(),
+            },
+            json!({"owner": "alice"}),
+        );
+        round_trip(
+            Row {
+                account: name!("alice"),
+                cached: 0,
+                extra: BinaryExtension::new(7),
+            },
+            json!({"owner": "alice", "extra": 7}),
+        );
+    }
+}
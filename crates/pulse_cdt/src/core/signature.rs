@@ -1,7 +1,74 @@
+use core::{fmt, str::FromStr};
+
+use alloc::{string::String, vec::Vec};
 use pulse_serialization::{NumBytes, Read, Write};
 
-use crate::core::FixedBytes;
+use crate::core::{
+    public_key::{fixed_bytes, from_key_string, to_key_string},
+    FixedBytes, ParseKeyError,
+};
+
+/// A signature, in one of the curves and schemes the chain supports.
+///
+/// The text form is `SIG_<type>_<base58>`, e.g. `SIG_K1_...`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
+#[pulse(crate_path = "pulse_serialization", abi = "signature")]
+pub enum Signature {
+    /// A compact secp256k1 signature, recovery id first
+    K1(FixedBytes<65>),
+    /// A compact secp256r1 signature, recovery id first
+    R1(FixedBytes<65>),
+    /// A signature made by a WebAuthn authenticator
+    WebAuthn(WebAuthnSignature),
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Read, Write, NumBytes)]
 #[pulse(crate_path = "pulse_serialization")]
-pub struct Signature(pub FixedBytes<65>);
+pub struct WebAuthnSignature {
+    /// The compact secp256r1 signature, recovery id first
+    pub compact_signature: FixedBytes<65>,
+    /// The authenticator data
+    pub auth_data: Vec<u8>,
+    /// The client data JSON, which holds the challenge
+    pub client_json: String,
+}
+
+impl Signature {
+    /// The signature type as it appears in the text form.
+    fn type_suffix(&self) -> &'static str {
+        match self {
+            Self::K1(_) => "K1",
+            Self::R1(_) => "R1",
+            Self::WebAuthn(_) => "WA",
+        }
+    }
+}
+
+/// Writes `SIG_<type>_<base58>`, e.g. `SIG_K1_...`.
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let data = match self {
+            Self::K1(sig) | Self::R1(sig) => sig.0.to_vec(),
+            Self::WebAuthn(sig) => sig.pack().map_err(|_| fmt::Error)?,
+        };
+        f.write_str(&to_key_string("SIG_", self.type_suffix(), &data))
+    }
+}
+
+/// Parses `SIG_K1_...`, `SIG_R1_...` and `SIG_WA_...` strings, checking
+/// their checksum.
+impl FromStr for Signature {
+    type Err = ParseKeyError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (suffix, data) = from_key_string("SIG_", value)?;
+        match suffix {
+            "K1" => Ok(Self::K1(fixed_bytes(&data)?)),
+            "R1" => Ok(Self::R1(fixed_bytes(&data)?)),
+            "WA" => WebAuthnSignature::unpack(&data)
+                .map(Self::WebAuthn)
+                .map_err(|_| ParseKeyError::BadData),
+            _ => Err(ParseKeyError::UnknownType),
+        }
+    }
+}
@@ -31,6 +31,12 @@ where
     pub fn get_instance(&self, code: Name, scope: u64) -> Singleton<T> {
         Singleton::new(self, code, scope)
     }
+
+    /// The name of the table holding the singleton.
+    #[inline]
+    pub const fn name(&self) -> Name {
+        self.singleton_name
+    }
 }
 
 pub struct Singleton<T>
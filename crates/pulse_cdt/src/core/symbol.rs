@@ -1,10 +1,19 @@
-use pulse_bytes::{symbol_from_code, symbol_to_code, symbol_to_precision};
+use core::{fmt, str::FromStr};
+
+use pulse_bytes::{symbol_from_code, symbol_to_code, symbol_to_precision, ParseSymbolCodeError};
 use pulse_proc_macro::{NumBytes, Read, Write};
 
-use super::symbol_code::SymbolCode;
+use super::{
+    asset::{fmt_parts, parse_decimal, write_decimal},
+    name::Name,
+    symbol_code::SymbolCode,
+};
+
+/// The largest number of decimals a symbol may have.
+pub const MAX_PRECISION: u8 = 18;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Read, NumBytes, Write, Default)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi = "symbol")]
 pub struct Symbol(u64);
 
 impl Symbol {
@@ -43,4 +52,112 @@ impl Symbol {
     pub fn is_valid(&self) -> bool {
         self.code().is_valid()
     }
+
+    /// Writes the text form of the symbol, e.g. `4,XPR`, in pieces.
+    pub(crate) fn write_parts(&self, mut out: impl FnMut(&str)) {
+        let mut buf = [0_u8; 20];
+        out(write_decimal(u64::from(self.precision()), &mut buf));
+        out(",");
+        self.code().with_str(out);
+    }
+}
+
+/// Writes `<precision>,<code>`, e.g. `4,XPR`.
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_parts(f, |out| self.write_parts(out))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ParseSymbolError {
+    /// The string is not of the form `<precision>,<code>`.
+    BadFormat,
+    /// The precision is larger than [`MAX_PRECISION`].
+    PrecisionTooHigh,
+    /// The code is not a valid symbol code.
+    BadCode(ParseSymbolCodeError),
+}
+
+impl From<ParseSymbolCodeError> for ParseSymbolError {
+    #[inline]
+    fn from(e: ParseSymbolCodeError) -> Self {
+        Self::BadCode(e)
+    }
+}
+
+/// Parses `<precision>,<code>`, e.g. `4,XPR`.
+impl FromStr for Symbol {
+    type Err = ParseSymbolError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (precision, code) = value.split_once(',').ok_or(ParseSymbolError::BadFormat)?;
+        let precision = parse_decimal(precision).ok_or(ParseSymbolError::BadFormat)?;
+        if precision > u64::from(MAX_PRECISION) {
+            return Err(ParseSymbolError::PrecisionTooHigh);
+        }
+        let code = code.parse::<SymbolCode>()?;
+        Ok(Self::new_with_code(precision as u8, code))
+    }
+}
+
+/// A symbol along with the contract that issues it, telling apart tokens
+/// that share a symbol but come from different token contracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Read, NumBytes, Write, Default)]
+#[pulse(crate_path = "pulse_serialization", abi = "extended_symbol")]
+#[cfg_attr(feature = "serde", pulse(serde))]
+pub struct ExtendedSymbol {
+    /// The symbol
+    pub sym: Symbol,
+    /// The token contract issuing the symbol
+    pub contract: Name,
+}
+
+impl ExtendedSymbol {
+    #[inline]
+    #[must_use]
+    pub const fn new(sym: Symbol, contract: Name) -> Self {
+        Self { sym, contract }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn get_symbol(&self) -> Symbol {
+        self.sym
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn get_contract(&self) -> Name {
+        self.contract
+    }
+
+    /// Writes the text form of the symbol, e.g. `4,XPR@eosio.token`, in
+    /// pieces.
+    pub(crate) fn write_parts(&self, mut out: impl FnMut(&str)) {
+        self.sym.write_parts(&mut out);
+        out("@");
+        self.contract.with_str(out);
+    }
+}
+
+impl From<(Symbol, Name)> for ExtendedSymbol {
+    #[inline]
+    fn from((sym, contract): (Symbol, Name)) -> Self {
+        Self::new(sym, contract)
+    }
+}
+
+impl From<ExtendedSymbol> for Symbol {
+    #[inline]
+    fn from(s: ExtendedSymbol) -> Self {
+        s.sym
+    }
+}
+
+/// Writes `<precision>,<code>@<contract>`, e.g. `4,XPR@eosio.token`.
+impl fmt::Display for ExtendedSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_parts(f, |out| self.write_parts(out))
+    }
 }
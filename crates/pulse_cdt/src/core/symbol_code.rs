@@ -1,4 +1,4 @@
-use core::str::FromStr;
+use core::{fmt, str::FromStr};
 
 use pulse_bytes::{symbol_code_from_bytes, symbol_code_to_bytes, ParseSymbolCodeError};
 use pulse_proc_macro::{NumBytes, Read, Write};
@@ -6,7 +6,7 @@ use pulse_proc_macro::{NumBytes, Read, Write};
 #[derive(
     Debug, PartialEq, Eq, Clone, Copy, Default, Read, Write, NumBytes, Hash, PartialOrd, Ord,
 )]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi = "symbol_code")]
 pub struct SymbolCode(u64);
 
 impl From<u64> for SymbolCode {
@@ -39,6 +39,13 @@ impl FromStr for SymbolCode {
     }
 }
 
+/// Writes the code's characters, e.g. `XPR`.
+impl fmt::Display for SymbolCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_str(|s| f.write_str(s))
+    }
+}
+
 impl SymbolCode {
     /// TODO docs
     #[inline]
@@ -68,4 +75,11 @@ impl SymbolCode {
     pub const fn raw(&self) -> u64 {
         self.0
     }
+
+    /// Calls `f` with the code's characters, without the padding.
+    pub(crate) fn with_str<R>(&self, f: impl FnOnce(&str) -> R) -> R {
+        let chars = symbol_code_to_bytes(self.0);
+        let len = chars.iter().position(|&c| c == b' ').unwrap_or(chars.len());
+        f(core::str::from_utf8(&chars[..len]).unwrap_or(""))
+    }
 }
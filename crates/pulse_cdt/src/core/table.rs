@@ -3,8 +3,11 @@ use core::{
 };
 
 use crate::{
-    contracts::{db_find_i64, db_get_i64, db_remove_i64, db_store_i64, db_update_i64},
-    core::{check, name::Name},
+    contracts::{
+        db_end_i64, db_find_i64, db_get_i64, db_lowerbound_i64, db_next_i64, db_previous_i64,
+        db_remove_i64, db_store_i64, db_update_i64, db_upperbound_i64,
+    },
+    core::{check, name::Name, SecondaryIndex, TableIndex},
     DataStream, NumBytes, Read, ReadError, Write, WriteError,
 };
 use alloc::vec;
@@ -34,6 +37,12 @@ where
     pub const fn index(&self, code: Name, scope: u64) -> MultiIndex<T> {
         MultiIndex::new(code, scope, self.table)
     }
+
+    /// The name of the table.
+    #[inline]
+    pub const fn name(&self) -> Name {
+        self.table
+    }
 }
 
 #[derive(Clone)]
@@ -72,6 +81,40 @@ where
         }
     }
 
+    /// Returns the first row of the table, or `end()` if it is empty.
+    #[inline]
+    pub fn begin(&self) -> ConstIterator<T> {
+        self.lower_bound(0)
+    }
+
+    /// Returns the first row whose primary key is not less than `key`.
+    #[inline]
+    pub fn lower_bound(&self, key: u64) -> ConstIterator<T> {
+        self.at(db_lowerbound_i64(self.code, self.scope, self.table, key))
+    }
+
+    /// Returns the first row whose primary key is greater than `key`.
+    #[inline]
+    pub fn upper_bound(&self, key: u64) -> ConstIterator<T> {
+        self.at(db_upperbound_i64(self.code, self.scope, self.table, key))
+    }
+
+    /// Iterates over all rows in primary key order.
+    #[inline]
+    pub fn iter(&self) -> MultiIndexIter<T> {
+        self.begin().into_iter()
+    }
+
+    #[inline]
+    fn at(&self, itr: i32) -> ConstIterator<T> {
+        if itr < 0 {
+            self.end()
+        } else {
+            let item = self.load_object_by_primary_iterator(itr);
+            ConstIterator::new(self.clone(), Some(Item::new(self.clone(), itr, item)))
+        }
+    }
+
     #[inline]
     pub fn get(&self, key: u64, error_msg: &str) -> ConstIterator<T> {
         let result = self.find(key);
@@ -81,7 +124,7 @@ where
 
     #[inline]
     pub fn load_object_by_primary_iterator(&self, itr: i32) -> T::Row {
-        let size = db_get_i64(itr, &[], 0);
+        let size = db_get_i64(itr, &mut [], 0);
         check(size >= 0, "error reading iterator");
 
         let mut buffer = vec![0_u8; size as usize];
@@ -106,6 +149,7 @@ where
             &bytes[..],
             pos as u32,
         );
+        T::store_secondary_keys(self.table, self.scope, payer, item);
         ConstIterator::new(
             self.clone(),
             Some(Item::new(self.clone(), itr, item.clone())),
@@ -118,7 +162,12 @@ where
         F: FnOnce(&mut T::Row),
     {
         let item = item.borrow_mut();
+        let pk: u64 = T::primary_key(item).into();
         modifier(item);
+        check(
+            T::primary_key(item).into() == pk,
+            "updater cannot change primary key when modifying an object",
+        );
         let size = item.num_bytes();
         let mut bytes = vec![0_u8; size];
         let mut pos = 0;
@@ -126,11 +175,40 @@ where
             .expect("failed to write item");
         #[allow(clippy::cast_possible_truncation)]
         db_update_i64(item.primary_itr, payer, &bytes, pos as u32);
+        T::update_secondary_keys(self.code, self.table, self.scope, payer, item);
     }
 
+    /// Removes the row and returns an iterator to the one after it.
     #[inline]
-    pub fn erase(&self, item: ConstIterator<T>) {
+    pub fn erase(&self, item: ConstIterator<T>) -> ConstIterator<T> {
+        let next = item.next();
+        T::remove_secondary_keys(self.code, self.table, self.scope, &item);
         db_remove_i64(item.primary_itr);
+        next
+    }
+
+    /// Returns the secondary index declared at position `N` of the table.
+    #[inline]
+    pub fn get_index<const N: usize>(&self) -> SecondaryIndex<T, N>
+    where
+        T: TableIndex<N>,
+    {
+        SecondaryIndex::new(self.clone())
+    }
+
+    #[inline]
+    pub const fn code(&self) -> Name {
+        self.code
+    }
+
+    #[inline]
+    pub const fn scope(&self) -> u64 {
+        self.scope
+    }
+
+    #[inline]
+    pub const fn table(&self) -> Name {
+        self.table
     }
 
     pub fn end(&self) -> ConstIterator<T> {
@@ -154,6 +232,18 @@ pub trait Table: Sized + Clone + PartialEq {
     type Row: Read + Write + NumBytes + Sized + PartialEq + Clone;
     /// TODO docs
     fn primary_key(row: &Self::Row) -> Self::Key;
+
+    /// Stores the secondary keys of a newly emplaced row.
+    #[inline]
+    fn store_secondary_keys(_table: Name, _scope: u64, _payer: Name, _row: &Self::Row) {}
+
+    /// Brings the secondary keys of a modified row up to date.
+    #[inline]
+    fn update_secondary_keys(_code: Name, _table: Name, _scope: u64, _payer: Name, _row: &Self::Row) {}
+
+    /// Removes the secondary keys of a row about to be erased.
+    #[inline]
+    fn remove_secondary_keys(_code: Name, _table: Name, _scope: u64, _row: &Self::Row) {}
 }
 
 pub trait TableCursor<T>: IntoIterator
@@ -196,6 +286,7 @@ where
         F: FnOnce(&mut T::Row);
 }
 
+#[derive(Clone)]
 pub struct ConstIterator<T>
 where
     T: Table,
@@ -216,6 +307,111 @@ where
     pub fn value(&self) -> T::Row {
         self.item.as_ref().expect("iterator is empty").inner.clone()
     }
+
+    /// Returns the row after this one, or `end()` past the last row.
+    #[inline]
+    pub fn next(&self) -> Self {
+        let itr = match &self.item {
+            Some(item) => item.primary_itr,
+            None => {
+                check(false, "cannot increment end iterator");
+                return self.idx.end();
+            }
+        };
+        let mut pk = 0_u64;
+        self.idx.at(db_next_i64(itr, &mut pk))
+    }
+
+    /// Returns the row before this one; from `end()` this is the last row.
+    #[inline]
+    pub fn previous(&self) -> Self {
+        let itr = match &self.item {
+            Some(item) => item.primary_itr,
+            None => db_end_i64(self.idx.code, self.idx.scope, self.idx.table),
+        };
+        let mut pk = 0_u64;
+        let prev = if itr == -1 { -1 } else { db_previous_i64(itr, &mut pk) };
+        check(prev >= 0, "cannot decrement iterator at beginning of table");
+        self.idx.at(prev)
+    }
+
+    #[inline]
+    fn itr(&self) -> Option<i32> {
+        self.item.as_ref().map(|item| item.primary_itr)
+    }
+}
+
+impl<T> IntoIterator for ConstIterator<T>
+where
+    T: Table,
+{
+    type Item = ConstIterator<T>;
+    type IntoIter = MultiIndexIter<T>;
+
+    /// Iterates from this row to the last row of the table.
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        let back = match self.itr() {
+            Some(_) => self.idx.end().previous(),
+            None => self.idx.end(),
+        };
+        MultiIndexIter {
+            done: self.item.is_none(),
+            front: self,
+            back,
+        }
+    }
+}
+
+/// Rows of a [`MultiIndex`] between two positions, in primary key order.
+///
+/// Both ends move past a row before yielding it, so the yielded iterator can
+/// be erased without invalidating the walk.
+pub struct MultiIndexIter<T>
+where
+    T: Table,
+{
+    front: ConstIterator<T>,
+    back: ConstIterator<T>,
+    done: bool,
+}
+
+impl<T> Iterator for MultiIndexIter<T>
+where
+    T: Table,
+{
+    type Item = ConstIterator<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.front.itr() == self.back.itr() {
+            self.done = true;
+            return Some(self.front.clone());
+        }
+        let next = self.front.next();
+        Some(core::mem::replace(&mut self.front, next))
+    }
+}
+
+impl<T> DoubleEndedIterator for MultiIndexIter<T>
+where
+    T: Table,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.front.itr() == self.back.itr() {
+            self.done = true;
+            return Some(self.back.clone());
+        }
+        let previous = self.back.previous();
+        Some(core::mem::replace(&mut self.back, previous))
+    }
 }
 
 impl<T> core::ops::DerefMut for ConstIterator<T>
@@ -342,4 +538,72 @@ mod tests {
         let end_iterator2 = ConstIterator::new(idx, None);
         assert!(end_iterator == end_iterator2, "end iterators should be equal");
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "testing")]
+    fn keys(rows: impl Iterator<Item = ConstIterator<TestTable>>) -> alloc::vec::Vec<u64> {
+        rows.map(|row| row.a).collect()
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    pub fn test_iteration() {
+        use crate::testing::{with_receiver, TestChain};
+
+        let _chain = TestChain::new();
+        let code = Name::new(1);
+        with_receiver(code, || {
+            let idx = MultiIndex::<TestTable>::new(code, 0, Name::new(1));
+            assert!(idx.begin() == idx.end());
+            assert_eq!(keys(idx.iter()), [0_u64; 0]);
+
+            for a in [5, 1, 3, 9] {
+                idx.emplace(code, TestTable { a });
+            }
+
+            assert_eq!(keys(idx.iter()), [1, 3, 5, 9]);
+            assert_eq!(keys(idx.iter().rev()), [9, 5, 3, 1]);
+            assert_eq!(keys(idx.lower_bound(3).into_iter()), [3, 5, 9]);
+            assert_eq!(keys(idx.upper_bound(3).into_iter()), [5, 9]);
+            assert!(idx.upper_bound(9) == idx.end());
+            assert_eq!(idx.end().previous().a, 9);
+
+            let mut both = idx.iter();
+            assert_eq!(both.next().map(|r| r.a), Some(1));
+            assert_eq!(both.next_back().map(|r| r.a), Some(9));
+            assert_eq!(keys(both), [3, 5]);
+
+            let next = idx.erase(idx.find(3));
+            assert_eq!(next.a, 5);
+            for row in idx.iter() {
+                if row.a < 9 {
+                    idx.erase(row);
+                }
+            }
+            assert_eq!(keys(idx.iter()), [9]);
+        });
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    #[should_panic(expected = "dereference of deleted object")]
+    pub fn test_step_after_last_row_removed() {
+        use crate::testing::{with_receiver, TestChain};
+
+        let _chain = TestChain::new();
+        let code = Name::new(1);
+        with_receiver(code, || {
+            let idx = MultiIndex::<TestTable>::new(code, 0, Name::new(1));
+            idx.emplace(code, TestTable { a: 1 });
+            let stale = idx.find(1);
+
+            // A fresh action's iterators don't know about `stale`, so removing
+            // the row there leaves it pointing into a table that is gone.
+            with_receiver(code, || {
+                let idx = MultiIndex::<TestTable>::new(code, 0, Name::new(1));
+                idx.erase(idx.find(1));
+            });
+
+            stale.next();
+        });
+    }
+}
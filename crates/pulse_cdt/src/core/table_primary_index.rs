@@ -40,7 +40,7 @@ where
     #[inline]
     fn bytes(&self) -> Vec<u8> {
         let nullptr: *mut c_void = null_mut() as *mut _ as *mut c_void;
-        let size = db_get_i64(self.value, &[], 0);
+        let size = db_get_i64(self.value, &mut [], 0);
         #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
         let mut bytes = vec![0u8; size as usize];
         #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
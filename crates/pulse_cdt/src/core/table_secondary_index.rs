@@ -0,0 +1,711 @@
+use crate::{
+    contracts::{
+        db_idx128_end, db_idx128_find_primary, db_idx128_find_secondary, db_idx128_lowerbound,
+        db_idx128_next, db_idx128_previous, db_idx128_remove, db_idx128_store, db_idx128_update,
+        db_idx128_upperbound, db_idx256_end, db_idx256_find_primary, db_idx256_find_secondary,
+        db_idx256_lowerbound, db_idx256_next, db_idx256_previous, db_idx256_remove,
+        db_idx256_store, db_idx256_update, db_idx256_upperbound, db_idx64_end,
+        db_idx64_find_primary, db_idx64_find_secondary, db_idx64_lowerbound, db_idx64_next,
+        db_idx64_previous, db_idx64_remove, db_idx64_store, db_idx64_update,
+        db_idx64_upperbound, db_idx_double_end, db_idx_double_find_primary,
+        db_idx_double_find_secondary, db_idx_double_lowerbound, db_idx_double_next,
+        db_idx_double_previous, db_idx_double_remove, db_idx_double_store,
+        db_idx_double_update, db_idx_double_upperbound,
+    },
+    core::{check, Checksum256, ConstIterator, FixedBytes, MultiIndex, Name, Table},
+};
+
+/// A key type backed by one of the chain's secondary index tables.
+pub trait SecondaryKey: Copy + PartialEq {
+    /// The smallest key, where iteration from `begin` starts.
+    const LOWEST: Self;
+
+    fn db_idx_store(scope: u64, table: Name, payer: Name, id: u64, key: &Self) -> i32;
+
+    fn db_idx_update(iterator: i32, payer: Name, key: &Self);
+
+    fn db_idx_remove(iterator: i32);
+
+    fn db_idx_find_secondary(code: Name, scope: u64, table: Name, key: &Self, primary: &mut u64) -> i32;
+
+    fn db_idx_find_primary(code: Name, scope: u64, table: Name, key: &mut Self, primary: u64) -> i32;
+
+    fn db_idx_lowerbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32;
+
+    fn db_idx_upperbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32;
+
+    fn db_idx_end(code: Name, scope: u64, table: Name) -> i32;
+
+    fn db_idx_next(iterator: i32, primary: &mut u64) -> i32;
+
+    fn db_idx_previous(iterator: i32, primary: &mut u64) -> i32;
+}
+
+impl SecondaryKey for u64 {
+    const LOWEST: Self = 0;
+
+    #[inline]
+    fn db_idx_store(scope: u64, table: Name, payer: Name, id: u64, key: &Self) -> i32 {
+        db_idx64_store(scope, table, payer, id, key)
+    }
+
+    #[inline]
+    fn db_idx_update(iterator: i32, payer: Name, key: &Self) {
+        db_idx64_update(iterator, payer, key)
+    }
+
+    #[inline]
+    fn db_idx_remove(iterator: i32) {
+        db_idx64_remove(iterator)
+    }
+
+    #[inline]
+    fn db_idx_find_secondary(code: Name, scope: u64, table: Name, key: &Self, primary: &mut u64) -> i32 {
+        db_idx64_find_secondary(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_find_primary(code: Name, scope: u64, table: Name, key: &mut Self, primary: u64) -> i32 {
+        db_idx64_find_primary(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_lowerbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32 {
+        db_idx64_lowerbound(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_upperbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32 {
+        db_idx64_upperbound(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_end(code: Name, scope: u64, table: Name) -> i32 {
+        db_idx64_end(code, scope, table)
+    }
+
+    #[inline]
+    fn db_idx_next(iterator: i32, primary: &mut u64) -> i32 {
+        db_idx64_next(iterator, primary)
+    }
+
+    #[inline]
+    fn db_idx_previous(iterator: i32, primary: &mut u64) -> i32 {
+        db_idx64_previous(iterator, primary)
+    }
+}
+
+impl SecondaryKey for u128 {
+    const LOWEST: Self = 0;
+
+    #[inline]
+    fn db_idx_store(scope: u64, table: Name, payer: Name, id: u64, key: &Self) -> i32 {
+        db_idx128_store(scope, table, payer, id, key)
+    }
+
+    #[inline]
+    fn db_idx_update(iterator: i32, payer: Name, key: &Self) {
+        db_idx128_update(iterator, payer, key)
+    }
+
+    #[inline]
+    fn db_idx_remove(iterator: i32) {
+        db_idx128_remove(iterator)
+    }
+
+    #[inline]
+    fn db_idx_find_secondary(code: Name, scope: u64, table: Name, key: &Self, primary: &mut u64) -> i32 {
+        db_idx128_find_secondary(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_find_primary(code: Name, scope: u64, table: Name, key: &mut Self, primary: u64) -> i32 {
+        db_idx128_find_primary(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_lowerbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32 {
+        db_idx128_lowerbound(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_upperbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32 {
+        db_idx128_upperbound(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_end(code: Name, scope: u64, table: Name) -> i32 {
+        db_idx128_end(code, scope, table)
+    }
+
+    #[inline]
+    fn db_idx_next(iterator: i32, primary: &mut u64) -> i32 {
+        db_idx128_next(iterator, primary)
+    }
+
+    #[inline]
+    fn db_idx_previous(iterator: i32, primary: &mut u64) -> i32 {
+        db_idx128_previous(iterator, primary)
+    }
+}
+
+/// Stored in the idx256 table as two 128-bit words, most significant byte
+/// first, so that word order matches byte order.
+impl SecondaryKey for Checksum256 {
+    const LOWEST: Self = FixedBytes([0; 32]);
+
+    #[inline]
+    fn db_idx_store(scope: u64, table: Name, payer: Name, id: u64, key: &Self) -> i32 {
+        db_idx256_store(scope, table, payer, id, &to_words(key))
+    }
+
+    #[inline]
+    fn db_idx_update(iterator: i32, payer: Name, key: &Self) {
+        db_idx256_update(iterator, payer, &to_words(key))
+    }
+
+    #[inline]
+    fn db_idx_remove(iterator: i32) {
+        db_idx256_remove(iterator)
+    }
+
+    #[inline]
+    fn db_idx_find_secondary(code: Name, scope: u64, table: Name, key: &Self, primary: &mut u64) -> i32 {
+        db_idx256_find_secondary(code, scope, table, &to_words(key), primary)
+    }
+
+    #[inline]
+    fn db_idx_find_primary(code: Name, scope: u64, table: Name, key: &mut Self, primary: u64) -> i32 {
+        let mut words = to_words(key);
+        let itr = db_idx256_find_primary(code, scope, table, &mut words, primary);
+        *key = from_words(&words);
+        itr
+    }
+
+    #[inline]
+    fn db_idx_lowerbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32 {
+        let mut words = to_words(key);
+        let itr = db_idx256_lowerbound(code, scope, table, &mut words, primary);
+        *key = from_words(&words);
+        itr
+    }
+
+    #[inline]
+    fn db_idx_upperbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32 {
+        let mut words = to_words(key);
+        let itr = db_idx256_upperbound(code, scope, table, &mut words, primary);
+        *key = from_words(&words);
+        itr
+    }
+
+    #[inline]
+    fn db_idx_end(code: Name, scope: u64, table: Name) -> i32 {
+        db_idx256_end(code, scope, table)
+    }
+
+    #[inline]
+    fn db_idx_next(iterator: i32, primary: &mut u64) -> i32 {
+        db_idx256_next(iterator, primary)
+    }
+
+    #[inline]
+    fn db_idx_previous(iterator: i32, primary: &mut u64) -> i32 {
+        db_idx256_previous(iterator, primary)
+    }
+}
+
+#[inline]
+fn to_words(key: &Checksum256) -> [u128; 2] {
+    let mut hi = [0_u8; 16];
+    let mut lo = [0_u8; 16];
+    hi.copy_from_slice(&key.0[..16]);
+    lo.copy_from_slice(&key.0[16..]);
+    [u128::from_be_bytes(hi), u128::from_be_bytes(lo)]
+}
+
+#[inline]
+fn from_words(words: &[u128; 2]) -> Checksum256 {
+    let mut bytes = [0_u8; 32];
+    bytes[..16].copy_from_slice(&words[0].to_be_bytes());
+    bytes[16..].copy_from_slice(&words[1].to_be_bytes());
+    FixedBytes(bytes)
+}
+
+impl SecondaryKey for f64 {
+    const LOWEST: Self = f64::NEG_INFINITY;
+
+    #[inline]
+    fn db_idx_store(scope: u64, table: Name, payer: Name, id: u64, key: &Self) -> i32 {
+        db_idx_double_store(scope, table, payer, id, key)
+    }
+
+    #[inline]
+    fn db_idx_update(iterator: i32, payer: Name, key: &Self) {
+        db_idx_double_update(iterator, payer, key)
+    }
+
+    #[inline]
+    fn db_idx_remove(iterator: i32) {
+        db_idx_double_remove(iterator)
+    }
+
+    #[inline]
+    fn db_idx_find_secondary(code: Name, scope: u64, table: Name, key: &Self, primary: &mut u64) -> i32 {
+        db_idx_double_find_secondary(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_find_primary(code: Name, scope: u64, table: Name, key: &mut Self, primary: u64) -> i32 {
+        db_idx_double_find_primary(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_lowerbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32 {
+        db_idx_double_lowerbound(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_upperbound(code: Name, scope: u64, table: Name, key: &mut Self, primary: &mut u64) -> i32 {
+        db_idx_double_upperbound(code, scope, table, key, primary)
+    }
+
+    #[inline]
+    fn db_idx_end(code: Name, scope: u64, table: Name) -> i32 {
+        db_idx_double_end(code, scope, table)
+    }
+
+    #[inline]
+    fn db_idx_next(iterator: i32, primary: &mut u64) -> i32 {
+        db_idx_double_next(iterator, primary)
+    }
+
+    #[inline]
+    fn db_idx_previous(iterator: i32, primary: &mut u64) -> i32 {
+        db_idx_double_previous(iterator, primary)
+    }
+}
+
+/// Secondary index `N` of a table, numbered in declaration order.
+///
+/// Implemented by `#[table(secondary_key(<type>) = <expr>)]`.
+pub trait TableIndex<const N: usize>: Table {
+    type Secondary: SecondaryKey;
+
+    fn secondary_key(row: &Self::Row) -> Self::Secondary;
+}
+
+/// Name of the chain table holding secondary index `index` of `table`.
+#[inline]
+pub const fn secondary_index_table(table: Name, index: usize) -> Name {
+    Name::new((table.raw() & 0xFFFF_FFFF_FFFF_FFF0) | (index as u64 & 0x0F))
+}
+
+#[inline]
+pub fn store_secondary_key<T, const N: usize>(table: Name, scope: u64, payer: Name, row: &T::Row)
+where
+    T: TableIndex<N>,
+{
+    let key = T::secondary_key(row);
+    T::Secondary::db_idx_store(
+        scope,
+        secondary_index_table(table, N),
+        payer,
+        T::primary_key(row).into(),
+        &key,
+    );
+}
+
+#[inline]
+pub fn update_secondary_key<T, const N: usize>(code: Name, table: Name, scope: u64, payer: Name, row: &T::Row)
+where
+    T: TableIndex<N>,
+{
+    let key = T::secondary_key(row);
+    let mut current = T::Secondary::LOWEST;
+    let itr = T::Secondary::db_idx_find_primary(
+        code,
+        scope,
+        secondary_index_table(table, N),
+        &mut current,
+        T::primary_key(row).into(),
+    );
+    check(itr >= 0, "secondary index entry not found");
+    if current != key {
+        T::Secondary::db_idx_update(itr, payer, &key);
+    }
+}
+
+#[inline]
+pub fn remove_secondary_key<T, const N: usize>(code: Name, table: Name, scope: u64, row: &T::Row)
+where
+    T: TableIndex<N>,
+{
+    let mut current = T::Secondary::LOWEST;
+    let itr = T::Secondary::db_idx_find_primary(
+        code,
+        scope,
+        secondary_index_table(table, N),
+        &mut current,
+        T::primary_key(row).into(),
+    );
+    check(itr >= 0, "secondary index entry not found");
+    T::Secondary::db_idx_remove(itr);
+}
+
+/// Rows of a [`MultiIndex`] ordered by one of its secondary keys.
+#[derive(Clone)]
+pub struct SecondaryIndex<T, const N: usize>
+where
+    T: TableIndex<N>,
+{
+    idx: MultiIndex<T>,
+}
+
+impl<T, const N: usize> SecondaryIndex<T, N>
+where
+    T: TableIndex<N>,
+{
+    #[inline]
+    pub const fn new(idx: MultiIndex<T>) -> Self {
+        Self { idx }
+    }
+
+    #[inline]
+    fn index_table(&self) -> Name {
+        secondary_index_table(self.idx.table(), N)
+    }
+
+    /// Positions on the first row whose key equals `key`.
+    #[inline]
+    pub fn find(&self, key: T::Secondary) -> SecondaryIterator<T, N> {
+        let mut primary = 0_u64;
+        let itr = T::Secondary::db_idx_find_secondary(self.idx.code(), self.idx.scope(), self.index_table(), &key, &mut primary);
+        SecondaryIterator::new(self.clone(), itr, primary)
+    }
+
+    /// Loads the first row whose key equals `key`, aborting with `error_msg` if there is none.
+    #[inline]
+    pub fn get(&self, key: T::Secondary, error_msg: &str) -> ConstIterator<T> {
+        let itr = self.find(key);
+        check(itr != self.end(), error_msg);
+        itr.row()
+    }
+
+    /// Positions on the first row whose key is not less than `key`.
+    #[inline]
+    pub fn lower_bound(&self, key: T::Secondary) -> SecondaryIterator<T, N> {
+        let mut key = key;
+        let mut primary = 0_u64;
+        let itr = T::Secondary::db_idx_lowerbound(self.idx.code(), self.idx.scope(), self.index_table(), &mut key, &mut primary);
+        SecondaryIterator::new(self.clone(), itr, primary)
+    }
+
+    /// Positions on the first row whose key is greater than `key`.
+    #[inline]
+    pub fn upper_bound(&self, key: T::Secondary) -> SecondaryIterator<T, N> {
+        let mut key = key;
+        let mut primary = 0_u64;
+        let itr = T::Secondary::db_idx_upperbound(self.idx.code(), self.idx.scope(), self.index_table(), &mut key, &mut primary);
+        SecondaryIterator::new(self.clone(), itr, primary)
+    }
+
+    #[inline]
+    pub fn begin(&self) -> SecondaryIterator<T, N> {
+        self.lower_bound(T::Secondary::LOWEST)
+    }
+
+    #[inline]
+    pub fn end(&self) -> SecondaryIterator<T, N> {
+        let itr = T::Secondary::db_idx_end(self.idx.code(), self.idx.scope(), self.index_table());
+        SecondaryIterator::new(self.clone(), itr, 0)
+    }
+
+    /// Iterates over all rows in key order.
+    #[inline]
+    pub fn iter(&self) -> SecondaryIterator<T, N> {
+        self.begin()
+    }
+}
+
+impl<T, const N: usize> PartialEq for SecondaryIndex<T, N>
+where
+    T: TableIndex<N>,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.idx == other.idx
+    }
+}
+
+/// A position in a [`SecondaryIndex`].
+///
+/// Iterating yields the rows from this position onwards as primary
+/// [`ConstIterator`]s, which can be passed to `modify` and `erase`.
+#[derive(Clone)]
+pub struct SecondaryIterator<T, const N: usize>
+where
+    T: TableIndex<N>,
+{
+    index: SecondaryIndex<T, N>,
+    itr: i32,
+    primary: u64,
+}
+
+impl<T, const N: usize> SecondaryIterator<T, N>
+where
+    T: TableIndex<N>,
+{
+    #[inline]
+    const fn new(index: SecondaryIndex<T, N>, itr: i32, primary: u64) -> Self {
+        Self { index, itr, primary }
+    }
+
+    #[inline]
+    pub const fn is_end(&self) -> bool {
+        self.itr < 0
+    }
+
+    #[inline]
+    pub fn primary_key(&self) -> u64 {
+        check(!self.is_end(), "cannot dereference end iterator");
+        self.primary
+    }
+
+    /// Loads the row at this position.
+    #[inline]
+    pub fn row(&self) -> ConstIterator<T> {
+        self.index.idx.get(self.primary_key(), "secondary index points to a missing row")
+    }
+}
+
+impl<T, const N: usize> Iterator for SecondaryIterator<T, N>
+where
+    T: TableIndex<N>,
+{
+    type Item = ConstIterator<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_end() {
+            return None;
+        }
+        let row = self.row();
+        self.itr = T::Secondary::db_idx_next(self.itr, &mut self.primary);
+        Some(row)
+    }
+}
+
+impl<T, const N: usize> PartialEq for SecondaryIterator<T, N>
+where
+    T: TableIndex<N>,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+            && match (self.is_end(), other.is_end()) {
+                (true, true) => true,
+                (false, false) => self.itr == other.itr,
+                _ => false,
+            }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{remove_secondary_key, store_secondary_key, update_secondary_key, TableIndex};
+    use crate::{
+        core::{Checksum256, FixedBytes, MultiIndexDefinition, Name, Table},
+        name, table,
+        testing::{with_receiver, TestChain},
+        NumBytes, Read, Write,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Read, Write, NumBytes)]
+    #[pulse(crate_path = "pulse_serialization")]
+    struct Vote {
+        id: u64,
+        weight: u64,
+    }
+
+    impl Table for Vote {
+        type Key = u64;
+        type Row = Self;
+
+        fn primary_key(row: &Self::Row) -> u64 {
+            row.id
+        }
+
+        fn store_secondary_keys(table: Name, scope: u64, payer: Name, row: &Self::Row) {
+            store_secondary_key::<Self, 0>(table, scope, payer, row);
+        }
+
+        fn update_secondary_keys(code: Name, table: Name, scope: u64, payer: Name, row: &Self::Row) {
+            update_secondary_key::<Self, 0>(code, table, scope, payer, row);
+        }
+
+        fn remove_secondary_keys(code: Name, table: Name, scope: u64, row: &Self::Row) {
+            remove_secondary_key::<Self, 0>(code, table, scope, row);
+        }
+    }
+
+    impl TableIndex<0> for Vote {
+        type Secondary = u64;
+
+        fn secondary_key(row: &Self::Row) -> u64 {
+            row.weight
+        }
+    }
+
+    const VOTES: MultiIndexDefinition<Vote> = MultiIndexDefinition::new(Name::new(0x1000));
+    const CODE: Name = Name::new(42);
+
+    fn ids(rows: impl Iterator<Item = crate::core::ConstIterator<Vote>>) -> Vec<u64> {
+        rows.map(|row| row.id).collect()
+    }
+
+    #[test]
+    fn secondary_index_tracks_rows() {
+        let _chain = TestChain::new();
+        with_receiver(CODE, || {
+            let table = VOTES.index(CODE, 0);
+            table.emplace(CODE, Vote { id: 1, weight: 30 });
+            table.emplace(CODE, Vote { id: 2, weight: 10 });
+            table.emplace(CODE, Vote { id: 3, weight: 20 });
+
+            let by_weight = table.get_index::<0>();
+            assert_eq!(ids(by_weight.iter()), [2, 3, 1]);
+            assert_eq!(by_weight.find(20).primary_key(), 3);
+            assert!(by_weight.find(25) == by_weight.end());
+            assert_eq!(ids(by_weight.lower_bound(15)), [3, 1]);
+            assert_eq!(ids(by_weight.upper_bound(20)), [1]);
+
+            let mut row = table.find(2);
+            table.modify(&mut row, CODE, |v| v.weight = 40);
+            assert_eq!(ids(by_weight.iter()), [3, 1, 2]);
+
+            table.erase(table.find(3));
+            assert_eq!(ids(by_weight.iter()), [1, 2]);
+            assert!(by_weight.find(20) == by_weight.end());
+        });
+    }
+
+    #[derive(Debug, Clone, PartialEq, Read, Write, NumBytes)]
+    #[pulse(crate_path = "pulse_serialization")]
+    struct Proposal {
+        id: u64,
+        pair: u128,
+        hash: Checksum256,
+        weight: f64,
+    }
+
+    impl Table for Proposal {
+        type Key = u64;
+        type Row = Self;
+
+        fn primary_key(row: &Self::Row) -> u64 {
+            row.id
+        }
+
+        fn store_secondary_keys(table: Name, scope: u64, payer: Name, row: &Self::Row) {
+            store_secondary_key::<Self, 0>(table, scope, payer, row);
+            store_secondary_key::<Self, 1>(table, scope, payer, row);
+            store_secondary_key::<Self, 2>(table, scope, payer, row);
+        }
+    }
+
+    impl TableIndex<0> for Proposal {
+        type Secondary = u128;
+
+        fn secondary_key(row: &Self::Row) -> u128 {
+            row.pair
+        }
+    }
+
+    impl TableIndex<1> for Proposal {
+        type Secondary = Checksum256;
+
+        fn secondary_key(row: &Self::Row) -> Checksum256 {
+            row.hash
+        }
+    }
+
+    impl TableIndex<2> for Proposal {
+        type Secondary = f64;
+
+        fn secondary_key(row: &Self::Row) -> f64 {
+            row.weight
+        }
+    }
+
+    #[test]
+    fn wide_and_floating_point_keys() {
+        let _chain = TestChain::new();
+        with_receiver(CODE, || {
+            let table = MultiIndexDefinition::<Proposal>::new(Name::new(0x2000)).index(CODE, 0);
+            let hash = |first: u8, last: u8| {
+                let mut bytes = [0_u8; 32];
+                bytes[0] = first;
+                bytes[31] = last;
+                FixedBytes(bytes)
+            };
+            table.emplace(CODE, Proposal { id: 1, pair: u128::MAX, hash: hash(2, 0), weight: 1.5 });
+            table.emplace(CODE, Proposal { id: 2, pair: 1 << 64, hash: hash(1, 9), weight: -3.0 });
+            table.emplace(CODE, Proposal { id: 3, pair: 7, hash: hash(1, 1), weight: 0.25 });
+
+            let ids = |rows: super::SecondaryIterator<Proposal, 0>| rows.map(|r| r.id).collect::<Vec<_>>();
+            assert_eq!(ids(table.get_index::<0>().iter()), [3, 2, 1]);
+            assert_eq!(ids(table.get_index::<0>().upper_bound(7)), [2, 1]);
+
+            let by_hash = table.get_index::<1>();
+            assert_eq!(by_hash.iter().map(|r| r.id).collect::<Vec<_>>(), [3, 2, 1]);
+            assert_eq!(by_hash.find(hash(1, 9)).primary_key(), 2);
+
+            let by_weight = table.get_index::<2>();
+            assert_eq!(by_weight.iter().map(|r| r.id).collect::<Vec<_>>(), [2, 3, 1]);
+            assert_eq!(by_weight.lower_bound(0.0).primary_key(), 3);
+        });
+    }
+
+    #[derive(Debug, Clone, PartialEq, Read, Write, NumBytes)]
+    #[pulse(crate_path = "pulse_serialization")]
+    #[table(
+        primary_key = row.id,
+        secondary_key(u64) = row.owner.raw(),
+        secondary_key(Checksum256) = row.hash
+    )]
+    struct Ballot {
+        id: u64,
+        owner: Name,
+        hash: Checksum256,
+    }
+
+    #[test]
+    fn table_attribute_declares_indexes() {
+        let _chain = TestChain::new();
+        with_receiver(CODE, || {
+            let table = MultiIndexDefinition::<Ballot>::new(Name::new(0x3000)).index(CODE, 0);
+            let hash = |byte: u8| FixedBytes([byte; 32]);
+            table.emplace(CODE, Ballot { id: 1, owner: name!("carol"), hash: hash(3) });
+            table.emplace(CODE, Ballot { id: 2, owner: name!("alice"), hash: hash(1) });
+            table.emplace(CODE, Ballot { id: 3, owner: name!("bob"), hash: hash(2) });
+
+            let by_owner = table.get_index::<0>();
+            assert_eq!(by_owner.iter().map(|r| r.id).collect::<Vec<_>>(), [2, 3, 1]);
+            assert_eq!(by_owner.find(name!("bob").raw()).primary_key(), 3);
+
+            let by_hash = table.get_index::<1>();
+            assert_eq!(by_hash.iter().map(|r| r.id).collect::<Vec<_>>(), [2, 3, 1]);
+
+            let mut row = table.find(2);
+            table.modify(&mut row, CODE, |b| b.hash = hash(4));
+            assert_eq!(by_hash.iter().map(|r| r.id).collect::<Vec<_>>(), [3, 1, 2]);
+            assert_eq!(by_hash.find(hash(4)).primary_key(), 2);
+
+            table.erase(table.find(3));
+            assert!(by_owner.find(name!("bob").raw()) == by_owner.end());
+            assert_eq!(by_hash.iter().map(|r| r.id).collect::<Vec<_>>(), [1, 2]);
+        });
+    }
+}
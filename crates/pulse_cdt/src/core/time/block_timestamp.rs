@@ -1,11 +1,13 @@
+use core::{fmt, str::FromStr};
+
 use pulse_proc_macro::{NumBytes, Read, Write};
 
-use crate::core::{milliseconds, TimePoint, TimePointSec};
+use crate::core::{milliseconds, time::iso8601, ParseTimeError, TimePoint, TimePointSec};
 
 #[derive(
     Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Read, Write, NumBytes,
 )]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi = "block_timestamp_type")]
 pub struct BlockTimestamp {
     pub slot: u32,
 }
@@ -71,3 +73,23 @@ impl From<TimePointSec> for BlockTimestamp {
         BlockTimestamp { slot }
     }
 }
+
+/// Writes the slot's time like [`TimePoint`] does, e.g.
+/// `2000-01-01T00:00:00.500`.
+impl fmt::Display for BlockTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_time_point(), f)
+    }
+}
+
+/// Parses the forms [`TimePoint`] does, rounding down to the slot.
+impl FromStr for BlockTimestamp {
+    type Err = ParseTimeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let millis = iso8601::parse(value)?.div_euclid(1_000) - Self::BLOCK_TIMESTAMP_EPOCH;
+        u32::try_from(millis.div_euclid(i64::from(Self::BLOCK_INTERVAL_MS)))
+            .map(Self::new)
+            .map_err(|_| ParseTimeError::OutOfRange)
+    }
+}
@@ -0,0 +1,167 @@
+//! The `YYYY-MM-DDTHH:MM:SS[.mmm]` form of time points, in UTC, as the node
+//! writes them.
+
+use core::fmt;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParseTimeError {
+    /// The string is not of the form `YYYY-MM-DDTHH:MM:SS[.ffffff][Z]`.
+    BadFormat,
+    /// The time can't be held by the type, e.g. a `TimePointSec` before
+    /// 1970.
+    OutOfRange,
+}
+
+/// `YYYY-MM-DDTHH:MM:SS.mmm`, as the node writes `time_point` and
+/// `block_timestamp_type`.
+pub(crate) fn write_millis(f: &mut fmt::Formatter<'_>, micros: i64) -> fmt::Result {
+    write_seconds(f, micros.div_euclid(MICROS_PER_SECOND))?;
+    write!(f, ".{:03}", micros.rem_euclid(MICROS_PER_SECOND) / 1000)
+}
+
+/// `YYYY-MM-DDTHH:MM:SS`, as the node writes `time_point_sec`.
+pub(crate) fn write_seconds(f: &mut fmt::Formatter<'_>, seconds: i64) -> fmt::Result {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+    let time = seconds.rem_euclid(SECONDS_PER_DAY);
+    write!(
+        f,
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Microseconds since the epoch of `YYYY-MM-DDTHH:MM:SS`, optionally
+/// followed by up to six decimals and a `Z`.
+pub(crate) fn parse(value: &str) -> Result<i64, ParseTimeError> {
+    let (seconds, micros) = split(value).ok_or(ParseTimeError::BadFormat)?;
+    seconds
+        .checked_mul(MICROS_PER_SECOND)
+        .and_then(|s| s.checked_add(micros))
+        .ok_or(ParseTimeError::OutOfRange)
+}
+
+/// The whole seconds since the epoch and the microseconds past them.
+fn split(value: &str) -> Option<(i64, i64)> {
+    let value = value.strip_suffix('Z').unwrap_or(value);
+    let (date, time) = value.split_once('T')?;
+    let (time, fraction) = match time.split_once('.') {
+        Some((time, fraction)) => (time, fraction),
+        None => (time, ""),
+    };
+
+    let mut date = date.splitn(3, '-');
+    let year = number(date.next()?, 4)?;
+    let month = number(date.next()?, 2)?;
+    let day = number(date.next()?, 2)?;
+    let mut time = time.splitn(3, ':');
+    let hour = number(time.next()?, 2)?;
+    let minute = number(time.next()?, 2)?;
+    let second = number(time.next()?, 2)?;
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+    if fraction.len() > 6 || (!fraction.is_empty() && number(fraction, fraction.len()).is_none()) {
+        return None;
+    }
+
+    let mut micros = 0;
+    for (i, c) in fraction
+        .bytes()
+        .chain(core::iter::repeat(b'0'))
+        .take(6)
+        .enumerate()
+    {
+        micros += i64::from(c - b'0') * 10_i64.pow(5 - i as u32);
+    }
+    let seconds =
+        days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+    Some((seconds, micros))
+}
+
+/// A number of exactly `len` ASCII digits.
+fn number(value: &str, len: usize) -> Option<i64> {
+    if value.len() != len || !value.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+// Howard Hinnant's `days_from_civil` and `civil_from_days`, in the
+// proleptic Gregorian calendar.
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::core::{BlockTimestamp, TimePoint, TimePointSec};
+
+    #[test]
+    fn formats_and_parses() {
+        // 2020-02-29T12:34:56.789
+        let micros = 1_582_979_696_789_000;
+        let time = "2020-02-29T12:34:56.789".parse::<TimePoint>().unwrap();
+        assert_eq!(time.elapsed.count(), micros);
+        assert_eq!(time.to_string(), "2020-02-29T12:34:56.789");
+        assert_eq!(parse("2020-02-29T12:34:56.789Z"), Ok(micros));
+        assert_eq!(TimePointSec::new(0).to_string(), "1970-01-01T00:00:00");
+        assert_eq!("1970-01-01T00:00:00".parse(), Ok(TimePointSec::new(0)));
+        assert_eq!(parse("2000-01-01T00:00:00.5"), Ok(946_684_800_500_000));
+        assert_eq!(
+            BlockTimestamp::new(1).to_string(),
+            "2000-01-01T00:00:00.500"
+        );
+        assert_eq!(
+            "2000-01-01T00:00:00.500".parse(),
+            Ok(BlockTimestamp::new(1))
+        );
+
+        assert_eq!(parse("2020-13-01T00:00:00"), Err(ParseTimeError::BadFormat));
+        assert_eq!(parse("2020-01-01 00:00:00"), Err(ParseTimeError::BadFormat));
+        assert_eq!(
+            parse("2020-01-01T00:00:00.1234567"),
+            Err(ParseTimeError::BadFormat)
+        );
+        assert_eq!(
+            "1969-12-31T23:59:59".parse::<TimePointSec>(),
+            Err(ParseTimeError::OutOfRange)
+        );
+    }
+}
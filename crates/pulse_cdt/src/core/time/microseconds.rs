@@ -5,7 +5,7 @@ use pulse_proc_macro::{NumBytes, Read, Write};
 #[derive(
     Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Read, Write, NumBytes,
 )]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi = "int64")]
 pub struct Microseconds(pub i64);
 
 impl Microseconds {
@@ -1,6 +1,9 @@
 mod block_timestamp;
 pub use block_timestamp::*;
 
+mod iso8601;
+pub use iso8601::ParseTimeError;
+
 mod microseconds;
 pub use microseconds::*;
 
@@ -1,13 +1,17 @@
-use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Sub, SubAssign},
+    str::FromStr,
+};
 
 use pulse_proc_macro::{NumBytes, Read, Write};
 
-use crate::core::Microseconds;
+use crate::core::{time::iso8601, Microseconds, ParseTimeError};
 
 #[derive(
     Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Read, Write, NumBytes,
 )]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi = "time_point")]
 pub struct TimePoint {
     pub elapsed: Microseconds, // microseconds since UNIX epoch
 }
@@ -29,6 +33,23 @@ impl TimePoint {
     }
 }
 
+/// Writes `YYYY-MM-DDTHH:MM:SS.mmm` in UTC, e.g. `2020-02-29T12:34:56.789`.
+impl fmt::Display for TimePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        iso8601::write_millis(f, self.elapsed.count())
+    }
+}
+
+/// Parses `YYYY-MM-DDTHH:MM:SS` in UTC, optionally followed by up to six
+/// decimals and a `Z`.
+impl FromStr for TimePoint {
+    type Err = ParseTimeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        iso8601::parse(value).map(|micros| Self::new(Microseconds::new(micros)))
+    }
+}
+
 /* ---- arithmetic/relations (match C++ semantics) ---- */
 
 impl Add<Microseconds> for TimePoint {
@@ -1,14 +1,18 @@
-use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::{
+    fmt,
+    ops::{Add, AddAssign, Sub, SubAssign},
+    str::FromStr,
+};
 
 use pulse_proc_macro::{NumBytes, Read, Write};
 
-use crate::core::{seconds, Microseconds, TimePoint};
+use crate::core::{seconds, time::iso8601, Microseconds, ParseTimeError, TimePoint};
 
 /// Seconds since UNIX epoch (UTC), second precision.
 #[derive(
     Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default, Read, Write, NumBytes,
 )]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi = "time_point_sec")]
 pub struct TimePointSec {
     pub utc_seconds: u32,
 }
@@ -39,6 +43,25 @@ impl TimePointSec {
     }
 }
 
+/// Writes `YYYY-MM-DDTHH:MM:SS` in UTC, e.g. `2020-02-29T12:34:56`.
+impl fmt::Display for TimePointSec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        iso8601::write_seconds(f, i64::from(self.utc_seconds))
+    }
+}
+
+/// Parses the forms [`TimePoint`] does, dropping fractions of a second.
+impl FromStr for TimePointSec {
+    type Err = ParseTimeError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let seconds = iso8601::parse(value)?.div_euclid(1_000_000);
+        u32::try_from(seconds)
+            .map(Self::new)
+            .map_err(|_| ParseTimeError::OutOfRange)
+    }
+}
+
 /* ----- conversions to/from TimePoint (microsecond precision) ----- */
 
 impl From<TimePoint> for TimePointSec {
@@ -1,11 +1,18 @@
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 use pulse_proc_macro::{NumBytes, Read, Write};
-use pulse_serialization::VarUint32;
+use pulse_serialization::{
+    abi::{Abi, AbiType},
+    Read as _, VarUint32, WriteError,
+};
 
-use crate::{contracts::Action, core::TimePointSec};
+use crate::{
+    contracts::{self, Action},
+    core::{Name, TimePointSec},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes)]
-#[pulse(crate_path = "pulse_serialization")]
+#[pulse(crate_path = "pulse_serialization", abi_name = "transaction_header")]
+#[cfg_attr(feature = "serde", pulse(serde))]
 pub struct TransactionHeader {
     pub expiration: TimePointSec,
     pub ref_block_num: u16,
@@ -15,7 +22,7 @@ pub struct TransactionHeader {
     pub delay_sec: VarUint32,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes)]
+#[derive(Debug, Clone, PartialEq, Eq, Read, NumBytes)]
 #[pulse(crate_path = "pulse_serialization")]
 pub struct Transaction {
     pub header: TransactionHeader,
@@ -23,3 +30,64 @@ pub struct Transaction {
     pub actions: Vec<Action>,              // Actions to be executed in this transaction
     pub transaction_extensions: Vec<(u16, Vec<u8>)>, // We don't use this for now
 }
+
+impl pulse_serialization::Write for Transaction {
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.header.write(bytes, pos)?;
+        self.context_free_actions.write(bytes, pos)?;
+        self.actions.write(bytes, pos)?;
+        self.transaction_extensions.write(bytes, pos)
+    }
+}
+
+/// Described as the node does: the header is the struct's base, and each
+/// extension is an `extension` struct.
+impl AbiType for Transaction {
+    fn abi_type(abi: &mut Abi) -> String {
+        let base = TransactionHeader::abi_type(abi);
+        let name = abi.add_struct_with("transaction", |abi| {
+            let extension = abi.add_struct_with("extension", |abi| {
+                [
+                    ("type", u16::abi_type(abi)),
+                    ("data", Vec::<u8>::abi_type(abi)),
+                ]
+            });
+            [
+                ("context_free_actions", Vec::<Action>::abi_type(abi)),
+                ("actions", Vec::<Action>::abi_type(abi)),
+                ("transaction_extensions", extension + "[]"),
+            ]
+        });
+        abi.structs.get_mut(&name).expect("added above").base = base;
+        name
+    }
+}
+
+impl Transaction {
+    /// Schedules the transaction, see [`contracts::send_deferred`].
+    #[inline]
+    pub fn send_deferred(&self, sender_id: u128, payer: Name, replace_existing: bool) {
+        contracts::send_deferred(sender_id, payer, self, replace_existing);
+    }
+}
+
+/// The data of the `onerror` action `pulse` sends a contract when a deferred
+/// transaction it sent fails. Handle it with
+/// `#[on_notify("pulse::onerror")]`.
+#[derive(Debug, Clone, PartialEq, Eq, Read, Write, NumBytes)]
+#[pulse(crate_path = "pulse_serialization", abi_name = "onerror")]
+#[cfg_attr(feature = "serde", pulse(serde))]
+pub struct OnError {
+    /// The id the transaction was sent with.
+    pub sender_id: u128,
+    /// The packed transaction that failed.
+    pub sent_trx: Vec<u8>,
+}
+
+impl OnError {
+    /// The transaction that failed.
+    #[inline]
+    pub fn unpack_sent_trx(&self) -> Transaction {
+        Transaction::unpack(&self.sent_trx).expect("failed to read sent transaction")
+    }
+}
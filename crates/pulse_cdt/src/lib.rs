@@ -1,17 +1,29 @@
 #![no_std]
 extern crate alloc;
+#[cfg(feature = "testing")]
+extern crate std;
+// Lets macros that expand to `pulse_cdt::...` paths be used in unit tests.
+#[cfg(test)]
+extern crate self as pulse_cdt;
 
+pub mod abi;
 pub mod contracts;
 pub mod core;
+#[cfg(any(feature = "native", not(target_arch = "wasm32")))]
+mod native;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use ::core::ffi::c_char;
 pub use ::core::ffi::c_void;
 
 pub use pulse_proc_macro::{
-    action, constructor, contract, destructor, dispatch, name, name_raw, symbol_with_code, table, on_notify,
+    action, constructor, contract, destructor, dispatch, name, name_raw, on_notify, print, println,
+    symbol_with_code, table,
 };
 pub use pulse_serialization::{
-    DataStream, NumBytes, Read, ReadError, VarInt32, VarUint32, Write, WriteError,
+    BinaryExtension, DataStream, NumBytes, PathSegment, Read, ReadBorrowed, ReadError,
+    ReadErrorKind, VarInt32, VarUint32, Write, WriteError,
 };
 
 use crate::core::Name;
@@ -21,3 +33,8 @@ pub const SAME_PAYER: Name = Name::new(0);
 pub mod __reexports {
     pub use lol_alloc;
 }
+
+// What `#[pulse(serde)]` derives expand to.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use pulse_serialization::serde_support;
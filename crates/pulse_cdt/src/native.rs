@@ -0,0 +1,206 @@
+//! Pure-Rust stand-ins for the hashing and key recovery host imports.
+//!
+//! These are used instead of the imports when the `native` feature is on
+//! and on every target but wasm32, where there is no host to import from.
+//! Every function has the same signature as its `extern "C"` counterpart
+//! so `contracts::crypto` can swap one for the other with a single `use`.
+
+use core::slice;
+
+use sha2::Digest;
+
+use crate::core::check;
+
+unsafe fn bytes<'a>(ptr: *const crate::c_void, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(ptr as *const u8, len) }
+    }
+}
+
+unsafe fn hash_into<D: Digest>(msg: *mut crate::c_void, len: u32, out: *mut crate::c_void) {
+    let digest = D::digest(unsafe { bytes(msg, len as usize) });
+    unsafe { core::ptr::copy_nonoverlapping(digest.as_ptr(), out as *mut u8, digest.len()) };
+}
+
+unsafe fn assert_hash<D: Digest>(msg: *mut crate::c_void, len: u32, expected: *mut crate::c_void) {
+    let digest = D::digest(unsafe { bytes(msg, len as usize) });
+    check(
+        unsafe { bytes(expected, digest.len()) } == digest.as_slice(),
+        "hash mismatch",
+    );
+}
+
+pub unsafe fn assert_sha1(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void) {
+    unsafe { assert_hash::<sha1::Sha1>(msg, len, ptr) }
+}
+
+pub unsafe fn assert_ripemd160(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void) {
+    unsafe { assert_hash::<ripemd::Ripemd160>(msg, len, ptr) }
+}
+
+pub unsafe fn assert_sha256(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void) {
+    unsafe { assert_hash::<sha2::Sha256>(msg, len, ptr) }
+}
+
+pub unsafe fn assert_sha512(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void) {
+    unsafe { assert_hash::<sha2::Sha512>(msg, len, ptr) }
+}
+
+pub unsafe fn sha1(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void) {
+    unsafe { hash_into::<sha1::Sha1>(msg, len, ptr) }
+}
+
+pub unsafe fn ripemd160(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void) {
+    unsafe { hash_into::<ripemd::Ripemd160>(msg, len, ptr) }
+}
+
+pub unsafe fn sha256(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void) {
+    unsafe { hash_into::<sha2::Sha256>(msg, len, ptr) }
+}
+
+pub unsafe fn sha512(msg: *mut crate::c_void, len: u32, ptr: *mut crate::c_void) {
+    unsafe { hash_into::<sha2::Sha512>(msg, len, ptr) }
+}
+
+/// Tags of the packed `Signature` and `PublicKey` variants.
+const K1_TAG: u8 = 0;
+const R1_TAG: u8 = 1;
+
+/// Recovers a compressed key from a packed K1 or R1 signature, copying as
+/// much of it as fits `pubkey_len` and returning its packed size. WebAuthn
+/// signatures are not supported.
+pub unsafe fn recover_key(
+    digest: *mut crate::c_void,
+    sig: *mut crate::c_void,
+    sig_len: usize,
+    pubkey: *mut crate::c_void,
+    pubkey_len: usize,
+) -> i32 {
+    let packed = unsafe { recover(digest, sig, sig_len) };
+    let copied = pubkey_len.min(packed.len());
+    unsafe { core::ptr::copy_nonoverlapping(packed.as_ptr(), pubkey as *mut u8, copied) };
+    packed.len() as i32
+}
+
+/// The packed key that made the packed signature `sig` over `digest`.
+unsafe fn recover(digest: *mut crate::c_void, sig: *mut crate::c_void, sig_len: usize) -> [u8; 34] {
+    let sig = unsafe { bytes(sig, sig_len) };
+    let recover_compact = match sig.first() {
+        Some(&K1_TAG) => recover_k1,
+        Some(&R1_TAG) => recover_r1,
+        _ => {
+            check(false, "unactivated signature type used during recover_key");
+            unreachable!()
+        }
+    };
+    // A tag, then the recovery id stored as 27 + id, plus 4 for compressed
+    // keys, then r and s.
+    let Some(compact) = sig.get(..66) else {
+        check(false, "unable to reconstruct public key from signature");
+        unreachable!()
+    };
+    let recovery_id = compact[1].wrapping_sub(27) & 3;
+    let digest = unsafe { bytes(digest, 32) };
+    let Some(key) = recover_compact(digest, &compact[2..], recovery_id) else {
+        check(false, "unable to reconstruct public key from signature");
+        unreachable!()
+    };
+
+    let mut packed = [0_u8; 34];
+    packed[0] = compact[0];
+    packed[1..].copy_from_slice(&key);
+    packed
+}
+
+fn recover_k1(digest: &[u8], sig: &[u8], recovery_id: u8) -> Option<[u8; 33]> {
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    let sig = Signature::from_slice(sig).ok()?;
+    let key = VerifyingKey::recover_from_prehash(digest, &sig, RecoveryId::from_byte(recovery_id)?)
+        .ok()?;
+    key.to_encoded_point(true).as_bytes().try_into().ok()
+}
+
+fn recover_r1(digest: &[u8], sig: &[u8], recovery_id: u8) -> Option<[u8; 33]> {
+    use k256::ecdsa::RecoveryId;
+    use p256::ecdsa::{Signature, VerifyingKey};
+
+    let sig = Signature::from_slice(sig).ok()?;
+    let key = VerifyingKey::recover_from_prehash(digest, &sig, RecoveryId::from_byte(recovery_id)?)
+        .ok()?;
+    key.to_encoded_point(true).as_bytes().try_into().ok()
+}
+
+pub unsafe fn assert_recover_key(
+    digest: *mut crate::c_void,
+    sig: *mut crate::c_void,
+    sig_len: usize,
+    pubkey: *mut crate::c_void,
+    pubkey_len: usize,
+) {
+    let recovered = unsafe { recover(digest, sig, sig_len) };
+    check(
+        unsafe { bytes(pubkey, pubkey_len) } == recovered.as_slice(),
+        "Error expected key different than recovered key",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::{ripemd160, sha1, sha256};
+
+    #[test]
+    fn hashes() {
+        let hash = sha256(b"abc", 3);
+        assert_eq!(
+            hash.0[..4],
+            [0xba, 0x78, 0x16, 0xbf],
+            "sha256 of abc starts with ba7816bf"
+        );
+        assert_eq!(sha1(b"abc", 3).0[..4], [0xa9, 0x99, 0x3e, 0x36]);
+        assert_eq!(ripemd160(b"abc", 3).0[..4], [0x8e, 0xb2, 0x08, 0xf7]);
+    }
+
+    // The `assert_*` functions fail through `check`, which needs the test host.
+    #[cfg(feature = "testing")]
+    #[test]
+    #[should_panic(expected = "hash mismatch")]
+    fn assert_hash_mismatch() {
+        use crate::{contracts::assert_sha256, core::Checksum256};
+
+        assert_sha256(b"abc", 3, sha256(b"abc", 3));
+        assert_sha256(b"abc", 3, Checksum256::default());
+    }
+
+    // `PublicKey::new` checks the recovered key.
+    #[cfg(feature = "testing")]
+    #[test]
+    fn recovers_k1_key() {
+        use k256::ecdsa::SigningKey;
+
+        use crate::{
+            contracts::{assert_recover_key, recover_key},
+            core::{FixedBytes, PublicKey, Signature},
+        };
+
+        let key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let digest = sha256(b"hello", 5);
+        let (sig, recovery_id) = key.sign_prehash_recoverable(&digest.0).unwrap();
+        let mut compact = [0_u8; 65];
+        compact[0] = 27 + 4 + recovery_id.to_byte();
+        compact[1..].copy_from_slice(&sig.to_bytes());
+        let sig = Signature::K1(FixedBytes(compact));
+
+        let expected = PublicKey::K1(FixedBytes(
+            key.verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .try_into()
+                .unwrap(),
+        ));
+        assert_eq!(recover_key(&digest, &sig), expected);
+        assert_recover_key(&digest, &sig, &expected);
+    }
+}
@@ -0,0 +1,486 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::cmp::Ordering;
+
+use super::host::fail;
+
+/// `(code, scope, table)` triple identifying a table.
+pub(crate) type TableId = (u64, u64, u64);
+
+#[derive(Clone)]
+pub(crate) struct Row {
+    pub payer: u64,
+    pub data: Vec<u8>,
+}
+
+/// Per-action iterator handles, mirroring the chain's iterator cache.
+///
+/// Non-negative handles index into `entries`. End iterators are encoded as
+/// `-(table_index + 2)` so that `-1` stays free to mean "no such table".
+pub(crate) struct IteratorCache<E> {
+    tables: Vec<TableId>,
+    entries: Vec<Option<E>>,
+}
+
+impl<E> Default for IteratorCache<E> {
+    fn default() -> Self {
+        Self {
+            tables: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<E: PartialEq> IteratorCache<E> {
+    pub fn end_iterator(&mut self, table: TableId) -> i32 {
+        let index = match self.tables.iter().position(|t| *t == table) {
+            Some(index) => index,
+            None => {
+                self.tables.push(table);
+                self.tables.len() - 1
+            }
+        };
+        -(index as i32) - 2
+    }
+
+    pub fn table_of_end(&self, itr: i32) -> TableId {
+        if itr >= -1 {
+            fail("not an end iterator");
+        }
+        match self.tables.get((-itr - 2) as usize) {
+            Some(table) => *table,
+            None => fail("invalid end iterator"),
+        }
+    }
+
+    pub fn add(&mut self, entry: E) -> i32 {
+        if let Some(index) = self.entries.iter().position(|e| e.as_ref() == Some(&entry)) {
+            return index as i32;
+        }
+        self.entries.push(Some(entry));
+        (self.entries.len() - 1) as i32
+    }
+
+    pub fn get(&self, itr: i32) -> &E {
+        if itr == -1 {
+            fail("invalid iterator");
+        }
+        if itr < -1 {
+            fail("cannot dereference end iterator");
+        }
+        match self.entries.get(itr as usize) {
+            Some(Some(entry)) => entry,
+            Some(None) => fail("dereference of deleted object"),
+            None => fail("iterator out of range"),
+        }
+    }
+
+    pub fn remove(&mut self, itr: i32) {
+        self.get(itr);
+        self.entries[itr as usize] = None;
+    }
+
+    pub fn replace(&mut self, itr: i32, entry: E) {
+        self.get(itr);
+        self.entries[itr as usize] = Some(entry);
+    }
+}
+
+/// Contract tables keyed by primary key.
+#[derive(Clone, Default)]
+pub(crate) struct PrimaryIndex {
+    tables: BTreeMap<TableId, BTreeMap<u64, Row>>,
+}
+
+pub(crate) type PrimaryIterators = IteratorCache<(TableId, u64)>;
+
+impl PrimaryIndex {
+    pub fn rows(&self, table: &TableId) -> impl Iterator<Item = (&u64, &Row)> {
+        self.tables.get(table).into_iter().flat_map(|rows| rows.iter())
+    }
+
+    pub fn row(&self, table: &TableId, id: u64) -> Option<&Row> {
+        self.tables.get(table).and_then(|rows| rows.get(&id))
+    }
+
+    pub fn store(
+        &mut self,
+        its: &mut PrimaryIterators,
+        table: TableId,
+        payer: u64,
+        id: u64,
+        data: Vec<u8>,
+    ) -> i32 {
+        let rows = self.tables.entry(table).or_default();
+        if rows.contains_key(&id) {
+            fail("could not insert object, most likely a uniqueness constraint was violated");
+        }
+        rows.insert(id, Row { payer, data });
+        its.end_iterator(table);
+        its.add((table, id))
+    }
+
+    pub fn update(&mut self, its: &PrimaryIterators, receiver: u64, itr: i32, payer: u64, data: Vec<u8>) {
+        let (table, id) = *its.get(itr);
+        if table.0 != receiver {
+            fail("db access violation");
+        }
+        let row = self.row_mut(&table, id);
+        if payer != 0 {
+            row.payer = payer;
+        }
+        row.data = data;
+    }
+
+    pub fn remove(&mut self, its: &mut PrimaryIterators, receiver: u64, itr: i32) {
+        let (table, id) = *its.get(itr);
+        if table.0 != receiver {
+            fail("db access violation");
+        }
+        if let Some(rows) = self.tables.get_mut(&table) {
+            rows.remove(&id);
+            if rows.is_empty() {
+                self.tables.remove(&table);
+            }
+        }
+        its.remove(itr);
+    }
+
+    pub fn get(&self, its: &PrimaryIterators, itr: i32) -> &[u8] {
+        let (table, id) = its.get(itr);
+        match self.row(table, *id) {
+            Some(row) => &row.data,
+            None => fail("dereference of deleted object"),
+        }
+    }
+
+    pub fn next(&self, its: &mut PrimaryIterators, itr: i32, primary: &mut u64) -> i32 {
+        if itr < -1 {
+            return -1;
+        }
+        let (table, id) = *its.get(itr);
+        let Some(rows) = self.tables.get(&table) else {
+            fail("dereference of deleted object")
+        };
+        let next = rows.range(id..).nth(1).map(|(k, _)| *k);
+        match next {
+            Some(next) => {
+                *primary = next;
+                its.add((table, next))
+            }
+            None => its.end_iterator(table),
+        }
+    }
+
+    pub fn previous(&self, its: &mut PrimaryIterators, itr: i32, primary: &mut u64) -> i32 {
+        let (table, prev) = if itr < -1 {
+            let table = its.table_of_end(itr);
+            let prev = self.tables.get(&table).and_then(|rows| rows.keys().next_back().copied());
+            (table, prev)
+        } else {
+            let (table, id) = *its.get(itr);
+            let Some(rows) = self.tables.get(&table) else {
+                fail("dereference of deleted object")
+            };
+            (table, rows.range(..id).next_back().map(|(k, _)| *k))
+        };
+        match prev {
+            Some(prev) => {
+                *primary = prev;
+                its.add((table, prev))
+            }
+            None => -1,
+        }
+    }
+
+    pub fn find(&self, its: &mut PrimaryIterators, table: TableId, id: u64) -> i32 {
+        let Some(rows) = self.tables.get(&table) else {
+            return -1;
+        };
+        let end = its.end_iterator(table);
+        if rows.contains_key(&id) {
+            its.add((table, id))
+        } else {
+            end
+        }
+    }
+
+    pub fn lowerbound(&self, its: &mut PrimaryIterators, table: TableId, id: u64) -> i32 {
+        self.bound(its, table, id, false)
+    }
+
+    pub fn upperbound(&self, its: &mut PrimaryIterators, table: TableId, id: u64) -> i32 {
+        self.bound(its, table, id, true)
+    }
+
+    pub fn end(&self, its: &mut PrimaryIterators, table: TableId) -> i32 {
+        if self.tables.contains_key(&table) {
+            its.end_iterator(table)
+        } else {
+            -1
+        }
+    }
+
+    fn bound(&self, its: &mut PrimaryIterators, table: TableId, id: u64, exclusive: bool) -> i32 {
+        let Some(rows) = self.tables.get(&table) else {
+            return -1;
+        };
+        let end = its.end_iterator(table);
+        let found = rows
+            .range(id..)
+            .map(|(k, _)| *k)
+            .find(|k| !exclusive || *k != id);
+        match found {
+            Some(found) => its.add((table, found)),
+            None => end,
+        }
+    }
+
+    fn row_mut(&mut self, table: &TableId, id: u64) -> &mut Row {
+        match self.tables.get_mut(table).and_then(|rows| rows.get_mut(&id)) {
+            Some(row) => row,
+            None => fail("dereference of deleted object"),
+        }
+    }
+}
+
+/// A secondary index over `K`, ordered by `(secondary, primary)`.
+#[derive(Clone)]
+pub(crate) struct SecondaryIndex<K> {
+    tables: BTreeMap<TableId, BTreeMap<(K, u64), u64>>,
+}
+
+impl<K> Default for SecondaryIndex<K> {
+    fn default() -> Self {
+        Self {
+            tables: BTreeMap::new(),
+        }
+    }
+}
+
+pub(crate) type SecondaryIterators<K> = IteratorCache<(TableId, K, u64)>;
+
+impl<K: Ord + Copy> SecondaryIndex<K> {
+    pub fn store(
+        &mut self,
+        its: &mut SecondaryIterators<K>,
+        table: TableId,
+        payer: u64,
+        id: u64,
+        secondary: K,
+    ) -> i32 {
+        let entries = self.tables.entry(table).or_default();
+        if entries.keys().any(|(_, primary)| *primary == id) {
+            fail("could not insert object, most likely a uniqueness constraint was violated");
+        }
+        entries.insert((secondary, id), payer);
+        its.end_iterator(table);
+        its.add((table, secondary, id))
+    }
+
+    pub fn update(&mut self, its: &mut SecondaryIterators<K>, receiver: u64, itr: i32, payer: u64, secondary: K) {
+        let (table, old, id) = *its.get(itr);
+        if table.0 != receiver {
+            fail("db access violation");
+        }
+        let entries = self.tables.get_mut(&table).expect("secondary table exists");
+        let old_payer = entries.remove(&(old, id)).unwrap_or(payer);
+        entries.insert((secondary, id), if payer != 0 { payer } else { old_payer });
+        its.replace(itr, (table, secondary, id));
+    }
+
+    pub fn remove(&mut self, its: &mut SecondaryIterators<K>, receiver: u64, itr: i32) {
+        let (table, secondary, id) = *its.get(itr);
+        if table.0 != receiver {
+            fail("db access violation");
+        }
+        if let Some(entries) = self.tables.get_mut(&table) {
+            entries.remove(&(secondary, id));
+            if entries.is_empty() {
+                self.tables.remove(&table);
+            }
+        }
+        its.remove(itr);
+    }
+
+    pub fn find_secondary(&self, its: &mut SecondaryIterators<K>, table: TableId, secondary: K, primary: &mut u64) -> i32 {
+        let Some(entries) = self.tables.get(&table) else {
+            return -1;
+        };
+        let end = its.end_iterator(table);
+        match entries.range((secondary, 0)..).next() {
+            Some(((k, id), _)) if *k == secondary => {
+                *primary = *id;
+                its.add((table, *k, *id))
+            }
+            _ => end,
+        }
+    }
+
+    pub fn find_primary(&self, its: &mut SecondaryIterators<K>, table: TableId, secondary: &mut K, primary: u64) -> i32 {
+        let Some(entries) = self.tables.get(&table) else {
+            return -1;
+        };
+        let end = its.end_iterator(table);
+        match entries.keys().find(|(_, id)| *id == primary) {
+            Some((k, id)) => {
+                *secondary = *k;
+                its.add((table, *k, *id))
+            }
+            None => end,
+        }
+    }
+
+    pub fn lowerbound(&self, its: &mut SecondaryIterators<K>, table: TableId, secondary: &mut K, primary: &mut u64) -> i32 {
+        let key = *secondary;
+        self.bound(its, table, secondary, primary, |k| *k >= key)
+    }
+
+    pub fn upperbound(&self, its: &mut SecondaryIterators<K>, table: TableId, secondary: &mut K, primary: &mut u64) -> i32 {
+        let key = *secondary;
+        self.bound(its, table, secondary, primary, |k| *k > key)
+    }
+
+    pub fn end(&self, its: &mut SecondaryIterators<K>, table: TableId) -> i32 {
+        if self.tables.contains_key(&table) {
+            its.end_iterator(table)
+        } else {
+            -1
+        }
+    }
+
+    pub fn next(&self, its: &mut SecondaryIterators<K>, itr: i32, primary: &mut u64) -> i32 {
+        if itr < -1 {
+            return -1;
+        }
+        let (table, secondary, id) = *its.get(itr);
+        let Some(entries) = self.tables.get(&table) else {
+            fail("dereference of deleted object")
+        };
+        let next = entries.range((secondary, id)..).nth(1).map(|(k, _)| *k);
+        match next {
+            Some((k, id)) => {
+                *primary = id;
+                its.add((table, k, id))
+            }
+            None => its.end_iterator(table),
+        }
+    }
+
+    pub fn previous(&self, its: &mut SecondaryIterators<K>, itr: i32, primary: &mut u64) -> i32 {
+        let (table, prev) = if itr < -1 {
+            let table = its.table_of_end(itr);
+            let prev = self.tables.get(&table).and_then(|entries| entries.keys().next_back().copied());
+            (table, prev)
+        } else {
+            let (table, secondary, id) = *its.get(itr);
+            let Some(entries) = self.tables.get(&table) else {
+                fail("dereference of deleted object")
+            };
+            let prev = entries.range(..(secondary, id)).next_back().map(|(k, _)| *k);
+            (table, prev)
+        };
+        match prev {
+            Some((k, id)) => {
+                *primary = id;
+                its.add((table, k, id))
+            }
+            None => -1,
+        }
+    }
+
+    fn bound(
+        &self,
+        its: &mut SecondaryIterators<K>,
+        table: TableId,
+        secondary: &mut K,
+        primary: &mut u64,
+        pred: impl Fn(&K) -> bool,
+    ) -> i32 {
+        let Some(entries) = self.tables.get(&table) else {
+            return -1;
+        };
+        let end = its.end_iterator(table);
+        match entries.keys().find(|(k, _)| pred(k)) {
+            Some((k, id)) => {
+                *secondary = *k;
+                *primary = *id;
+                its.add((table, *k, *id))
+            }
+            None => end,
+        }
+    }
+}
+
+/// An `f64` secondary key, totally ordered.
+#[derive(Clone, Copy)]
+pub(crate) struct F64Key(pub f64);
+
+impl PartialEq for F64Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for F64Key {}
+
+impl PartialOrd for F64Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for F64Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// A `long double` secondary key given as little-endian binary128 bits,
+/// ordered numerically.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LongDoubleKey(pub [u8; 16]);
+
+impl LongDoubleKey {
+    fn sortable(&self) -> u128 {
+        let bits = u128::from_le_bytes(self.0);
+        if bits >> 127 == 1 {
+            !bits
+        } else {
+            bits | (1 << 127)
+        }
+    }
+}
+
+impl PartialOrd for LongDoubleKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LongDoubleKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sortable().cmp(&other.sortable())
+    }
+}
+
+/// Everything a contract can persist.
+#[derive(Clone, Default)]
+pub(crate) struct Database {
+    pub primary: PrimaryIndex,
+    pub idx64: SecondaryIndex<u64>,
+    pub idx128: SecondaryIndex<u128>,
+    pub idx256: SecondaryIndex<[u128; 2]>,
+    pub idx_double: SecondaryIndex<F64Key>,
+    pub idx_long_double: SecondaryIndex<LongDoubleKey>,
+}
+
+/// Iterator handles handed out during a single action.
+#[derive(Default)]
+pub(crate) struct Iterators {
+    pub primary: PrimaryIterators,
+    pub idx64: SecondaryIterators<u64>,
+    pub idx128: SecondaryIterators<u128>,
+    pub idx256: SecondaryIterators<[u128; 2]>,
+    pub idx_double: SecondaryIterators<F64Key>,
+    pub idx_long_double: SecondaryIterators<LongDoubleKey>,
+}
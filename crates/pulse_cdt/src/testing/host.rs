@@ -0,0 +1,1149 @@
+//! Native stand-ins for the host imports.
+//!
+//! Every function here has the same signature as its `extern "C"`
+//! counterpart so the binding modules can swap one for the other with a
+//! single `use`.
+
+use core::{cell::RefCell, slice};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    thread_local,
+    vec,
+    vec::Vec,
+};
+
+use sha3::Digest;
+
+use super::database::{Database, F64Key, Iterators, LongDoubleKey};
+use crate::{
+    contracts::{Action, BlockchainParameters, PermissionLevel},
+    core::{Checksum256, FixedBytes, Name, ProducerAuthority, ProducerKey, PublicKey, Transaction},
+    NumBytes, Read, Write,
+};
+
+/// Entry point generated by `#[contract]` for native builds.
+pub type ApplyFn = fn(u64, u64, u64);
+
+/// The chain's default `max_action_return_value_size`.
+const MAX_ACTION_RETURN_VALUE_SIZE: usize = 256;
+
+/// The most producers a schedule may have.
+const MAX_PRODUCERS: usize = 125;
+
+#[derive(Clone)]
+pub(crate) struct ChainState {
+    pub accounts: BTreeSet<u64>,
+    pub privileged: BTreeSet<u64>,
+    pub resource_limits: BTreeMap<u64, (i64, i64, i64)>,
+    pub contracts: BTreeMap<u64, ApplyFn>,
+    pub now: u64,
+    pub db: Database,
+    /// The transaction being executed, if any.
+    pub transaction: Option<Transaction>,
+    pub context_free_data: Vec<Vec<u8>>,
+    /// Deferred transactions waiting to run, in the order they were sent.
+    pub deferred: Vec<DeferredTransaction>,
+    pub schedule_version: u32,
+    pub active_producers: Vec<ProducerAuthority>,
+    /// The schedule waiting for its block to become irreversible.
+    pub proposed_producers: Option<Vec<ProducerAuthority>>,
+    pub blockchain_parameters: BlockchainParameters,
+    pub activated_features: BTreeSet<Checksum256>,
+    /// Features activated by the next block, in the order they were
+    /// pre-activated.
+    pub preactivated_features: Vec<Checksum256>,
+}
+
+#[derive(Clone)]
+pub(crate) struct DeferredTransaction {
+    pub sender: u64,
+    pub sender_id: u128,
+    pub transaction: Transaction,
+    /// When the transaction's delay is over.
+    pub run_at: u64,
+}
+
+impl Default for ChainState {
+    fn default() -> Self {
+        Self {
+            accounts: BTreeSet::new(),
+            privileged: BTreeSet::new(),
+            resource_limits: BTreeMap::new(),
+            contracts: BTreeMap::new(),
+            // 2020-01-01T00:00:00Z
+            now: 1_577_836_800_000_000,
+            db: Database::default(),
+            transaction: None,
+            context_free_data: Vec::new(),
+            deferred: Vec::new(),
+            schedule_version: 0,
+            active_producers: vec![ProducerAuthority::from(ProducerKey {
+                producer_name: Name::new(crate::name_raw!("pulse")),
+                block_signing_key: PublicKey::default(),
+            })],
+            proposed_producers: None,
+            blockchain_parameters: default_blockchain_parameters(),
+            activated_features: BTreeSet::new(),
+            preactivated_features: Vec::new(),
+        }
+    }
+}
+
+/// The genesis parameters of a new chain.
+fn default_blockchain_parameters() -> BlockchainParameters {
+    BlockchainParameters {
+        max_block_net_usage: 1024 * 1024,
+        target_block_net_usage_pct: 1000,
+        max_transaction_net_usage: 512 * 1024,
+        base_per_transaction_net_usage: 12,
+        net_usage_leeway: 500,
+        context_free_discount_net_usage_num: 20,
+        context_free_discount_net_usage_den: 100,
+        max_block_cpu_usage: 200_000,
+        target_block_cpu_usage_pct: 1000,
+        max_transaction_cpu_usage: 150_000,
+        min_transaction_cpu_usage: 100,
+        max_transaction_lifetime: 60 * 60,
+        deferred_trx_expiration_window: 10 * 60,
+        max_transaction_delay: 45 * 24 * 60 * 60,
+        max_inline_action_size: 512 * 1024,
+        max_inline_action_depth: 4,
+        max_authority_depth: 6,
+    }
+}
+
+/// State of the action currently being applied.
+#[derive(Default)]
+pub(crate) struct ApplyContext {
+    pub receiver: u64,
+    pub action: Action,
+    pub notified: Vec<u64>,
+    pub inline_actions: Vec<Action>,
+    pub iterators: Iterators,
+    pub console: String,
+    pub return_value: Vec<u8>,
+    /// Whether the action is one of the transaction's context-free actions.
+    pub context_free: bool,
+}
+
+thread_local! {
+    static CHAIN: RefCell<ChainState> = RefCell::new(ChainState::default());
+    static CONTEXT: RefCell<ApplyContext> = RefCell::new(ApplyContext::default());
+}
+
+pub(crate) fn with_chain<R>(f: impl FnOnce(&mut ChainState) -> R) -> R {
+    CHAIN.with(|chain| f(&mut chain.borrow_mut()))
+}
+
+pub(crate) fn with_context<R>(f: impl FnOnce(&mut ApplyContext) -> R) -> R {
+    CONTEXT.with(|ctx| f(&mut ctx.borrow_mut()))
+}
+
+fn with_db<R>(f: impl FnOnce(&mut Database, &mut ApplyContext) -> R) -> R {
+    with_chain(|chain| with_context(|ctx| f(&mut chain.db, ctx)))
+}
+
+pub(crate) fn replace_context(ctx: ApplyContext) -> ApplyContext {
+    CONTEXT.with(|current| core::mem::replace(&mut *current.borrow_mut(), ctx))
+}
+
+/// Aborts the running action, the native equivalent of a failed `pulse_assert`.
+pub(crate) fn fail(msg: &str) -> ! {
+    std::panic::panic_any(String::from(msg))
+}
+
+unsafe fn bytes<'a>(ptr: *const crate::c_void, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(ptr as *const u8, len) }
+    }
+}
+
+unsafe fn copy_out(src: &[u8], ptr: *mut crate::c_void, len: usize) -> usize {
+    let copied = src.len().min(len);
+    if copied > 0 {
+        unsafe { core::ptr::copy_nonoverlapping(src.as_ptr(), ptr as *mut u8, copied) };
+    }
+    copied
+}
+
+// ===== assert =====
+
+pub unsafe fn pulse_assert(test: u32, msg: *const crate::c_char, msg_len: usize) {
+    if test == 0 {
+        let msg = unsafe { bytes(msg as *const crate::c_void, msg_len) };
+        fail(&String::from_utf8_lossy(msg));
+    }
+}
+
+// ===== console =====
+
+fn print_str(s: &str) {
+    with_context(|ctx| ctx.console.push_str(s));
+}
+
+pub unsafe fn prints(cstr: *const crate::c_char) {
+    let cstr = unsafe { core::ffi::CStr::from_ptr(cstr) };
+    print_str(&cstr.to_string_lossy());
+}
+
+pub unsafe fn prints_l(cstr: *const crate::c_char, len: u32) {
+    let bytes = unsafe { bytes(cstr as *const crate::c_void, len as usize) };
+    print_str(&String::from_utf8_lossy(bytes));
+}
+
+pub unsafe fn printi(value: i64) {
+    print_str(&value.to_string());
+}
+
+pub unsafe fn printui(value: u64) {
+    print_str(&value.to_string());
+}
+
+pub unsafe fn printn(name: u64) {
+    print_str(&Name::new(name).to_string());
+}
+
+pub unsafe fn printhex(data: *const crate::c_void, datalen: u32) {
+    let data = unsafe { bytes(data, datalen as usize) };
+    let hex: String = data.iter().map(|b| format!("{b:02x}")).collect();
+    print_str(&hex);
+}
+
+pub unsafe fn printdf(value: f64) {
+    print_str(&value.to_string());
+}
+
+// ===== action =====
+
+pub unsafe fn action_data_size() -> u32 {
+    with_context(|ctx| ctx.action.data.len() as u32)
+}
+
+pub unsafe fn read_action_data(msg: *mut crate::c_void, len: u32) -> u32 {
+    with_context(|ctx| {
+        if len == 0 {
+            return ctx.action.data.len() as u32;
+        }
+        unsafe { copy_out(&ctx.action.data, msg, len as usize) as u32 }
+    })
+}
+
+pub unsafe fn require_auth(name: u64) {
+    if !unsafe { has_auth(name) } {
+        fail(&missing_authority(name));
+    }
+}
+
+pub unsafe fn require_auth2(name: u64, permission: u64) {
+    let level = PermissionLevel::new(Name::new(name), Name::new(permission));
+    if !with_context(|ctx| ctx.action.authorization.contains(&level)) {
+        fail(&missing_authority(name));
+    }
+}
+
+pub unsafe fn has_auth(name: u64) -> bool {
+    with_context(|ctx| ctx.action.authorization.iter().any(|auth| auth.actor == name))
+}
+
+pub unsafe fn require_recipient(recipient: u64) {
+    with_context(|ctx| {
+        if !ctx.notified.contains(&recipient) {
+            ctx.notified.push(recipient);
+        }
+    })
+}
+
+pub unsafe fn is_account(name: u64) -> bool {
+    with_chain(|chain| chain.accounts.contains(&name))
+}
+
+pub unsafe fn current_receiver() -> u64 {
+    with_context(|ctx| ctx.receiver)
+}
+
+pub unsafe fn send_inline(msg: *mut crate::c_void, len: usize) {
+    let data = unsafe { bytes(msg, len) };
+    let action = match Action::unpack(data) {
+        Ok(action) => action,
+        Err(_) => fail("send_inline: failed to unpack action"),
+    };
+    with_context(|ctx| ctx.inline_actions.push(action));
+}
+
+pub unsafe fn set_action_return_value(data: *mut crate::c_void, len: usize) {
+    if len > MAX_ACTION_RETURN_VALUE_SIZE {
+        fail("action return value size must be less or equal to 256 bytes");
+    }
+    let data = unsafe { bytes(data, len) }.to_vec();
+    with_context(|ctx| ctx.return_value = data);
+}
+
+fn missing_authority(name: u64) -> String {
+    let mut msg = String::from("missing authority of ");
+    msg.push_str(&Name::new(name).to_string());
+    msg
+}
+
+// ===== crypto =====
+
+fn hash_into<D: Digest>(msg: *mut crate::c_void, len: u32, out: *mut crate::c_void) {
+    let data = unsafe { bytes(msg, len as usize) };
+    let digest = D::digest(data);
+    unsafe { copy_out(&digest, out, digest.len()) };
+}
+
+/// What the extended crypto functions return on malformed input.
+const CRYPTO_ERROR: i32 = -1;
+
+pub unsafe fn sha3(
+    data: *mut crate::c_void,
+    data_len: u32,
+    hash: *mut crate::c_void,
+    hash_len: u32,
+    keccak: i32,
+) {
+    if hash_len < 32 {
+        fail("sha3 output buffer is too small");
+    }
+    if keccak != 0 {
+        hash_into::<sha3::Keccak256>(data, data_len, hash)
+    } else {
+        hash_into::<sha3::Sha3_256>(data, data_len, hash)
+    }
+}
+
+/// The BLAKE2b compression function `F`, as specified by EIP-152.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn blake2_f(
+    rounds: u32,
+    state: *mut crate::c_void,
+    state_len: u32,
+    msg: *mut crate::c_void,
+    msg_len: u32,
+    t0: *mut crate::c_void,
+    t0_len: u32,
+    t1: *mut crate::c_void,
+    t1_len: u32,
+    last: i32,
+    result: *mut crate::c_void,
+    result_len: u32,
+) -> i32 {
+    if state_len != 64 || msg_len != 128 || t0_len != 8 || t1_len != 8 || result_len != 64 {
+        return CRYPTO_ERROR;
+    }
+    let words = |data: &[u8]| -> Vec<u64> {
+        data.chunks_exact(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+            .collect()
+    };
+    let mut h = words(unsafe { bytes(state, 64) });
+    let m = words(unsafe { bytes(msg, 128) });
+    let t = [
+        words(unsafe { bytes(t0, 8) })[0],
+        words(unsafe { bytes(t1, 8) })[0],
+    ];
+    blake2b_compress(&mut h, &m, t, last != 0, rounds);
+    let out: Vec<u8> = h.iter().flat_map(|w| w.to_le_bytes()).collect();
+    unsafe { copy_out(&out, result, 64) };
+    0
+}
+
+fn blake2b_compress(h: &mut [u64], m: &[u64], t: [u64; 2], last: bool, rounds: u32) {
+    const IV: [u64; 8] = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+    const SIGMA: [[usize; 16]; 10] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+        [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+        [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+        [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+        [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+        [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+        [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+        [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+        [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    ];
+
+    let mut v = [0_u64; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= t[0];
+    v[13] ^= t[1];
+    if last {
+        v[14] = !v[14];
+    }
+
+    let mut mix = |a: usize, b: usize, c: usize, d: usize, x: u64, y: u64| {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(32);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(24);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(63);
+    };
+    for round in 0..rounds as usize {
+        let s = &SIGMA[round % 10];
+        mix(0, 4, 8, 12, m[s[0]], m[s[1]]);
+        mix(1, 5, 9, 13, m[s[2]], m[s[3]]);
+        mix(2, 6, 10, 14, m[s[4]], m[s[5]]);
+        mix(3, 7, 11, 15, m[s[6]], m[s[7]]);
+        mix(0, 5, 10, 15, m[s[8]], m[s[9]]);
+        mix(1, 6, 11, 12, m[s[10]], m[s[11]]);
+        mix(2, 7, 8, 13, m[s[12]], m[s[13]]);
+        mix(3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+pub unsafe fn k1_recover(
+    sig: *mut crate::c_void,
+    sig_len: u32,
+    dig: *mut crate::c_void,
+    dig_len: u32,
+    pubkey: *mut crate::c_void,
+    pubkey_len: u32,
+) -> i32 {
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    if sig_len != 65 || dig_len != 32 || pubkey_len < 65 {
+        return CRYPTO_ERROR;
+    }
+    let sig = unsafe { bytes(sig, 65) };
+    // The recovery id is stored as 27 + id, plus 4 for compressed keys.
+    let recovery_id = match sig[0] {
+        v @ 27..=34 => RecoveryId::from_byte((v - 27) & 3),
+        _ => None,
+    };
+    let recovered = recovery_id
+        .zip(Signature::from_slice(&sig[1..]).ok())
+        .and_then(|(recovery_id, signature)| {
+            let digest = unsafe { bytes(dig, 32) };
+            VerifyingKey::recover_from_prehash(digest, &signature, recovery_id).ok()
+        });
+    match recovered {
+        Some(key) => {
+            let point = key.to_encoded_point(false);
+            unsafe { copy_out(point.as_bytes(), pubkey, 65) };
+            0
+        }
+        None => CRYPTO_ERROR,
+    }
+}
+
+fn read_g1(data: &[u8]) -> Option<bn::G1> {
+    use bn::{AffineG1, Fq, Group, G1};
+
+    let x = Fq::from_slice(&data[..32]).ok()?;
+    let y = Fq::from_slice(&data[32..64]).ok()?;
+    if x.is_zero() && y.is_zero() {
+        Some(G1::zero())
+    } else {
+        AffineG1::new(x, y).ok().map(Into::into)
+    }
+}
+
+/// G2 points are written `(x.imaginary, x.real, y.imaginary, y.real)`.
+fn read_g2(data: &[u8]) -> Option<bn::G2> {
+    use bn::{AffineG2, Fq, Fq2, Group, G2};
+
+    let fq = |i: usize| Fq::from_slice(&data[i * 32..(i + 1) * 32]).ok();
+    let x = Fq2::new(fq(1)?, fq(0)?);
+    let y = Fq2::new(fq(3)?, fq(2)?);
+    if x.is_zero() && y.is_zero() {
+        Some(G2::zero())
+    } else {
+        AffineG2::new(x, y).ok().map(Into::into)
+    }
+}
+
+unsafe fn write_g1(point: bn::G1, result: *mut crate::c_void, result_len: u32) -> i32 {
+    if result_len != 64 {
+        return CRYPTO_ERROR;
+    }
+    let mut out = [0_u8; 64];
+    if let Some(affine) = bn::AffineG1::from_jacobian(point) {
+        affine.x().to_big_endian(&mut out[..32]).unwrap();
+        affine.y().to_big_endian(&mut out[32..]).unwrap();
+    }
+    unsafe { copy_out(&out, result, 64) };
+    0
+}
+
+pub unsafe fn alt_bn128_add(
+    op1: *mut crate::c_void,
+    op1_len: u32,
+    op2: *mut crate::c_void,
+    op2_len: u32,
+    result: *mut crate::c_void,
+    result_len: u32,
+) -> i32 {
+    if op1_len != 64 || op2_len != 64 {
+        return CRYPTO_ERROR;
+    }
+    match (
+        read_g1(unsafe { bytes(op1, 64) }),
+        read_g1(unsafe { bytes(op2, 64) }),
+    ) {
+        (Some(a), Some(b)) => unsafe { write_g1(a + b, result, result_len) },
+        _ => CRYPTO_ERROR,
+    }
+}
+
+pub unsafe fn alt_bn128_mul(
+    g1: *mut crate::c_void,
+    g1_len: u32,
+    scalar: *mut crate::c_void,
+    scalar_len: u32,
+    result: *mut crate::c_void,
+    result_len: u32,
+) -> i32 {
+    if g1_len != 64 || scalar_len != 32 {
+        return CRYPTO_ERROR;
+    }
+    let point = read_g1(unsafe { bytes(g1, 64) });
+    let scalar = bn::Fr::from_slice(unsafe { bytes(scalar, 32) }).ok();
+    match point.zip(scalar) {
+        Some((point, scalar)) => unsafe { write_g1(point * scalar, result, result_len) },
+        None => CRYPTO_ERROR,
+    }
+}
+
+/// 0 if the pairing check holds, 1 if it doesn't.
+pub unsafe fn alt_bn128_pair(pairs: *mut crate::c_void, pairs_len: u32) -> i32 {
+    if !pairs_len.is_multiple_of(192) {
+        return CRYPTO_ERROR;
+    }
+    let points: Option<Vec<_>> = unsafe { bytes(pairs, pairs_len as usize) }
+        .chunks_exact(192)
+        .map(|pair| Some((read_g1(&pair[..64])?, read_g2(&pair[64..])?)))
+        .collect();
+    match points {
+        Some(points) => i32::from(bn::pairing_batch(&points) != bn::Gt::one()),
+        None => CRYPTO_ERROR,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn mod_exp(
+    base: *mut crate::c_void,
+    base_len: u32,
+    exp: *mut crate::c_void,
+    exp_len: u32,
+    modulus: *mut crate::c_void,
+    modulus_len: u32,
+    result: *mut crate::c_void,
+    result_len: u32,
+) -> i32 {
+    use num_bigint::BigUint;
+
+    if modulus_len == 0 || result_len != modulus_len {
+        return CRYPTO_ERROR;
+    }
+    let number = |ptr, len| BigUint::from_bytes_be(unsafe { bytes(ptr, len as usize) });
+    let modulus = number(modulus, modulus_len);
+    let value = if modulus == BigUint::ZERO {
+        BigUint::ZERO
+    } else {
+        number(base, base_len).modpow(&number(exp, exp_len), &modulus)
+    };
+    // Left-padded to the modulus' length.
+    let digits = value.to_bytes_be();
+    let mut out = vec![0_u8; modulus_len as usize];
+    out[modulus_len as usize - digits.len()..].copy_from_slice(&digits);
+    unsafe { copy_out(&out, result, result_len as usize) };
+    0
+}
+
+// ===== database =====
+
+pub unsafe fn db_get_i64(iterator: i32, data: *mut crate::c_void, len: u32) -> i32 {
+    with_db(|db, ctx| {
+        let row = db.primary.get(&ctx.iterators.primary, iterator);
+        if len == 0 {
+            return row.len() as i32;
+        }
+        unsafe { copy_out(row, data, len as usize) as i32 }
+    })
+}
+
+pub unsafe fn db_remove_i64(iterator: i32) {
+    with_db(|db, ctx| db.primary.remove(&mut ctx.iterators.primary, ctx.receiver, iterator))
+}
+
+pub unsafe fn db_next_i64(iterator: i32, primary: *mut u64) -> i32 {
+    with_db(|db, ctx| db.primary.next(&mut ctx.iterators.primary, iterator, unsafe { &mut *primary }))
+}
+
+pub unsafe fn db_previous_i64(iterator: i32, primary: *mut u64) -> i32 {
+    with_db(|db, ctx| db.primary.previous(&mut ctx.iterators.primary, iterator, unsafe { &mut *primary }))
+}
+
+pub unsafe fn db_update_i64(iterator: i32, payer: u64, data: *const crate::c_void, len: u32) {
+    let data = unsafe { bytes(data, len as usize) }.to_vec();
+    with_db(|db, ctx| db.primary.update(&ctx.iterators.primary, ctx.receiver, iterator, payer, data))
+}
+
+pub unsafe fn db_store_i64(
+    scope: u64,
+    table: u64,
+    payer: u64,
+    id: u64,
+    data: *const crate::c_void,
+    len: u32,
+) -> i32 {
+    let data = unsafe { bytes(data, len as usize) }.to_vec();
+    with_db(|db, ctx| {
+        if ctx.receiver == 0 {
+            fail("db access violation");
+        }
+        db.primary.store(&mut ctx.iterators.primary, (ctx.receiver, scope, table), payer, id, data)
+    })
+}
+
+pub unsafe fn db_find_i64(code: u64, scope: u64, table: u64, id: u64) -> i32 {
+    with_db(|db, ctx| db.primary.find(&mut ctx.iterators.primary, (code, scope, table), id))
+}
+
+pub unsafe fn db_end_i64(code: u64, scope: u64, table: u64) -> i32 {
+    with_db(|db, ctx| db.primary.end(&mut ctx.iterators.primary, (code, scope, table)))
+}
+
+pub unsafe fn db_lowerbound_i64(code: u64, scope: u64, table: u64, id: u64) -> i32 {
+    with_db(|db, ctx| db.primary.lowerbound(&mut ctx.iterators.primary, (code, scope, table), id))
+}
+
+pub unsafe fn db_upperbound_i64(code: u64, scope: u64, table: u64, id: u64) -> i32 {
+    with_db(|db, ctx| db.primary.upperbound(&mut ctx.iterators.primary, (code, scope, table), id))
+}
+
+// Every fixed-width secondary index family has the same ten intrinsics; only
+// the key type and the table it lives in differ.
+macro_rules! secondary_index {
+    (
+        $index:ident, $ty:ty, $key:expr, $value:expr,
+        $store:ident, $update:ident, $remove:ident, $find_secondary:ident, $find_primary:ident,
+        $lowerbound:ident, $upperbound:ident, $end:ident, $next:ident, $previous:ident
+    ) => {
+        pub unsafe fn $store(scope: u64, table: u64, payer: u64, id: u64, secondary: *const $ty) -> i32 {
+            let secondary = ($key)(unsafe { *secondary });
+            with_db(|db, ctx| {
+                if ctx.receiver == 0 {
+                    fail("db access violation");
+                }
+                db.$index.store(&mut ctx.iterators.$index, (ctx.receiver, scope, table), payer, id, secondary)
+            })
+        }
+
+        pub unsafe fn $update(iterator: i32, payer: u64, secondary: *const $ty) {
+            let secondary = ($key)(unsafe { *secondary });
+            with_db(|db, ctx| db.$index.update(&mut ctx.iterators.$index, ctx.receiver, iterator, payer, secondary))
+        }
+
+        pub unsafe fn $remove(iterator: i32) {
+            with_db(|db, ctx| db.$index.remove(&mut ctx.iterators.$index, ctx.receiver, iterator))
+        }
+
+        pub unsafe fn $find_secondary(
+            code: u64,
+            scope: u64,
+            table: u64,
+            secondary: *const $ty,
+            primary: *mut u64,
+        ) -> i32 {
+            let secondary = ($key)(unsafe { *secondary });
+            with_db(|db, ctx| {
+                db.$index
+                    .find_secondary(&mut ctx.iterators.$index, (code, scope, table), secondary, unsafe { &mut *primary })
+            })
+        }
+
+        pub unsafe fn $find_primary(code: u64, scope: u64, table: u64, secondary: *mut $ty, primary: u64) -> i32 {
+            let mut key = ($key)(unsafe { *secondary });
+            let itr = with_db(|db, ctx| {
+                db.$index.find_primary(&mut ctx.iterators.$index, (code, scope, table), &mut key, primary)
+            });
+            unsafe { *secondary = ($value)(key) };
+            itr
+        }
+
+        pub unsafe fn $lowerbound(code: u64, scope: u64, table: u64, secondary: *mut $ty, primary: *mut u64) -> i32 {
+            let mut key = ($key)(unsafe { *secondary });
+            let itr = with_db(|db, ctx| {
+                db.$index.lowerbound(&mut ctx.iterators.$index, (code, scope, table), &mut key, unsafe { &mut *primary })
+            });
+            unsafe { *secondary = ($value)(key) };
+            itr
+        }
+
+        pub unsafe fn $upperbound(code: u64, scope: u64, table: u64, secondary: *mut $ty, primary: *mut u64) -> i32 {
+            let mut key = ($key)(unsafe { *secondary });
+            let itr = with_db(|db, ctx| {
+                db.$index.upperbound(&mut ctx.iterators.$index, (code, scope, table), &mut key, unsafe { &mut *primary })
+            });
+            unsafe { *secondary = ($value)(key) };
+            itr
+        }
+
+        pub unsafe fn $end(code: u64, scope: u64, table: u64) -> i32 {
+            with_db(|db, ctx| db.$index.end(&mut ctx.iterators.$index, (code, scope, table)))
+        }
+
+        pub unsafe fn $next(iterator: i32, primary: *mut u64) -> i32 {
+            with_db(|db, ctx| db.$index.next(&mut ctx.iterators.$index, iterator, unsafe { &mut *primary }))
+        }
+
+        pub unsafe fn $previous(iterator: i32, primary: *mut u64) -> i32 {
+            with_db(|db, ctx| db.$index.previous(&mut ctx.iterators.$index, iterator, unsafe { &mut *primary }))
+        }
+    };
+}
+
+secondary_index!(
+    idx64, u64, |v| v, |v| v,
+    db_idx64_store, db_idx64_update, db_idx64_remove, db_idx64_find_secondary, db_idx64_find_primary,
+    db_idx64_lowerbound, db_idx64_upperbound, db_idx64_end, db_idx64_next, db_idx64_previous
+);
+
+secondary_index!(
+    idx128, u128, |v| v, |v| v,
+    db_idx128_store, db_idx128_update, db_idx128_remove, db_idx128_find_secondary, db_idx128_find_primary,
+    db_idx128_lowerbound, db_idx128_upperbound, db_idx128_end, db_idx128_next, db_idx128_previous
+);
+
+secondary_index!(
+    idx_double, f64, F64Key, |k: F64Key| k.0,
+    db_idx_double_store, db_idx_double_update, db_idx_double_remove, db_idx_double_find_secondary,
+    db_idx_double_find_primary, db_idx_double_lowerbound, db_idx_double_upperbound, db_idx_double_end,
+    db_idx_double_next, db_idx_double_previous
+);
+
+secondary_index!(
+    idx_long_double, [u8; 16], LongDoubleKey, |k: LongDoubleKey| k.0,
+    db_idx_long_double_store, db_idx_long_double_update, db_idx_long_double_remove,
+    db_idx_long_double_find_secondary, db_idx_long_double_find_primary, db_idx_long_double_lowerbound,
+    db_idx_long_double_upperbound, db_idx_long_double_end, db_idx_long_double_next, db_idx_long_double_previous
+);
+
+unsafe fn idx256_key(data: *const u128, data_len: u32) -> [u128; 2] {
+    if data_len != 2 {
+        fail("invalid size of secondary key array for idx256");
+    }
+    unsafe { [*data, *data.add(1)] }
+}
+
+unsafe fn idx256_write(data: *mut u128, key: [u128; 2]) {
+    unsafe {
+        *data = key[0];
+        *data.add(1) = key[1];
+    }
+}
+
+pub unsafe fn db_idx256_store(scope: u64, table: u64, payer: u64, id: u64, data: *const u128, data_len: u32) -> i32 {
+    let secondary = unsafe { idx256_key(data, data_len) };
+    with_db(|db, ctx| {
+        if ctx.receiver == 0 {
+            fail("db access violation");
+        }
+        db.idx256.store(&mut ctx.iterators.idx256, (ctx.receiver, scope, table), payer, id, secondary)
+    })
+}
+
+pub unsafe fn db_idx256_update(iterator: i32, payer: u64, data: *const u128, data_len: u32) {
+    let secondary = unsafe { idx256_key(data, data_len) };
+    with_db(|db, ctx| db.idx256.update(&mut ctx.iterators.idx256, ctx.receiver, iterator, payer, secondary))
+}
+
+pub unsafe fn db_idx256_remove(iterator: i32) {
+    with_db(|db, ctx| db.idx256.remove(&mut ctx.iterators.idx256, ctx.receiver, iterator))
+}
+
+pub unsafe fn db_idx256_find_secondary(
+    code: u64,
+    scope: u64,
+    table: u64,
+    data: *const u128,
+    data_len: u32,
+    primary: *mut u64,
+) -> i32 {
+    let secondary = unsafe { idx256_key(data, data_len) };
+    with_db(|db, ctx| {
+        db.idx256.find_secondary(&mut ctx.iterators.idx256, (code, scope, table), secondary, unsafe { &mut *primary })
+    })
+}
+
+pub unsafe fn db_idx256_find_primary(
+    code: u64,
+    scope: u64,
+    table: u64,
+    data: *mut u128,
+    data_len: u32,
+    primary: u64,
+) -> i32 {
+    let mut key = unsafe { idx256_key(data, data_len) };
+    let itr = with_db(|db, ctx| db.idx256.find_primary(&mut ctx.iterators.idx256, (code, scope, table), &mut key, primary));
+    unsafe { idx256_write(data, key) };
+    itr
+}
+
+pub unsafe fn db_idx256_lowerbound(
+    code: u64,
+    scope: u64,
+    table: u64,
+    data: *mut u128,
+    data_len: u32,
+    primary: *mut u64,
+) -> i32 {
+    let mut key = unsafe { idx256_key(data, data_len) };
+    let itr = with_db(|db, ctx| {
+        db.idx256.lowerbound(&mut ctx.iterators.idx256, (code, scope, table), &mut key, unsafe { &mut *primary })
+    });
+    unsafe { idx256_write(data, key) };
+    itr
+}
+
+pub unsafe fn db_idx256_upperbound(
+    code: u64,
+    scope: u64,
+    table: u64,
+    data: *mut u128,
+    data_len: u32,
+    primary: *mut u64,
+) -> i32 {
+    let mut key = unsafe { idx256_key(data, data_len) };
+    let itr = with_db(|db, ctx| {
+        db.idx256.upperbound(&mut ctx.iterators.idx256, (code, scope, table), &mut key, unsafe { &mut *primary })
+    });
+    unsafe { idx256_write(data, key) };
+    itr
+}
+
+pub unsafe fn db_idx256_end(code: u64, scope: u64, table: u64) -> i32 {
+    with_db(|db, ctx| db.idx256.end(&mut ctx.iterators.idx256, (code, scope, table)))
+}
+
+pub unsafe fn db_idx256_next(iterator: i32, primary: *mut u64) -> i32 {
+    with_db(|db, ctx| db.idx256.next(&mut ctx.iterators.idx256, iterator, unsafe { &mut *primary }))
+}
+
+pub unsafe fn db_idx256_previous(iterator: i32, primary: *mut u64) -> i32 {
+    with_db(|db, ctx| db.idx256.previous(&mut ctx.iterators.idx256, iterator, unsafe { &mut *primary }))
+}
+
+// ===== privileged =====
+
+pub unsafe fn is_privileged(account: u64) -> bool {
+    with_chain(|chain| chain.privileged.contains(&account))
+}
+
+pub unsafe fn set_privileged(account: u64, privileged: bool) {
+    with_chain(|chain| {
+        if privileged {
+            chain.privileged.insert(account);
+        } else {
+            chain.privileged.remove(&account);
+        }
+    })
+}
+
+pub unsafe fn get_resource_limits(
+    account: u64,
+    ram_bytes_ptr: *mut i64,
+    net_weight_ptr: *mut i64,
+    cpu_weight_ptr: *mut i64,
+) {
+    let (ram, net, cpu) = with_chain(|chain| chain.resource_limits.get(&account).copied().unwrap_or((-1, -1, -1)));
+    unsafe {
+        *ram_bytes_ptr = ram;
+        *net_weight_ptr = net;
+        *cpu_weight_ptr = cpu;
+    }
+}
+
+pub unsafe fn set_resource_limits(account: u64, ram_bytes: i64, net_weight: i64, cpu_weight: i64) {
+    with_chain(|chain| {
+        chain.resource_limits.insert(account, (ram_bytes, net_weight, cpu_weight));
+    })
+}
+
+pub unsafe fn get_active_producers(producers: *mut u64, datalen: u32) -> u32 {
+    let names: Vec<u8> = with_chain(|chain| {
+        chain
+            .active_producers
+            .iter()
+            .flat_map(|p| p.producer_name.raw().to_le_bytes())
+            .collect()
+    });
+    if datalen == 0 {
+        return names.len() as u32;
+    }
+    let len = names.len().min(datalen as usize);
+    unsafe { copy_out(&names[..len], producers as *mut _, len) as u32 }
+}
+
+pub unsafe fn set_proposed_producers(producer_data: *mut crate::c_void, producer_data_size: u32) -> i64 {
+    let data = unsafe { bytes(producer_data, producer_data_size as usize) };
+    let Ok(producers) = Vec::<ProducerKey>::unpack(data) else {
+        fail("set_proposed_producers: failed to unpack producers");
+    };
+    propose_producers(producers.into_iter().map(ProducerAuthority::from).collect())
+}
+
+pub unsafe fn set_proposed_producers_ex(
+    producer_data_format: u64,
+    producer_data: *mut crate::c_void,
+    producer_data_size: u32,
+) -> i64 {
+    match producer_data_format {
+        0 => unsafe { set_proposed_producers(producer_data, producer_data_size) },
+        1 => {
+            let data = unsafe { bytes(producer_data, producer_data_size as usize) };
+            let Ok(producers) = Vec::<ProducerAuthority>::unpack(data) else {
+                fail("set_proposed_producers_ex: failed to unpack producers");
+            };
+            propose_producers(producers)
+        }
+        _ => fail("set_proposed_producers_ex: unknown producer data format"),
+    }
+}
+
+/// Validates `producers` like the node does and stores them as the
+/// proposed schedule, returning its version or -1 if it is already active.
+fn propose_producers(producers: Vec<ProducerAuthority>) -> i64 {
+    if producers.is_empty() {
+        fail("producer schedule cannot be empty");
+    }
+    if producers.len() > MAX_PRODUCERS {
+        fail("producer schedule exceeds the maximum producer count");
+    }
+    let mut names = BTreeSet::new();
+    for producer in &producers {
+        if !with_chain(|chain| chain.accounts.contains(&producer.producer_name.raw())) {
+            fail("producer schedule includes a nonexisting account");
+        }
+        if !producer.authority.is_valid() {
+            fail("producer schedule includes an invalid key");
+        }
+        if !names.insert(producer.producer_name) {
+            fail("duplicate producer name in producer schedule");
+        }
+    }
+    with_chain(|chain| {
+        if chain.active_producers == producers {
+            return -1;
+        }
+        chain.proposed_producers = Some(producers);
+        i64::from(chain.schedule_version) + 1
+    })
+}
+
+pub unsafe fn get_blockchain_parameters_packed(data: *mut crate::c_void, datalen: u32) -> u32 {
+    let packed = with_chain(|chain| chain.blockchain_parameters.pack().unwrap());
+    if datalen == 0 {
+        return packed.len() as u32;
+    }
+    if (datalen as usize) < packed.len() {
+        return 0;
+    }
+    unsafe { copy_out(&packed, data, packed.len()) as u32 }
+}
+
+pub unsafe fn set_blockchain_parameters_packed(data: *mut crate::c_void, datalen: u32) {
+    let Ok(params) = BlockchainParameters::unpack(unsafe { bytes(data, datalen as usize) }) else {
+        fail("set_blockchain_parameters_packed: failed to unpack parameters");
+    };
+    with_chain(|chain| chain.blockchain_parameters = params);
+}
+
+unsafe fn feature_digest(feature_digest: *const crate::c_void) -> Checksum256 {
+    let mut digest = [0_u8; 32];
+    digest.copy_from_slice(unsafe { bytes(feature_digest, 32) });
+    FixedBytes(digest)
+}
+
+pub unsafe fn is_feature_activated(feature_digest: *const crate::c_void) -> bool {
+    let digest = unsafe { self::feature_digest(feature_digest) };
+    with_chain(|chain| chain.activated_features.contains(&digest))
+}
+
+/// Any digest is accepted as a known feature.
+pub unsafe fn preactivate_feature(feature_digest: *const crate::c_void) {
+    let digest = unsafe { self::feature_digest(feature_digest) };
+    with_chain(|chain| {
+        if chain.activated_features.contains(&digest) {
+            fail("protocol feature is already activated");
+        }
+        if chain.preactivated_features.contains(&digest) {
+            fail("protocol feature is already pre-activated");
+        }
+        chain.preactivated_features.push(digest);
+    })
+}
+
+// ===== system =====
+
+pub unsafe fn current_time() -> u64 {
+    with_chain(|chain| chain.now)
+}
+
+// ===== transaction =====
+
+/// Keys are ignored: a transaction is authorized when every declared
+/// authorization is among the provided permissions.
+pub unsafe fn check_transaction_authorization(
+    trx_msg: *mut crate::c_void,
+    trx_len: usize,
+    _pubkeys_msg: *mut crate::c_void,
+    _pubkeys_len: usize,
+    perms_msg: *mut crate::c_void,
+    perms_len: usize,
+) -> u32 {
+    let Ok(trx) = Transaction::unpack(unsafe { bytes(trx_msg, trx_len) }) else {
+        fail("check_transaction_authorization: failed to unpack transaction");
+    };
+    let perms = if perms_len == 0 {
+        BTreeSet::new()
+    } else {
+        match BTreeSet::<PermissionLevel>::unpack(unsafe { bytes(perms_msg, perms_len) }) {
+            Ok(perms) => perms,
+            Err(_) => fail("check_transaction_authorization: failed to unpack permissions"),
+        }
+    };
+    let authorized = trx
+        .context_free_actions
+        .iter()
+        .chain(trx.actions.iter())
+        .flat_map(|action| action.authorization.iter())
+        .all(|auth| perms.contains(auth));
+    authorized as u32
+}
+
+fn with_transaction<R>(f: impl FnOnce(&Transaction, &[Vec<u8>]) -> R) -> R {
+    with_chain(|chain| match &chain.transaction {
+        Some(trx) => f(trx, &chain.context_free_data),
+        None => fail("no transaction is being executed"),
+    })
+}
+
+/// Copies `data` out if `size` is non-zero, otherwise returns its size.
+unsafe fn read_sized(data: &[u8], buffer: *mut crate::c_void, size: usize) -> usize {
+    if size == 0 {
+        data.len()
+    } else {
+        unsafe { copy_out(data, buffer, size) }
+    }
+}
+
+pub unsafe fn read_transaction(buffer: *mut crate::c_void, size: usize) -> usize {
+    let packed = with_transaction(|trx, _| trx.pack().unwrap());
+    unsafe { read_sized(&packed, buffer, size) }
+}
+
+pub unsafe fn transaction_size() -> usize {
+    with_transaction(|trx, _| trx.num_bytes())
+}
+
+pub unsafe fn tapos_block_num() -> i32 {
+    with_transaction(|trx, _| i32::from(trx.header.ref_block_num))
+}
+
+pub unsafe fn tapos_block_prefix() -> i32 {
+    with_transaction(|trx, _| trx.header.ref_block_prefix as i32)
+}
+
+pub unsafe fn expiration() -> u32 {
+    with_transaction(|trx, _| trx.header.expiration.sec_since_epoch())
+}
+
+pub unsafe fn get_action(action_type: u32, index: u32, buffer: *mut crate::c_void, size: usize) -> i32 {
+    let packed = with_transaction(|trx, _| {
+        let actions = match action_type {
+            0 => &trx.context_free_actions,
+            1 => &trx.actions,
+            _ => fail("get_action: invalid action type"),
+        };
+        actions.get(index as usize).map(|action| action.pack().unwrap())
+    });
+    match packed {
+        Some(packed) => unsafe { read_sized(&packed, buffer, size) as i32 },
+        None => -1,
+    }
+}
+
+pub unsafe fn get_context_free_data(index: u32, buffer: *mut crate::c_void, size: usize) -> i32 {
+    if !with_context(|ctx| ctx.context_free) {
+        fail("this API may only be called from context_free apply");
+    }
+    let data = with_transaction(|_, data| data.get(index as usize).cloned());
+    match data {
+        Some(data) => unsafe { read_sized(&data, buffer, size) as i32 },
+        None => -1,
+    }
+}
+
+/// The harness includes every transaction in a block as soon as it is pushed.
+pub unsafe fn publication_time() -> u64 {
+    with_chain(|chain| chain.now)
+}
+
+/// The payer is ignored: the harness doesn't meter RAM.
+pub unsafe fn send_deferred(
+    sender_id: *const u128,
+    _payer: u64,
+    data: *mut crate::c_void,
+    len: usize,
+    replace_existing: u32,
+) {
+    let sender_id = unsafe { *sender_id };
+    let Ok(transaction) = Transaction::unpack(unsafe { bytes(data, len) }) else {
+        fail("send_deferred: failed to unpack transaction");
+    };
+    let sender = with_context(|ctx| ctx.receiver);
+    with_chain(|chain| {
+        let existing = chain
+            .deferred
+            .iter()
+            .position(|d| d.sender == sender && d.sender_id == sender_id);
+        if let Some(i) = existing {
+            if replace_existing == 0 {
+                fail("deferred transaction with the same sender_id and payer already exists");
+            }
+            chain.deferred.remove(i);
+        }
+        let delay = u64::from(transaction.header.delay_sec.0) * 1_000_000;
+        chain.deferred.push(DeferredTransaction {
+            sender,
+            sender_id,
+            run_at: chain.now + delay,
+            transaction,
+        });
+    });
+}
+
+pub unsafe fn cancel_deferred(sender_id: *const u128) -> i32 {
+    let sender_id = unsafe { *sender_id };
+    let sender = with_context(|ctx| ctx.receiver);
+    with_chain(|chain| {
+        let before = chain.deferred.len();
+        chain
+            .deferred
+            .retain(|d| d.sender != sender || d.sender_id != sender_id);
+        i32::from(chain.deferred.len() != before)
+    })
+}
@@ -0,0 +1,517 @@
+//! An in-memory chain for running contracts natively under `cargo test`.
+//!
+//! With the `testing` feature enabled every host import is served by
+//! [`host`] instead of the VM. Contracts built with `#[contract]` expose a
+//! plain `apply` function on non-wasm targets which can be deployed to a
+//! [`TestChain`]:
+//!
+//! ```ignore
+//! let mut chain = TestChain::new();
+//! chain.create_account(name!("alice"));
+//! chain.deploy(name!("pulse.token"), crate::apply);
+//! chain.push_action(Action::new(
+//!     vec![PermissionLevel::new(name!("pulse.token"), name!("active"))],
+//!     name!("pulse.token"),
+//!     name!("create"),
+//!     (name!("alice"), max_supply).pack().unwrap(),
+//! ))?;
+//! ```
+//!
+//! The chain lives in thread-local storage, so each test gets its own and
+//! creating a new `TestChain` resets it. Authorizations attached to an
+//! action are taken at face value and inline actions inherit no extra
+//! checks.
+
+mod database;
+pub(crate) mod host;
+
+use core::{fmt, marker::PhantomData};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+pub use host::ApplyFn;
+use host::{replace_context, with_chain, ApplyContext, ChainState, DeferredTransaction};
+
+use crate::{
+    contracts::{Action, BlockchainParameters, PermissionLevel},
+    core::{
+        Checksum256, Microseconds, Name, OnError, ProducerAuthority, TimePoint, TimePointSec,
+        Transaction, TransactionHeader,
+    },
+    Read, VarUint32, Write,
+};
+
+/// Inline actions may nest at most this deep.
+const MAX_INLINE_DEPTH: usize = 4;
+
+/// How long after being pushed a transaction built by `push_transaction`
+/// expires.
+const DEFAULT_EXPIRATION_SECONDS: u32 = 30;
+
+/// The outcome of a single `apply` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionTrace {
+    pub receiver: Name,
+    pub action: Action,
+    /// Everything the receiver printed while handling the action.
+    pub console: String,
+    /// What the receiver passed to `set_action_return_value`, if anything.
+    pub return_value: Vec<u8>,
+}
+
+/// Every `apply` call made while executing a transaction, in execution order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionTrace {
+    pub action_traces: Vec<ActionTrace>,
+}
+
+impl TransactionTrace {
+    /// Accounts that received `action`, including notifications.
+    pub fn receivers_of(&self, account: Name, action: Name) -> Vec<Name> {
+        self.action_traces
+            .iter()
+            .filter(|t| t.action.account == account && t.action.name == action)
+            .map(|t| t.receiver)
+            .collect()
+    }
+
+    /// The value returned by the first execution of `action` by `account`
+    /// itself (not a notification), e.g. an inline action sent by the
+    /// contract under test.
+    ///
+    /// Panics if the returned bytes do not decode as a `T`.
+    pub fn return_value<T: Read>(&self, account: Name, action: Name) -> Option<T> {
+        self.action_traces
+            .iter()
+            .find(|t| t.receiver == account && t.action.account == account && t.action.name == action)
+            .map(|t| T::unpack(&t.return_value).expect("failed to read action return value"))
+    }
+
+    /// Console output of every action, in execution order.
+    pub fn console(&self) -> String {
+        self.action_traces.iter().map(|t| t.console.as_str()).collect()
+    }
+}
+
+/// What became of a deferred transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeferredTrace {
+    pub sender: Name,
+    pub sender_id: u128,
+    pub result: Result<TransactionTrace, ActionError>,
+    /// If the transaction failed, how its sender handled `onerror`.
+    pub onerror: Option<Result<TransactionTrace, ActionError>>,
+}
+
+/// Why a transaction was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionError {
+    /// The receiver whose `apply` failed.
+    pub receiver: Name,
+    /// The failing action.
+    pub action: Action,
+    /// The `check` message, or the panic message for other failures.
+    pub message: String,
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}::{} failed on {}: {}",
+            self.action.account.to_string(),
+            self.action.name.to_string(),
+            self.receiver.to_string(),
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+pub struct TestChain {
+    // The chain is thread-local; keep the handle on the thread that owns it.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Default for TestChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestChain {
+    /// Resets the chain of the current thread to its genesis state.
+    pub fn new() -> Self {
+        with_chain(|chain| *chain = ChainState::default());
+        replace_context(ApplyContext::default());
+        Self {
+            _not_send: PhantomData,
+        }
+    }
+
+    pub fn create_account(&mut self, account: Name) {
+        with_chain(|chain| chain.accounts.insert(account.raw()));
+    }
+
+    /// Creates `account` if needed and sets its code to `apply`.
+    pub fn deploy(&mut self, account: Name, apply: ApplyFn) {
+        with_chain(|chain| {
+            chain.accounts.insert(account.raw());
+            chain.contracts.insert(account.raw(), apply);
+        });
+    }
+
+    pub fn set_privileged(&mut self, account: Name, privileged: bool) {
+        with_chain(|chain| {
+            if privileged {
+                chain.privileged.insert(account.raw());
+            } else {
+                chain.privileged.remove(&account.raw());
+            }
+        });
+    }
+
+    pub fn now(&self) -> TimePoint {
+        TimePoint::new(Microseconds::new(with_chain(|chain| chain.now) as i64))
+    }
+
+    pub fn set_time(&mut self, time: TimePoint) {
+        with_chain(|chain| chain.now = time.time_since_epoch().count() as u64);
+    }
+
+    pub fn advance_time(&mut self, by: Microseconds) {
+        with_chain(|chain| chain.now = chain.now.saturating_add_signed(by.count()));
+    }
+
+    /// The active producer schedule and its version.
+    pub fn active_producers(&self) -> (u32, Vec<ProducerAuthority>) {
+        with_chain(|chain| (chain.schedule_version, chain.active_producers.clone()))
+    }
+
+    /// The schedule last proposed with `set_proposed_producers`, if it isn't
+    /// active yet.
+    pub fn proposed_producers(&self) -> Option<Vec<ProducerAuthority>> {
+        with_chain(|chain| chain.proposed_producers.clone())
+    }
+
+    /// Makes the proposed schedule active, as happens once the block that
+    /// proposed it is irreversible. Returns `false` if none was proposed.
+    pub fn activate_proposed_producers(&mut self) -> bool {
+        with_chain(|chain| match chain.proposed_producers.take() {
+            Some(producers) => {
+                chain.active_producers = producers;
+                chain.schedule_version += 1;
+                true
+            }
+            None => false,
+        })
+    }
+
+    pub fn blockchain_parameters(&self) -> BlockchainParameters {
+        with_chain(|chain| chain.blockchain_parameters.clone())
+    }
+
+    pub fn is_feature_activated(&self, feature_digest: Checksum256) -> bool {
+        with_chain(|chain| chain.activated_features.contains(&feature_digest))
+    }
+
+    /// Activates or deactivates a protocol feature directly, without going
+    /// through `preactivate_feature`.
+    pub fn set_feature_activated(&mut self, feature_digest: Checksum256, activated: bool) {
+        with_chain(|chain| {
+            chain.preactivated_features.retain(|f| *f != feature_digest);
+            if activated {
+                chain.activated_features.insert(feature_digest);
+            } else {
+                chain.activated_features.remove(&feature_digest);
+            }
+        });
+    }
+
+    /// Features pre-activated with `preactivate_feature` and not yet active.
+    pub fn preactivated_features(&self) -> Vec<Checksum256> {
+        with_chain(|chain| chain.preactivated_features.clone())
+    }
+
+    /// Activates the pre-activated features, as the next block does.
+    pub fn activate_preactivated_features(&mut self) {
+        with_chain(|chain| {
+            let features = core::mem::take(&mut chain.preactivated_features);
+            chain.activated_features.extend(features);
+        });
+    }
+
+    /// Pushes a transaction with a single action.
+    pub fn push_action(&mut self, action: Action) -> Result<TransactionTrace, ActionError> {
+        self.push_transaction(vec![action])
+    }
+
+    /// Executes `actions` in order, together with their notifications and
+    /// inline actions. If any of them fails, no state change is kept.
+    pub fn push_transaction(&mut self, actions: Vec<Action>) -> Result<TransactionTrace, ActionError> {
+        let transaction = Transaction {
+            header: TransactionHeader {
+                expiration: TimePointSec::from(self.now()) + DEFAULT_EXPIRATION_SECONDS,
+                ref_block_num: 0,
+                ref_block_prefix: 0,
+                max_net_usage_words: VarUint32(0),
+                max_cpu_usage: 0,
+                delay_sec: VarUint32(0),
+            },
+            context_free_actions: Vec::new(),
+            actions,
+            transaction_extensions: Vec::new(),
+        };
+        self.push_full_transaction(transaction, Vec::new())
+    }
+
+    /// Like [`push_transaction`](Self::push_transaction), with control over
+    /// the header and context-free actions. The context-free actions run
+    /// first; like the others they may touch tables, which a node would
+    /// reject.
+    pub fn push_full_transaction(
+        &mut self,
+        transaction: Transaction,
+        context_free_data: Vec<Vec<u8>>,
+    ) -> Result<TransactionTrace, ActionError> {
+        let snapshot = with_chain(|chain| chain.clone());
+        let actions: Vec<(Action, bool)> = transaction
+            .context_free_actions
+            .iter()
+            .map(|action| (action.clone(), true))
+            .chain(
+                transaction
+                    .actions
+                    .iter()
+                    .map(|action| (action.clone(), false)),
+            )
+            .collect();
+        with_chain(|chain| {
+            chain.transaction = Some(transaction);
+            chain.context_free_data = context_free_data;
+        });
+        let mut trace = TransactionTrace::default();
+        let result = actions
+            .into_iter()
+            .try_for_each(|(action, context_free)| execute(action, &mut trace, 0, context_free));
+        replace_context(ApplyContext::default());
+        with_chain(|chain| {
+            chain.transaction = None;
+            chain.context_free_data = Vec::new();
+        });
+        match result {
+            Ok(()) => Ok(trace),
+            Err(err) => {
+                with_chain(|chain| *chain = snapshot);
+                Err(err)
+            }
+        }
+    }
+
+    /// The `(sender, sender_id)` of every deferred transaction waiting to
+    /// run, in the order they were sent.
+    pub fn deferred_transactions(&self) -> Vec<(Name, u128)> {
+        with_chain(|chain| {
+            chain
+                .deferred
+                .iter()
+                .map(|d| (Name::new(d.sender), d.sender_id))
+                .collect()
+        })
+    }
+
+    /// Runs every deferred transaction whose delay is over, in the order
+    /// they were sent. When one fails its sender is notified with an
+    /// `onerror` action from `pulse`.
+    pub fn run_deferred(&mut self) -> Vec<DeferredTrace> {
+        let due = with_chain(|chain| {
+            let now = chain.now;
+            let (due, pending) = chain.deferred.drain(..).partition(|d| d.run_at <= now);
+            chain.deferred = pending;
+            due
+        });
+        due.into_iter()
+            .map(|deferred: DeferredTransaction| {
+                let sender = Name::new(deferred.sender);
+                let packed = deferred.transaction.pack().unwrap();
+                let result = self.push_full_transaction(deferred.transaction, Vec::new());
+                let onerror = result.is_err().then(|| {
+                    let data = OnError {
+                        sender_id: deferred.sender_id,
+                        sent_trx: packed,
+                    };
+                    self.notify_onerror(sender, data)
+                });
+                DeferredTrace {
+                    sender,
+                    sender_id: deferred.sender_id,
+                    result,
+                    onerror,
+                }
+            })
+            .collect()
+    }
+
+    /// Delivers `onerror` to `sender` alone, as `pulse` does, with its
+    /// inline actions.
+    fn notify_onerror(&mut self, sender: Name, data: OnError) -> Result<TransactionTrace, ActionError> {
+        let action = Action::new(
+            vec![PermissionLevel::new(sender, Name::new(crate::name_raw!("active")))],
+            Name::new(crate::name_raw!("pulse")),
+            Name::new(crate::name_raw!("onerror")),
+            data.pack().unwrap(),
+        );
+        let snapshot = with_chain(|chain| chain.clone());
+        let mut trace = TransactionTrace::default();
+        let result = apply_one(sender.raw(), &action, false).and_then(|ctx| {
+            trace.action_traces.push(ActionTrace {
+                receiver: sender,
+                action: action.clone(),
+                console: ctx.console,
+                return_value: ctx.return_value,
+            });
+            ctx.inline_actions
+                .into_iter()
+                .try_for_each(|inline| execute(inline, &mut trace, 1, false))
+        });
+        match result {
+            Ok(()) => Ok(trace),
+            Err(err) => {
+                with_chain(|chain| *chain = snapshot);
+                Err(err)
+            }
+        }
+    }
+
+    /// Decodes every row of `code`'s `table` in `scope`, ordered by primary key.
+    pub fn get_table_rows<T: Read>(&self, code: Name, scope: u64, table: Name) -> Vec<T> {
+        with_chain(|chain| {
+            chain
+                .db
+                .primary
+                .rows(&(code.raw(), scope, table.raw()))
+                .map(|(_, row)| T::unpack(&row.data).expect("failed to read row"))
+                .collect()
+        })
+    }
+
+    pub fn get_table_row<T: Read>(&self, code: Name, scope: u64, table: Name, primary_key: u64) -> Option<T> {
+        with_chain(|chain| {
+            chain
+                .db
+                .primary
+                .row(&(code.raw(), scope, table.raw()), primary_key)
+                .map(|row| T::unpack(&row.data).expect("failed to read row"))
+        })
+    }
+}
+
+/// Runs `action` with its notifications and inline actions. Only the
+/// transaction's own context-free actions are `context_free`.
+fn execute(
+    action: Action,
+    trace: &mut TransactionTrace,
+    depth: usize,
+    context_free: bool,
+) -> Result<(), ActionError> {
+    if depth > MAX_INLINE_DEPTH {
+        return Err(ActionError {
+            receiver: action.account,
+            action,
+            message: "max inline action depth exceeded".to_string(),
+        });
+    }
+
+    let mut receivers = vec![action.account.raw()];
+    let mut inline_actions = Vec::new();
+    let mut i = 0;
+    while i < receivers.len() {
+        let receiver = receivers[i];
+        let ctx = apply_one(receiver, &action, context_free)?;
+        for account in ctx.notified {
+            if !receivers.contains(&account) {
+                receivers.push(account);
+            }
+        }
+        inline_actions.extend(ctx.inline_actions);
+        trace.action_traces.push(ActionTrace {
+            receiver: Name::new(receiver),
+            action: action.clone(),
+            console: ctx.console,
+            return_value: ctx.return_value,
+        });
+        i += 1;
+    }
+
+    inline_actions
+        .into_iter()
+        .try_for_each(|inline| execute(inline, trace, depth + 1, false))
+}
+
+/// Runs `receiver`'s `apply` for `action`, returning the context it left.
+fn apply_one(
+    receiver: u64,
+    action: &Action,
+    context_free: bool,
+) -> Result<ApplyContext, ActionError> {
+    let error = |message: String| ActionError {
+        receiver: Name::new(receiver),
+        action: action.clone(),
+        message,
+    };
+
+    let (exists, apply) = with_chain(|chain| {
+        (
+            chain.accounts.contains(&receiver),
+            chain.contracts.get(&receiver).copied(),
+        )
+    });
+    if !exists {
+        return Err(error("account does not exist".to_string()));
+    }
+
+    replace_context(ApplyContext {
+        receiver,
+        action: action.clone(),
+        context_free,
+        ..ApplyContext::default()
+    });
+    let result = match apply {
+        Some(apply) => panic::catch_unwind(AssertUnwindSafe(|| {
+            apply(receiver, action.account.raw(), action.name.raw())
+        })),
+        None => Ok(()),
+    };
+    let ctx = replace_context(ApplyContext::default());
+
+    match result {
+        Ok(()) => Ok(ctx),
+        Err(payload) => {
+            let message = if let Some(s) = payload.downcast_ref::<String>() {
+                s.clone()
+            } else if let Some(s) = payload.downcast_ref::<&str>() {
+                s.to_string()
+            } else {
+                "panic without message".to_string()
+            };
+            Err(error(message))
+        }
+    }
+}
+
+/// Runs `f` as if it were executing inside `receiver`'s `apply`, e.g. to
+/// read tables through [`MultiIndex`](crate::core::MultiIndex).
+pub fn with_receiver<R>(receiver: Name, f: impl FnOnce() -> R) -> R {
+    let previous = replace_context(ApplyContext {
+        receiver: receiver.raw(),
+        ..ApplyContext::default()
+    });
+    let result = f();
+    replace_context(previous);
+    result
+}
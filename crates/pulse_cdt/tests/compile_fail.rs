@@ -0,0 +1,6 @@
+//! Derives that must be rejected at compile time.
+
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
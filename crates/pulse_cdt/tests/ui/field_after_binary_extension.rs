@@ -0,0 +1,10 @@
+use pulse_cdt::{BinaryExtension, NumBytes, Read, Write};
+
+#[derive(Read, Write, NumBytes)]
+struct Row {
+    id: u64,
+    extra: BinaryExtension<u64>,
+    memo: u64,
+}
+
+fn main() {}
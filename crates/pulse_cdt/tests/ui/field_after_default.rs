@@ -0,0 +1,11 @@
+use pulse_cdt::{NumBytes, Read, Write};
+
+#[derive(Read, Write, NumBytes)]
+struct Row {
+    id: u64,
+    #[pulse(default)]
+    extra: u64,
+    memo: u64,
+}
+
+fn main() {}
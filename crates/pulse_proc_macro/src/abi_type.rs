@@ -0,0 +1,113 @@
+use alloc::{format, string::ToString, vec::Vec};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, Fields, GenericParam, Generics, Ident, Path};
+
+use crate::internal::{get_fields_options, AbiOptions};
+
+/// The `AbiType` impl that goes along a derived `Write`.
+///
+/// Structs describe their fields in declaration order. Enums become
+/// variants tagged by position: a variant holding a single unnamed field is
+/// that field's type, any other is a `<Enum><Variant>` struct of its fields.
+pub fn expand(
+    root: &Path,
+    name: &Ident,
+    generics: &Generics,
+    data: &Data,
+    options: &AbiOptions,
+) -> TokenStream {
+    let mut generics = generics.clone();
+    let mut type_params = Vec::new();
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param.bounds.push(parse_quote!(#root::abi::AbiType));
+            type_params.push(type_param.ident.clone());
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = if let Some(builtin) = &options.builtin {
+        quote! {
+            ::core::convert::Into::into(#builtin)
+        }
+    } else {
+        let base = options
+            .name
+            .as_ref()
+            .map(|n| n.value())
+            .unwrap_or_else(|| name.to_string());
+        // Each instantiation of a generic type is its own struct.
+        let abi_name = if type_params.is_empty() {
+            quote! { ::core::convert::Into::into(#base) }
+        } else {
+            quote! {
+                #root::abi::generic_abi_name(#base, &[#(<#type_params as #root::abi::AbiType>::abi_type(abi)),*])
+            }
+        };
+        match data {
+            Data::Struct(data) => {
+                let fields = describe_fields(root, &data.fields);
+                quote! {
+                    let name: #root::abi::__String = #abi_name;
+                    abi.add_struct_with(&name, |abi| #fields)
+                }
+            }
+            Data::Enum(data) => {
+                let alternatives = data.variants.iter().map(|v| match &v.fields {
+                    Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                        let ty = &fields.unnamed[0].ty;
+                        quote! { <#ty as #root::abi::AbiType>::abi_type(abi) }
+                    }
+                    fields => {
+                        let suffix = v.ident.to_string();
+                        let fields = describe_fields(root, fields);
+                        quote! {
+                            abi.add_struct_with(
+                                &#root::abi::variant_struct_name(&name, #suffix),
+                                |abi| #fields,
+                            )
+                        }
+                    }
+                });
+                quote! {
+                    let name: #root::abi::__String = #abi_name;
+                    abi.add_variant_with(&name, |abi| [#(#alternatives),*])
+                }
+            }
+            Data::Union(_) => unimplemented!(),
+        }
+    };
+
+    quote! {
+        #[automatically_derived]
+        #[allow(unused_qualifications)]
+        impl #impl_generics #root::abi::AbiType for #name #ty_generics #where_clause {
+            fn abi_type(abi: &mut #root::abi::Abi) -> #root::abi::__String {
+                #body
+            }
+        }
+    }
+}
+
+/// An array of `(field name, ABI type)`, `field_<i>` naming unnamed fields.
+/// Skipped fields are left out, and binary extensions get a `$` suffix.
+fn describe_fields(root: &Path, fields: &Fields) -> TokenStream {
+    let options = get_fields_options(fields);
+    let entries = fields
+        .iter()
+        .zip(&options)
+        .enumerate()
+        .filter(|(_, (_, options))| !options.skip)
+        .map(|(i, (f, options))| {
+            let field = options.name(f).unwrap_or_else(|| format!("field_{i}"));
+            let ty = &f.ty;
+            let ty = quote! { <#ty as #root::abi::AbiType>::abi_type(abi) };
+            if options.default {
+                quote! { (#field, #root::abi::binary_extension_abi_name(#ty)) }
+            } else {
+                quote! { (#field, #ty) }
+            }
+        });
+    quote! { [#(#entries),*] }
+}
@@ -4,14 +4,15 @@ use alloc::{
     vec::Vec,
 };
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Group, TokenStream as TokenStream2, TokenTree};
 use quote::{format_ident, quote};
 use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input,
+    punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, FnArg, ImplItem, ImplItemMethod, ItemImpl, Lit, Meta, MetaList, MetaNameValue,
-    NestedMeta, Path, Result, Type,
+    Attribute, FnArg, ImplItem, ImplItemMethod, ItemImpl, Lifetime, Lit, Meta, MetaList,
+    MetaNameValue, NestedMeta, Pat, Path, Result, ReturnType, Token, Type,
 };
 
 pub fn contract_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -31,47 +32,66 @@ pub fn contract_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// Global options for #[contract]
 struct ContractArgs {
     decoder: Option<Path>, // generic fn<T>() -> T
+    /// `tables(ACCOUNTS, STATS)`: table definitions described in the ABI.
+    tables: Vec<Path>,
 }
 
 impl Parse for ContractArgs {
     fn parse(input: ParseStream<'_>) -> Result<Self> {
-        if input.is_empty() {
-            return Ok(Self { decoder: None });
-        }
-        // Parse: decoder = <path>
-        let meta: Meta = input.parse()?;
-        match meta {
-            Meta::NameValue(MetaNameValue {
-                path,
-                lit: Lit::Str(s),
-                ..
-            }) if path.is_ident("decoder") => {
-                let p: Path = s.parse()?;
-                Ok(Self { decoder: Some(p) })
-            }
-            Meta::List(MetaList { path, nested, .. }) if path.is_ident("decoder") => {
-                // allow #[contract(decoder(path::to::decode))]
-                let mut iter = nested.into_iter();
-                let first = iter
-                    .next()
-                    .ok_or_else(|| syn::Error::new(path.span(), "expected decoder path"))?;
-                if iter.next().is_some() {
+        let mut args = Self {
+            decoder: None,
+            tables: Vec::new(),
+        };
+        let metas = Punctuated::<Meta, Token![,]>::parse_terminated(input)?;
+        for meta in metas {
+            match meta {
+                // Parse: decoder = <path>
+                Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(s),
+                    ..
+                }) if path.is_ident("decoder") => {
+                    args.decoder = Some(s.parse()?);
+                }
+                Meta::List(MetaList { path, nested, .. }) if path.is_ident("decoder") => {
+                    // allow #[contract(decoder(path::to::decode))]
+                    let mut iter = nested.into_iter();
+                    let first = iter
+                        .next()
+                        .ok_or_else(|| syn::Error::new(path.span(), "expected decoder path"))?;
+                    if iter.next().is_some() {
+                        return Err(syn::Error::new(
+                            path.span(),
+                            "expected a single path for decoder",
+                        ));
+                    }
+                    args.decoder = match first {
+                        NestedMeta::Meta(Meta::Path(p)) => Some(p),
+                        other => return Err(syn::Error::new(other.span(), "expected a path")),
+                    };
+                }
+                Meta::List(MetaList { path, nested, .. }) if path.is_ident("tables") => {
+                    for table in nested {
+                        match table {
+                            NestedMeta::Meta(Meta::Path(p)) => args.tables.push(p),
+                            other => {
+                                return Err(syn::Error::new(
+                                    other.span(),
+                                    "expected the path of a table definition",
+                                ))
+                            }
+                        }
+                    }
+                }
+                meta => {
                     return Err(syn::Error::new(
-                        path.span(),
-                        "expected a single path for decoder",
-                    ));
+                        meta.span(),
+                        r#"expected `decoder = "path::to::decode"`, `decoder(path::to::decode)` or `tables(...)`"#,
+                    ))
                 }
-                let p = match first {
-                    NestedMeta::Meta(Meta::Path(p)) => p,
-                    other => return Err(syn::Error::new(other.span(), "expected a path")),
-                };
-                Ok(Self { decoder: Some(p) })
             }
-            _ => Err(syn::Error::new(
-                meta.span(),
-                r#"expected `decoder = "path::to::decode"` or `decoder(path::to::decode)`"#,
-            )),
         }
+        Ok(args)
     }
 }
 
@@ -230,18 +250,6 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
 
         let args_len = arg_types.len();
 
-        let tuple_ty = tuple_type_tokens(&arg_types);
-
-        // Choose decoder path:
-        // - explicit per-action
-        // - else global impl-level
-        // - else default pulse_cdt::core::unpack_action_data
-        let decoder_path: TokenStream2 = a
-            .decoder
-            .clone()
-            .map(|p| quote!(#p))
-            .unwrap_or_else(|| quote!(::pulse_cdt::contracts::read_action_data));
-
         // Generate the call depending on receiver kind
         let call_no_args = match a.rk {
             ReceiverKind::None => quote! { <#self_ty>::#method_ident() },
@@ -249,6 +257,7 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
             ReceiverKind::MutRef => quote! { __instance.#method_ident() },
             _ => unreachable!(),
         };
+        let call_no_args = with_return_value(&a.method, call_no_args);
 
         if args_len == 0 {
             // no-arg action: no decode needed
@@ -258,11 +267,17 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
                 }
             }
         } else {
-            // decode tuple, destructure, call
-            let tmp_ident = format_ident!("__args");
+            // decode, call
             let bind_idents: Vec<proc_macro2::Ident> =
                 (0..args_len).map(|i| format_ident!("__a{}", i)).collect();
 
+            // Choose decoder:
+            // - explicit per-action
+            // - else global impl-level
+            // - else default pulse_cdt::contracts::read_action_arg, which names
+            //   the argument that fails to decode
+            let decode = decode_args(a.decoder.as_ref(), action_name_str, &a.method, &bind_idents);
+
             let call_with_args = match a.rk {
                 ReceiverKind::None => {
                     let args = quote! { #(#bind_idents),* };
@@ -275,19 +290,11 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
                 }
                 _ => unreachable!(),
             };
-
-            let bind_pat = if args_len == 1 {
-                let a0 = &bind_idents[0];
-                quote! { ( #a0 , ) } // <-- note the trailing comma
-            } else {
-                quote! { ( #(#bind_idents),* ) }
-            };
+            let call_with_args = with_return_value(&a.method, call_with_args);
 
             quote! {
                 #kw action == pulse_cdt::name_raw!(#action_name_str) {
-                    type __Args = #tuple_ty;
-                    let #tmp_ident: __Args = #decoder_path::<__Args>();
-                    let #bind_pat = #tmp_ident;
+                    #decode
                     #call_with_args;
                 }
             }
@@ -322,13 +329,6 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
             .collect();
 
         let args_len = arg_types.len();
-        let tuple_ty = tuple_type_tokens(&arg_types);
-
-        let decoder_path: TokenStream2 = h
-            .decoder
-            .clone()
-            .map(|p| quote!(#p))
-            .unwrap_or_else(|| quote!(::pulse_cdt::contracts::read_action_data));
 
         // Build the match condition for code/action against the (possibly wildcarded) pattern.
         let code_cond: TokenStream2 = match &h.acct_pat {
@@ -354,10 +354,16 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
                 }
             }
         } else {
-            let tmp_ident = format_ident!("__nargs");
             let bind_idents: Vec<proc_macro2::Ident> =
                 (0..args_len).map(|i| format_ident!("__n{}", i)).collect();
 
+            // Errors are reported against the notifying action if it's known.
+            let label = match &h.action_pat {
+                NotifyPat::Wildcard => h.method.sig.ident.to_string(),
+                NotifyPat::Name(s) => s.clone(),
+            };
+            let decode = decode_args(h.decoder.as_ref(), &label, &h.method, &bind_idents);
+
             let call_with_args = match h.rk {
                 ReceiverKind::None => {
                     let args = quote! { #(#bind_idents),* };
@@ -370,18 +376,9 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
                 _ => unreachable!(),
             };
 
-            let bind_pat = if args_len == 1 {
-                let a0 = &bind_idents[0];
-                quote! { ( #a0 , ) }
-            } else {
-                quote! { ( #(#bind_idents),* ) }
-            };
-
             quote! {
                 #kw (#code_cond) && (#action_cond) {
-                    type __NArgs = #tuple_ty;
-                    let #tmp_ident: __NArgs = #decoder_path::<__NArgs>();
-                    let #bind_pat = #tmp_ident;
+                    #decode
                     #call_with_args;
                 }
             }
@@ -429,30 +426,169 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
         proc_macro2::Span::call_site(),
     );
 
-    let output = quote! {
-        #impl_block
+    let apply_body = quote! {
+        // set receiver for the entire call; cleared on all exits (incl. early returns)
+        let __guard = #ctx_mod_ident::ReceiverGuard::new(receiver);
+
+        #ctor_arm
+
+        // `pulse` reports failed deferred transactions to their sender with
+        // an `onerror` notification, which `#[on_notify("pulse::onerror")]`
+        // handles. Nobody else may send one.
+        if action == pulse_cdt::name_raw!("onerror") {
+            pulse_cdt::core::check(code == pulse_cdt::name_raw!("pulse"), "onerror action's are only valid from the \"pulse\" system account");
+        }
+
+        // The `code == receiver` invariant is tested once here rather than
+        // in every dispatch arm:
+        //   - code == receiver  => a self-received action
+        //   - code != receiver  => a notification from another contract
+        if code == receiver {
+            #action_dispatch
+        } else {
+            #notify_dispatch
+        }
 
-        #[cfg(all(target_arch = "wasm32"))]
-        #[global_allocator]
-        static ALLOCATOR: ::pulse_cdt::__reexports::lol_alloc::AssumeSingleThreaded<
-            ::pulse_cdt::__reexports::lol_alloc::LeakingAllocator
-        > = unsafe {
-            ::pulse_cdt::__reexports::lol_alloc::AssumeSingleThreaded::new(
-                ::pulse_cdt::__reexports::lol_alloc::LeakingAllocator::new()
-            )
+        #dtor_call
+
+        // guard drops here, clearing the receiver
+        core::mem::drop(__guard);
+    };
+
+    // ABI description: one struct per action, named after it, plus the
+    // listed tables.
+    let abi_actions = actions.iter().map(|a| {
+        let action_name_str = &a.name;
+        let params = a.method.sig.inputs.iter().filter_map(|arg| match arg {
+            FnArg::Typed(pt) => Some(erase_lifetimes(&pt.ty)),
+            FnArg::Receiver(_) => None,
+        });
+        let params = param_names(&a.method)
+            .into_iter()
+            .zip(params)
+            .map(|(param, ty)| {
+                quote! { (#param, <#ty as ::pulse_cdt::abi::AbiType>::abi_type(abi)) }
+            });
+        let result = action_output(&a.method).map(|ty| {
+            quote! {
+                let result = <#ty as ::pulse_cdt::abi::AbiType>::abi_type(abi);
+                abi.action_results.insert(::core::convert::Into::into(#action_name_str), result);
+            }
+        });
+        quote! {
+            let params = [#(#params),*];
+            abi.add_action(#action_name_str, params);
+            #result
+        }
+    });
+    let abi_tables = &args.tables;
+    let abi_impl = quote! {
+        impl ::pulse_cdt::abi::AbiContract for #self_ty {
+            fn describe(abi: &mut ::pulse_cdt::abi::Abi) {
+                #(#abi_actions)*
+                #( ::pulse_cdt::abi::AbiTableDefinition::add_to_abi(&#abi_tables, abi); )*
+            }
+        }
+    };
+
+    // Client side: `actions::<method>(args..)` packs the arguments into an
+    // `InlineAction`, so callers can only build data matching the signature.
+    let action_builders = actions.iter().map(|a| {
+        let method_ident = &a.method.sig.ident;
+        let action_name_str = &a.name;
+        let doc = format!("Builds the `{}` action, ready to be sent inline.", a.name);
+        let params: Vec<_> = a
+            .method
+            .sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                FnArg::Typed(pt) => Some(pt),
+                FnArg::Receiver(_) => None,
+            })
+            .enumerate()
+            .map(|(i, pt)| {
+                let ident = match &*pt.pat {
+                    Pat::Ident(p) => p.ident.clone(),
+                    _ => format_ident!("arg{}", i),
+                };
+                (ident, erase_lifetimes(&pt.ty))
+            })
+            .collect();
+        let idents: Vec<_> = params.iter().map(|(ident, _)| ident).collect();
+        let tys = params.iter().map(|(_, ty)| ty);
+        let name = quote! { ::pulse_cdt::name!(#action_name_str) };
+        let body = match idents.len() {
+            0 => quote! { ::pulse_cdt::contracts::InlineAction::without_data(#name) },
+            1 => {
+                let a0 = idents[0];
+                quote! { ::pulse_cdt::contracts::InlineAction::new(#name, (#a0,)) }
+            }
+            _ => quote! { ::pulse_cdt::contracts::InlineAction::new(#name, (#(#idents),*)) },
         };
+        quote! {
+            #[doc = #doc]
+            pub fn #method_ident(#(#idents: #tys),*) -> ::pulse_cdt::contracts::InlineAction {
+                #body
+            }
+        }
+    });
+    let actions_mod = quote! {
+        /// Typed builders for this contract's actions, for use by other
+        /// contracts sending them inline.
+        #[allow(dead_code)]
+        pub mod actions {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #(#action_builders)*
+        }
+    };
+
+    // The allocator, the panic handler and the exported `apply` may only be
+    // defined once per wasm. Crates that only want another contract's
+    // `actions` builders build its sources with `PULSE_CONTRACT_CLIENT` set
+    // by their build script, see `pulse_token_client`.
+    let wasm_entry = if is_client_build() {
+        quote! {}
+    } else {
+        quote! {
+            #[cfg(target_arch = "wasm32")]
+            #[global_allocator]
+            static ALLOCATOR: ::pulse_cdt::__reexports::lol_alloc::AssumeSingleThreaded<
+                ::pulse_cdt::__reexports::lol_alloc::LeakingAllocator
+            > = unsafe {
+                ::pulse_cdt::__reexports::lol_alloc::AssumeSingleThreaded::new(
+                    ::pulse_cdt::__reexports::lol_alloc::LeakingAllocator::new()
+                )
+            };
+
+            #[cfg(target_arch = "wasm32")]
+            #[panic_handler]
+            fn panic(panic_info: &core::panic::PanicInfo) -> ! {
+                let s = panic_info.message().as_str();
+                if let Some(s) = s {
+                    pulse_cdt::core::check(false, s);
+                } else {
+                    pulse_cdt::core::check(false, "panic without message");
+                }
+                ::core::arch::wasm32::unreachable()
+            }
 
-        #[cfg(all(target_arch = "wasm32"))]
-        #[panic_handler]
-        fn panic(panic_info: &core::panic::PanicInfo) -> ! {
-            let s = panic_info.message().as_str();
-            if let Some(s) = s {
-                pulse_cdt::core::check(false, s);
-            } else {
-                pulse_cdt::core::check(false, "panic without message");
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn apply(receiver: u64, code: u64, action: u64) {
+                #apply_body
             }
-            ::core::arch::wasm32::unreachable()
         }
+    };
+
+    let output = quote! {
+        #impl_block
+
+        #abi_impl
+
+        #actions_mod
 
         // ===== per-call context (receiver) =====
         #[doc(hidden)]
@@ -461,6 +597,7 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
 
             static RECEIVER: AtomicU64 = AtomicU64::new(0);
 
+            #[cfg(target_arch = "wasm32")]
             #[inline]
             pub fn get_self() -> u64 {
                 RECEIVER.load(Ordering::Relaxed)
@@ -494,36 +631,25 @@ fn expand_contract(impl_block: ItemImpl, args: ContractArgs) -> Result<TokenStre
             }
         }
 
+        #[cfg(target_arch = "wasm32")]
         #[inline]
         pub fn get_self() -> Name {
             pulse_cdt::core::Name::new(#ctx_mod_ident::get_self())
         }
 
-        #[no_mangle]
-        pub extern "C" fn apply(receiver: u64, code: u64, action: u64) {
-            // set receiver for the entire call; cleared on all exits (incl. early returns)
-            let __guard = #ctx_mod_ident::ReceiverGuard::new(receiver);
-
-            #ctor_arm
-
-            if action == pulse_cdt::name_raw!("onerror") {
-                pulse_cdt::core::check(false, "onerror action's are only valid from the \"pulse\" system account");
-            }
-
-            // The `code == receiver` invariant is tested once here rather than
-            // in every dispatch arm:
-            //   - code == receiver  => a self-received action
-            //   - code != receiver  => a notification from another contract
-            if code == receiver {
-                #action_dispatch
-            } else {
-                #notify_dispatch
-            }
+        // Native builds run under the test host, where several chains may be
+        // applying concurrently on different threads.
+        #[cfg(not(target_arch = "wasm32"))]
+        #[inline]
+        pub fn get_self() -> Name {
+            pulse_cdt::contracts::current_receiver()
+        }
 
-            #dtor_call
+        #wasm_entry
 
-            // guard drops here, clearing the receiver
-            core::mem::drop(__guard);
+        #[cfg(not(target_arch = "wasm32"))]
+        pub fn apply(receiver: u64, code: u64, action: u64) {
+            #apply_body
         }
     };
 
@@ -561,6 +687,99 @@ enum NotifyPat {
     Name(String),
 }
 
+/// The names of a method's arguments, as the ABI describes them: the
+/// binding's name, or `arg<i>` for other patterns.
+/// Whether the crate being compiled only wants the contract's `actions`
+/// builders, not its exported `apply`.
+fn is_client_build() -> bool {
+    extern crate std;
+    std::env::var_os("PULSE_CONTRACT_CLIENT").is_some()
+}
+
+fn param_names(method: &ImplItemMethod) -> Vec<String> {
+    method
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pt) => Some(pt),
+            FnArg::Receiver(_) => None,
+        })
+        .enumerate()
+        .map(|(i, pt)| match &*pt.pat {
+            Pat::Ident(p) => p.ident.to_string(),
+            _ => format!("arg{i}"),
+        })
+        .collect()
+}
+
+/// Statements binding `idents` to `method`'s arguments.
+///
+/// A custom decoder reads them all as a tuple. By default they're read one
+/// by one from the action's data, which stays alive for the rest of the
+/// dispatch arm, so arguments can borrow from it.
+fn decode_args(
+    decoder: Option<&Path>,
+    label: &str,
+    method: &ImplItemMethod,
+    idents: &[proc_macro2::Ident],
+) -> TokenStream2 {
+    match decoder {
+        Some(decoder) => {
+            let tys = method.sig.inputs.iter().filter_map(|arg| match arg {
+                FnArg::Typed(pt) => Some(erase_lifetimes(&pt.ty)),
+                FnArg::Receiver(_) => None,
+            });
+            quote! {
+                let (#(#idents,)*) = #decoder::<(#(#tys,)*)>();
+            }
+        }
+        None => {
+            let names = param_names(method);
+            quote! {
+                let __data = ::pulse_cdt::contracts::action_data();
+                let mut __pos = 0;
+                #(
+                    let #idents = ::pulse_cdt::contracts::read_action_arg(
+                        &__data,
+                        &mut __pos,
+                        #label,
+                        #names,
+                    );
+                )*
+            }
+        }
+    }
+}
+
+/// `ty` with the lifetimes of the method it appears in, e.g. `'a` in
+/// `&'a str`, replaced by `'_`, so the type can be named outside of it.
+fn erase_lifetimes(ty: &Type) -> TokenStream2 {
+    fn erase(tokens: TokenStream2) -> TokenStream2 {
+        let mut out = TokenStream2::new();
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Group(group) => {
+                    let mut erased = Group::new(group.delimiter(), erase(group.stream()));
+                    erased.set_span(group.span());
+                    out.extend([TokenTree::Group(erased)]);
+                }
+                TokenTree::Punct(p) if p.as_char() == '\'' => match tokens.next() {
+                    Some(TokenTree::Ident(ident)) if ident != "static" => {
+                        let lifetime = Lifetime::new("'_", ident.span());
+                        out.extend(quote!(#lifetime));
+                    }
+                    next => out.extend(core::iter::once(TokenTree::Punct(p)).chain(next)),
+                },
+                token => out.extend([token]),
+            }
+        }
+        out
+    }
+    erase(quote!(#ty))
+}
+
 fn parse_action_attr(attrs: &[Attribute]) -> Result<Option<ActionCfg>> {
     // Accept #[action], #[action(name = "...")], #[action(decoder = path)], or both
     let mut cfg: Option<ActionCfg> = None;
@@ -805,16 +1024,27 @@ fn ensure_arg_count(m: &ImplItemMethod, n: usize, kind: &str) -> Result<()> {
     Ok(())
 }
 
-fn tuple_type_tokens(tys: &[&Type]) -> TokenStream2 {
-    match tys.len() {
-        0 => quote! { () },
-        1 => {
-            let t0 = tys[0];
-            quote! { ( #t0 , ) }
-        }
-        _ => {
-            quote! { ( #(#tys),* ) }
-        }
+/// The type an action method returns, unless it is `()`.
+fn action_output(m: &ImplItemMethod) -> Option<&Type> {
+    match &m.sig.output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Tuple(t) if t.elems.is_empty() => None,
+            ty => Some(ty),
+        },
+        ReturnType::Default => None,
+    }
+}
+
+/// Wraps an action call so that whatever it returns is packed and handed to
+/// the host as the action's return value.
+fn with_return_value(m: &ImplItemMethod, call: TokenStream2) -> TokenStream2 {
+    match action_output(m) {
+        Some(ty) => quote! {
+            let __ret: #ty = #call;
+            let __ret = ::pulse_cdt::Write::pack(&__ret).expect("failed to pack action return value");
+            ::pulse_cdt::contracts::set_action_return_value(&__ret)
+        },
+        None => call,
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::internal::get_root_path;
+use crate::internal::{get_fields_options, get_root_path, variant_bindings, variant_pattern};
 use proc_macro2::TokenStream;
 use quote::{quote, quote_spanned, ToTokens};
 use syn::{
@@ -49,28 +49,41 @@ impl ToTokens for DeriveNumBytes {
         let add_to_count = match &self.data {
             Data::Struct(ref data) => match data.fields {
                 Fields::Named(ref fields) => {
-                    let recurse = fields.named.iter().map(|f| {
-                        let name = &f.ident;
-                        let access = quote_spanned!(call_site => #var.#name);
-                        quote_spanned! { f.span() =>
-                            count += #root::NumBytes::num_bytes(&#access);
-                        }
-                    });
+                    let options = get_fields_options(&data.fields);
+                    let recurse = fields
+                        .named
+                        .iter()
+                        .zip(&options)
+                        .filter(|(_, o)| !o.skip)
+                        .map(|(f, _)| {
+                            let name = &f.ident;
+                            let access = quote_spanned!(call_site => #var.#name);
+                            quote_spanned! { f.span() =>
+                                count += #root::NumBytes::num_bytes(&#access);
+                            }
+                        });
                     quote! {
                         #(#recurse)*
                     }
                 }
                 Fields::Unnamed(ref fields) => {
-                    let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                        let index = Index {
-                            index: i as u32,
-                            span: call_site,
-                        };
-                        let access = quote_spanned!(call_site => #var.#index);
-                        quote_spanned! { f.span() =>
-                            count += #root::NumBytes::num_bytes(&#access);
-                        }
-                    });
+                    let options = get_fields_options(&data.fields);
+                    let recurse = fields
+                        .unnamed
+                        .iter()
+                        .zip(&options)
+                        .enumerate()
+                        .filter(|(_, (_, o))| !o.skip)
+                        .map(|(i, (f, _))| {
+                            let index = Index {
+                                index: i as u32,
+                                span: call_site,
+                            };
+                            let access = quote_spanned!(call_site => #var.#index);
+                            quote_spanned! { f.span() =>
+                                count += #root::NumBytes::num_bytes(&#access);
+                            }
+                        });
                     quote! {
                         #(#recurse)*
                     }
@@ -79,7 +92,38 @@ impl ToTokens for DeriveNumBytes {
                     quote! {}
                 }
             },
-            Data::Enum(_) | Data::Union(_) => unimplemented!(),
+            Data::Enum(ref data) => {
+                let arms = data.variants.iter().enumerate().map(|(i, v)| {
+                    let ident = &v.ident;
+                    let tag = i as u32;
+                    let bindings = variant_bindings(&v.fields);
+                    let pattern = variant_pattern(&v.fields, &bindings);
+                    let options = get_fields_options(&v.fields);
+                    // Skipped fields are bound all the same.
+                    let allow = options
+                        .iter()
+                        .any(|o| o.skip)
+                        .then(|| quote!(#[allow(unused_variables)]));
+                    let bindings = bindings
+                        .iter()
+                        .zip(&options)
+                        .filter(|(_, o)| !o.skip)
+                        .map(|(b, _)| b);
+                    quote_spanned! { v.span() =>
+                        #allow
+                        #name::#ident #pattern => {
+                            count += #root::NumBytes::num_bytes(&#root::VarUint32(#tag));
+                            #( count += #root::NumBytes::num_bytes(#bindings); )*
+                        }
+                    }
+                });
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+            Data::Union(_) => unimplemented!(),
         };
 
         let expanded = quote! {
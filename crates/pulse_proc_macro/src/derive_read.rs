@@ -1,116 +1,286 @@
-use crate::internal::get_root_path;
+use crate::{
+    internal::{
+        get_abi_options, get_fields_options, get_root_path, has_serde_option, variant_bindings,
+        variant_pattern, FieldOptions,
+    },
+    serde_impl::{self, SerdeTrait},
+};
 use alloc::{borrow::ToOwned, string::ToString};
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
 use syn::{
     parse::{Parse, ParseStream, Result as ParseResult},
     parse_quote,
     spanned::Spanned,
-    Data, DeriveInput, Fields, GenericParam, Generics, Ident, Path,
+    Data, DeriveInput, Field, Fields, GenericParam, Generics, Ident, Lifetime, LitStr, Path,
 };
 
 pub struct DeriveRead {
     ident: Ident,
     generics: Generics,
+    /// `generics`, plus the data's lifetime if it had to be introduced.
+    impl_generics: Generics,
+    /// The lifetime of the data the value borrows from, when deriving
+    /// `ReadBorrowed`.
+    borrowed: Option<Lifetime>,
     data: Data,
     root_path: Path,
+    /// `#[pulse(abi = "...")]`: errors are reported as reading this builtin,
+    /// without the fields inside it.
+    builtin: Option<LitStr>,
+    /// The generics as declared, for the serde impl, if `#[pulse(serde)]`.
+    serde_generics: Option<Generics>,
 }
 
 impl Parse for DeriveRead {
     fn parse(input: ParseStream) -> ParseResult<Self> {
-        let DeriveInput {
-            attrs,
-            ident,
-            mut generics,
-            data,
-            ..
-        } = input.parse()?;
-        let root_path = get_root_path(&attrs);
-        for param in &mut generics.params {
-            if let GenericParam::Type(ref mut type_param) = *param {
-                type_param.bounds.push(parse_quote!(#root_path::Read));
-            }
-        }
-        Ok(Self {
-            ident,
-            generics,
-            data,
-            root_path,
-        })
+        Ok(Self::new(input.parse()?, false))
+    }
+}
+
+/// `ReadBorrowed`, for a type with fields borrowing from the data. Its first
+/// lifetime parameter, if any, is the data's.
+pub struct DeriveReadBorrowed(DeriveRead);
+
+impl Parse for DeriveReadBorrowed {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        Ok(Self(DeriveRead::new(input.parse()?, true)))
+    }
+}
+
+impl ToTokens for DeriveReadBorrowed {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.0.to_tokens(tokens);
     }
 }
 
 impl ToTokens for DeriveRead {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let name = &self.ident;
-        let (impl_generics, ty_generics, where_clause) = &self.generics.split_for_impl();
+        let (_, ty_generics, _) = self.generics.split_for_impl();
+        let (impl_generics, _, where_clause) = self.impl_generics.split_for_impl();
         let root = &self.root_path;
         let call_site = ::proc_macro2::Span::call_site();
-        let reads = match &self.data {
-            Data::Struct(ref data) => match data.fields {
-                Fields::Named(ref fields) => {
-                    let field_reads = fields.named.iter().map(|f| {
-                        let ident = &f.ident;
-                        let ty = &f.ty;
-                        quote_spanned! {f.span() =>
-                            let #ident = <#ty as #root::Read>::read(bytes, pos)?;
-                        }
-                    });
-                    let field_names = fields.named.iter().map(|f| {
-                        let ident = &f.ident;
+        let type_name = name.to_string();
+        let reads =
+            match &self.data {
+                Data::Struct(ref data) => match data.fields {
+                    Fields::Named(ref fields) => {
+                        let options = get_fields_options(&data.fields);
+                        let field_reads = fields.named.iter().zip(&options).enumerate().map(
+                            |(i, (f, options))| {
+                                let ident = f.ident.as_ref().expect("named field");
+                                self.read_field(f, options, i, ident)
+                            },
+                        );
+                        let field_names = fields.named.iter().map(|f| {
+                            let ident = &f.ident;
+                            quote! {
+                                #ident,
+                            }
+                        });
                         quote! {
-                            #ident,
+                            #(#field_reads)*
+                            let item = #name {
+                                #(#field_names)*
+                            };
+                            Ok(item)
                         }
-                    });
-                    quote! {
-                        #(#field_reads)*
-                        let item = #name {
-                            #(#field_names)*
-                        };
-                        Ok(item)
                     }
-                }
-                Fields::Unnamed(ref fields) => {
-                    let field_reads = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                        let ty = &f.ty;
-                        let ident_name = "field_".to_owned() + &i.to_string();
-                        let ident = Ident::new(&ident_name, call_site);
-                        quote_spanned! {f.span() =>
-                            let #ident = <#ty as #root::Read>::read(bytes, pos)?;
-                        }
-                    });
-                    let fields_list = fields.unnamed.iter().enumerate().map(|(i, _f)| {
-                        let ident_name = "field_".to_owned() + &i.to_string();
-                        let ident = Ident::new(&ident_name, call_site);
+                    Fields::Unnamed(ref fields) => {
+                        let options = get_fields_options(&data.fields);
+                        let field_reads = fields.unnamed.iter().zip(&options).enumerate().map(
+                            |(i, (f, options))| {
+                                let ident_name = "field_".to_owned() + &i.to_string();
+                                let ident = Ident::new(&ident_name, call_site);
+                                self.read_field(f, options, i, &ident)
+                            },
+                        );
+                        let fields_list = fields.unnamed.iter().enumerate().map(|(i, _f)| {
+                            let ident_name = "field_".to_owned() + &i.to_string();
+                            let ident = Ident::new(&ident_name, call_site);
+                            quote! {
+                                #ident,
+                            }
+                        });
                         quote! {
-                            #ident,
+                            #(#field_reads)*
+                            let item = #name(
+                                #(#fields_list)*
+                            );
+                            Ok(item)
+                        }
+                    }
+                    Fields::Unit => {
+                        unimplemented!();
+                    }
+                },
+                Data::Enum(ref data) => {
+                    let arms = data.variants.iter().enumerate().map(|(i, v)| {
+                        let ident = &v.ident;
+                        let tag = i as u32;
+                        let bindings = variant_bindings(&v.fields);
+                        let pattern = variant_pattern(&v.fields, &bindings);
+                        let options = get_fields_options(&v.fields);
+                        let field_reads = v
+                            .fields
+                            .iter()
+                            .zip(&options)
+                            .zip(&bindings)
+                            .enumerate()
+                            .map(|(i, ((f, options), binding))| {
+                                self.read_field(f, options, i, binding)
+                            });
+                        quote_spanned! { v.span() =>
+                            #tag => {
+                                #(#field_reads)*
+                                Ok(#name::#ident #pattern)
+                            }
                         }
                     });
                     quote! {
-                        #(#field_reads)*
-                        let item = #name(
-                            #(#fields_list)*
-                        );
-                        Ok(item)
+                        let tag = <#root::VarUint32 as #root::Read>::read(bytes, pos)?;
+                        match tag.0 {
+                            #(#arms)*
+                            _ => Err(#root::ReadError::new(
+                                #root::ReadErrorKind::ParseError,
+                                #type_name,
+                                __start,
+                            )),
+                        }
                     }
                 }
-                Fields::Unit => {
-                    unimplemented!();
-                }
-            },
-            Data::Enum(_) | Data::Union(_) => unimplemented!(),
-        };
+                Data::Union(_) => unimplemented!(),
+            };
 
+        // Where the value starts, for errors that point at it as a whole.
+        let start = (self.builtin.is_some() || matches!(self.data, Data::Enum(_)))
+            .then(|| quote!(let __start = *pos;));
+        let deserialize = self.serde_generics.as_ref().map(|generics| {
+            serde_impl::expand(root, name, generics, &self.data, SerdeTrait::Deserialize)
+        });
+        let (read_trait, read) = self.read_trait();
+        let lifetime = &self.borrowed;
         let expanded = quote! {
             #[automatically_derived]
             #[allow(unused_qualifications)]
-            impl #impl_generics #root::Read for #name #ty_generics #where_clause {
+            impl #impl_generics #read_trait for #name #ty_generics #where_clause {
                 #[inline]
-                fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, #root::ReadError> {
+                fn #read(bytes: &#lifetime [u8], pos: &mut usize) -> Result<Self, #root::ReadError> {
+                    #start
                     #reads
                 }
             }
+
+            #deserialize
         };
         expanded.to_tokens(tokens);
     }
 }
+
+impl DeriveRead {
+    fn new(input: DeriveInput, borrowed: bool) -> Self {
+        let DeriveInput {
+            attrs,
+            ident,
+            mut generics,
+            data,
+            ..
+        } = input;
+        let root_path = get_root_path(&attrs);
+        let builtin = get_abi_options(&attrs).builtin;
+        // The serde impl goes along `Read` only.
+        let serde_generics = (has_serde_option(&attrs) && !borrowed).then(|| generics.clone());
+        let borrowed = borrowed.then(|| {
+            generics
+                .lifetimes()
+                .next()
+                .map(|param| param.lifetime.clone())
+                .unwrap_or_else(|| Lifetime::new("'__a", Span::call_site()))
+        });
+        let bound: Path = match &borrowed {
+            Some(lifetime) => parse_quote!(#root_path::ReadBorrowed<#lifetime>),
+            None => parse_quote!(#root_path::Read),
+        };
+        for param in &mut generics.params {
+            if let GenericParam::Type(ref mut type_param) = *param {
+                type_param.bounds.push(parse_quote!(#bound));
+            }
+        }
+        let mut impl_generics = generics.clone();
+        if let Some(lifetime) = &borrowed {
+            if impl_generics.lifetimes().next().is_none() {
+                impl_generics.params.insert(0, parse_quote!(#lifetime));
+            }
+        }
+        Self {
+            ident,
+            generics,
+            impl_generics,
+            borrowed,
+            data,
+            root_path,
+            builtin,
+            serde_generics,
+        }
+    }
+
+    /// The trait being derived and its method.
+    fn read_trait(&self) -> (TokenStream, TokenStream) {
+        let root = &self.root_path;
+        match &self.borrowed {
+            Some(lifetime) => (
+                quote!(#root::ReadBorrowed<#lifetime>),
+                quote!(read_borrowed),
+            ),
+            None => (quote!(#root::Read), quote!(read)),
+        }
+    }
+
+    /// Reads `field`, the `index`th of its struct or variant, into `binding`.
+    fn read_field(
+        &self,
+        field: &Field,
+        options: &FieldOptions,
+        index: usize,
+        binding: &Ident,
+    ) -> TokenStream {
+        let (read_trait, read) = self.read_trait();
+        let ty = &field.ty;
+        if options.skip {
+            return quote_spanned! {field.span() =>
+                let #binding: #ty = ::core::default::Default::default();
+            };
+        }
+        let context = match options.name(field) {
+            Some(name) => self.error_context(quote!(in_field), quote!(#name)),
+            None => self.error_context(quote!(in_index), quote!(#index)),
+        };
+        let read = quote_spanned! {field.span() =>
+            <#ty as #read_trait>::#read(bytes, pos)#context?
+        };
+        if options.default {
+            quote_spanned! {field.span() =>
+                let #binding: #ty = if *pos < bytes.len() {
+                    #read
+                } else {
+                    ::core::default::Default::default()
+                };
+            }
+        } else {
+            quote_spanned! {field.span() =>
+                let #binding = #read;
+            }
+        }
+    }
+
+    /// What follows a field's `read(bytes, pos)` to record where an error
+    /// happened: `method` (`in_field` or `in_index`) with `label`, or for a
+    /// builtin, the builtin as a whole.
+    fn error_context(&self, method: TokenStream, label: TokenStream) -> TokenStream {
+        match &self.builtin {
+            Some(builtin) => quote! { .map_err(|e| e.reading(#builtin, __start)) },
+            None => quote! { .map_err(|e| e.#method(#label)) },
+        }
+    }
+}
@@ -7,13 +7,22 @@ use syn::{
     Data, DeriveInput, Fields, GenericParam, Generics, Ident, Index, Path,
 };
 
-use crate::internal::get_root_path;
+use crate::{
+    internal::{
+        get_abi_options, get_fields_options, get_root_path, has_serde_option, variant_bindings,
+        variant_pattern, AbiOptions,
+    },
+    serde_impl::{self, SerdeTrait},
+};
 
 pub struct DeriveWrite {
     ident: Ident,
     generics: Generics,
     data: Data,
     root_path: Path,
+    abi_generics: Generics,
+    abi_options: AbiOptions,
+    serde: bool,
 }
 
 impl Parse for DeriveWrite {
@@ -26,6 +35,9 @@ impl Parse for DeriveWrite {
             ..
         } = input.parse()?;
         let root_path = get_root_path(&attrs);
+        let abi_generics = generics.clone();
+        let abi_options = get_abi_options(&attrs);
+        let serde = has_serde_option(&attrs);
         for param in &mut generics.params {
             if let GenericParam::Type(ref mut type_param) = *param {
                 type_param.bounds.push(parse_quote!(#root_path::Write));
@@ -36,6 +48,9 @@ impl Parse for DeriveWrite {
             generics,
             data,
             root_path,
+            abi_generics,
+            abi_options,
+            serde,
         })
     }
 }
@@ -50,29 +65,42 @@ impl ToTokens for DeriveWrite {
         let writes = match &self.data {
             Data::Struct(ref data) => match data.fields {
                 Fields::Named(ref fields) => {
-                    let recurse = fields.named.iter().map(|f| {
-                        let name = &f.ident;
-                        let access = quote_spanned!(call_site => #var.#name);
-                        quote_spanned! { f.span() =>
-                            #root::Write::write(&#access, bytes, pos)?;
-                        }
-                    });
+                    let options = get_fields_options(&data.fields);
+                    let recurse = fields
+                        .named
+                        .iter()
+                        .zip(&options)
+                        .filter(|(_, o)| !o.skip)
+                        .map(|(f, _)| {
+                            let name = &f.ident;
+                            let access = quote_spanned!(call_site => #var.#name);
+                            quote_spanned! { f.span() =>
+                                #root::Write::write(&#access, bytes, pos)?;
+                            }
+                        });
                     quote! {
                         #(#recurse)*
                         Ok(())
                     }
                 }
                 Fields::Unnamed(ref fields) => {
-                    let recurse = fields.unnamed.iter().enumerate().map(|(i, f)| {
-                        let index = Index {
-                            index: i as u32,
-                            span: call_site,
-                        };
-                        let access = quote_spanned!(call_site => #var.#index);
-                        quote_spanned! { f.span() =>
-                            #root::Write::write(&#access, bytes, pos)?;
-                        }
-                    });
+                    let options = get_fields_options(&data.fields);
+                    let recurse = fields
+                        .unnamed
+                        .iter()
+                        .zip(&options)
+                        .enumerate()
+                        .filter(|(_, (_, o))| !o.skip)
+                        .map(|(i, (f, _))| {
+                            let index = Index {
+                                index: i as u32,
+                                span: call_site,
+                            };
+                            let access = quote_spanned!(call_site => #var.#index);
+                            quote_spanned! { f.span() =>
+                                #root::Write::write(&#access, bytes, pos)?;
+                            }
+                        });
                     quote! {
                         #(#recurse)*
                         Ok(())
@@ -84,9 +112,57 @@ impl ToTokens for DeriveWrite {
                     }
                 }
             },
-            Data::Enum(_) | Data::Union(_) => unimplemented!(),
+            Data::Enum(ref data) => {
+                let arms = data.variants.iter().enumerate().map(|(i, v)| {
+                    let ident = &v.ident;
+                    let tag = i as u32;
+                    let bindings = variant_bindings(&v.fields);
+                    let pattern = variant_pattern(&v.fields, &bindings);
+                    let options = get_fields_options(&v.fields);
+                    // Skipped fields are bound all the same.
+                    let allow = options
+                        .iter()
+                        .any(|o| o.skip)
+                        .then(|| quote!(#[allow(unused_variables)]));
+                    let bindings = bindings
+                        .iter()
+                        .zip(&options)
+                        .filter(|(_, o)| !o.skip)
+                        .map(|(b, _)| b);
+                    quote_spanned! { v.span() =>
+                        #allow
+                        #name::#ident #pattern => {
+                            #root::Write::write(&#root::VarUint32(#tag), bytes, pos)?;
+                            #( #root::Write::write(#bindings, bytes, pos)?; )*
+                        }
+                    }
+                });
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                    Ok(())
+                }
+            }
+            Data::Union(_) => unimplemented!(),
         };
 
+        let abi_type = crate::abi_type::expand(
+            root,
+            name,
+            &self.abi_generics,
+            &self.data,
+            &self.abi_options,
+        );
+        let serialize = self.serde.then(|| {
+            serde_impl::expand(
+                root,
+                name,
+                &self.abi_generics,
+                &self.data,
+                SerdeTrait::Serialize,
+            )
+        });
         let expanded = quote! {
             #[automatically_derived]
             #[allow(unused_qualifications)]
@@ -96,6 +172,10 @@ impl ToTokens for DeriveWrite {
                     #writes
                 }
             }
+
+            #abi_type
+
+            #serialize
         };
 
         expanded.to_tokens(tokens);
@@ -49,7 +49,7 @@ pub fn expand(input: TokenStream) -> TokenStream {
         let action = pair.action;
         quote! {
             else if code == #code && action == <#action as pulse_cdt::contracts::ActionFn>::NAME.raw() {
-                let data = pulse_cdt::contracts::read_action_data::<#action>().expect("failed to read action data");
+                let data = pulse_cdt::contracts::read_action_data::<#action>();
                 <#action as pulse_cdt::contracts::ActionFn>::call(data)
             }
         }
@@ -86,7 +86,7 @@ pub fn expand(input: TokenStream) -> TokenStream {
         #[no_mangle]
         pub extern "C" fn apply(receiver: u64, code: u64, action: u64) {
             if action == pulse_cdt::name_raw!("onerror") {
-                pulse_cdt::core::check(false, "onerror action's are only valid from the \"pulse\" system account");
+                pulse_cdt::core::check(code == pulse_cdt::name_raw!("pulse"), "onerror action's are only valid from the \"pulse\" system account");
             }
             #(#actions)*
             else if code == receiver {
@@ -1,9 +1,14 @@
-use alloc::vec::Vec;
-use proc_macro2::Span;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
 use syn::{
-    Attribute, Ident, Lit, LitStr,
+    Attribute, Field, Fields, GenericArgument, Ident, Lit, LitStr,
     Meta::{self, List},
-    NestedMeta, Path,
+    NestedMeta, Path, PathArguments, Type,
 };
 
 #[derive(Copy, Clone)]
@@ -72,3 +77,153 @@ pub fn get_root_path(attrs: &[Attribute]) -> Path {
         .parse_with(Path::parse_mod_style)
         .unwrap()
 }
+
+/// Local names an enum variant's fields are bound to when matching on it or
+/// constructing it, `field_<i>` in declaration order. Positional names keep
+/// the bindings from shadowing the `bytes`/`pos` arguments of the generated
+/// methods.
+pub fn variant_bindings(fields: &Fields) -> Vec<Ident> {
+    (0..fields.len())
+        .map(|i| Ident::new(&format!("field_{i}"), Span::call_site()))
+        .collect()
+}
+
+/// The part of a variant pattern (or constructor) that follows its path,
+/// binding every field to the matching name from [`variant_bindings`].
+pub fn variant_pattern(fields: &Fields, bindings: &[Ident]) -> TokenStream {
+    match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| &f.ident);
+            quote! { { #(#names: #bindings),* } }
+        }
+        Fields::Unnamed(_) => quote! { ( #(#bindings),* ) },
+        Fields::Unit => quote! {},
+    }
+}
+
+pub const SERDE: Symbol = Symbol("serde");
+
+/// `#[pulse(serde)]`: the derived `Write` and `Read` also implement serde's
+/// `Serialize` and `Deserialize`.
+pub fn has_serde_option(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .flat_map(get_pulse_meta_items)
+        .flatten()
+        .any(|meta_item| matches!(meta_item, NestedMeta::Meta(Meta::Path(p)) if p == SERDE))
+}
+
+pub const ABI: Symbol = Symbol("abi");
+pub const ABI_NAME: Symbol = Symbol("abi_name");
+
+/// Container options for the derived `AbiType`.
+#[derive(Default)]
+pub struct AbiOptions {
+    /// `#[pulse(abi = "asset")]`: the type is this ABI builtin.
+    pub builtin: Option<LitStr>,
+    /// `#[pulse(abi_name = "permission_level")]`: the name of the struct or
+    /// variant, instead of the Rust one.
+    pub name: Option<LitStr>,
+}
+
+pub fn get_abi_options(attrs: &[Attribute]) -> AbiOptions {
+    let mut options = AbiOptions::default();
+    for meta_item in attrs.iter().flat_map(get_pulse_meta_items).flatten() {
+        if let NestedMeta::Meta(Meta::NameValue(m)) = meta_item {
+            let slot = if m.path == ABI {
+                &mut options.builtin
+            } else if m.path == ABI_NAME {
+                &mut options.name
+            } else {
+                continue;
+            };
+            match m.lit {
+                Lit::Str(string) => *slot = Some(string),
+                _ => panic!("`#[pulse(abi = \"...\")]` and `#[pulse(abi_name = \"...\")]` take a string"),
+            }
+        }
+    }
+    options
+}
+
+pub const SKIP: Symbol = Symbol("skip");
+pub const DEFAULT: Symbol = Symbol("default");
+pub const RENAME: Symbol = Symbol("rename");
+
+/// Field options, honored by every derive.
+#[derive(Default)]
+pub struct FieldOptions {
+    /// `#[pulse(skip)]`: the field isn't serialized, and reads as
+    /// `Default::default()`.
+    pub skip: bool,
+    /// `#[pulse(default)]`: the field is a binary extension, reading as
+    /// `Default::default()` when the data ends before it. Its ABI type gets a
+    /// `$` suffix.
+    pub default: bool,
+    /// `#[pulse(rename = "...")]`: the field's name in the ABI, in serde and
+    /// in read errors.
+    pub rename: Option<LitStr>,
+}
+
+impl FieldOptions {
+    /// The name the field goes by outside Rust, `None` for an unnamed field
+    /// that isn't renamed.
+    pub fn name(&self, field: &Field) -> Option<String> {
+        self.rename
+            .as_ref()
+            .map(LitStr::value)
+            .or_else(|| field.ident.as_ref().map(ToString::to_string))
+    }
+}
+
+pub fn get_field_options(field: &Field) -> FieldOptions {
+    let mut options = FieldOptions::default();
+    for meta_item in field.attrs.iter().flat_map(get_pulse_meta_items).flatten() {
+        match meta_item {
+            NestedMeta::Meta(Meta::Path(p)) if p == SKIP => options.skip = true,
+            NestedMeta::Meta(Meta::Path(p)) if p == DEFAULT => options.default = true,
+            NestedMeta::Meta(Meta::NameValue(m)) if m.path == RENAME => match m.lit {
+                Lit::Str(string) => options.rename = Some(string),
+                _ => panic!("`#[pulse(rename = \"...\")]` takes a string"),
+            },
+            _ => panic!("unknown field option, expected `skip`, `default` or `rename = \"...\"`"),
+        }
+    }
+    options
+}
+
+/// The options of each of `fields`, in order.
+///
+/// Only other binary extensions (`#[pulse(default)]` or `BinaryExtension`
+/// fields) may follow a binary extension.
+pub fn get_fields_options(fields: &Fields) -> Vec<FieldOptions> {
+    let options: Vec<_> = fields.iter().map(get_field_options).collect();
+    let is_extension =
+        |(f, o): (&Field, &FieldOptions)| o.default || is_generic(&f.ty, "BinaryExtension");
+    // `any` stops at the first extension, leaving the fields after it.
+    let mut fields = fields.iter().zip(&options).filter(|(_, o)| !o.skip);
+    if fields.any(is_extension) && !fields.all(is_extension) {
+        panic!("fields following a binary extension must be binary extensions too");
+    }
+    options
+}
+
+/// Whether `ty` is (a path to) `name<...>`, e.g. `Vec<u8>` for `"Vec"`.
+pub fn is_generic(ty: &Type, name: &str) -> bool {
+    generic_arg(ty, name).is_some()
+}
+
+/// The first generic argument of `ty` if it's (a path to) `name<...>`.
+pub fn generic_arg<'a>(ty: &'a Type, name: &str) -> Option<&'a GenericArgument> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    if last.ident != name {
+        return None;
+    }
+    args.args.first()
+}
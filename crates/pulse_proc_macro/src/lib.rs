@@ -7,6 +7,7 @@ use syn::parse_macro_input;
 
 use crate::{contract::contract_macro, table::table_macro};
 
+mod abi_type;
 mod contract;
 mod derive_numbytes;
 mod derive_read;
@@ -15,6 +16,8 @@ mod dispatch;
 mod internal;
 mod name;
 mod name_raw;
+mod print;
+mod serde_impl;
 mod symbol_with_code;
 mod table;
 
@@ -69,7 +72,8 @@ pub fn dispatch(input: TokenStream) -> TokenStream {
 }
 
 #[inline]
-#[proc_macro_derive(Read)]
+/// With `#[pulse(serde)]`, also derives serde's `Deserialize`.
+#[proc_macro_derive(Read, attributes(pulse))]
 pub fn derive_read(input: TokenStream) -> TokenStream {
     use crate::derive_read::DeriveRead;
     let item = parse_macro_input!(input as DeriveRead);
@@ -77,7 +81,18 @@ pub fn derive_read(input: TokenStream) -> TokenStream {
 }
 
 #[inline]
-#[proc_macro_derive(NumBytes)]
+/// `ReadBorrowed` for a type borrowing from the data, e.g. with `&'a str`
+/// fields; the data's lifetime is the type's first lifetime parameter. Types
+/// deriving `Read` are `ReadBorrowed` already.
+#[proc_macro_derive(ReadBorrowed, attributes(pulse))]
+pub fn derive_read_borrowed(input: TokenStream) -> TokenStream {
+    use crate::derive_read::DeriveReadBorrowed;
+    let item = parse_macro_input!(input as DeriveReadBorrowed);
+    quote!(#item).into()
+}
+
+#[inline]
+#[proc_macro_derive(NumBytes, attributes(pulse))]
 pub fn derive_numbytes(input: TokenStream) -> TokenStream {
     use crate::derive_numbytes::DeriveNumBytes;
     let item = parse_macro_input!(input as DeriveNumBytes);
@@ -85,6 +100,11 @@ pub fn derive_numbytes(input: TokenStream) -> TokenStream {
 }
 
 #[inline]
+/// Also derives `abi::AbiType`, so the type can be described in an ABI, and
+/// with `#[pulse(serde)]` serde's `Serialize`.
+///
+/// Like `Read` and `NumBytes`, honors the field options `#[pulse(skip)]`,
+/// `#[pulse(default)]` and `#[pulse(rename = "...")]`.
 #[proc_macro_derive(Write, attributes(pulse))]
 pub fn derive_write(input: TokenStream) -> TokenStream {
     use crate::derive_write::DeriveWrite;
@@ -92,6 +112,22 @@ pub fn derive_write(input: TokenStream) -> TokenStream {
     quote!(#item).into()
 }
 
+#[inline]
+#[proc_macro]
+pub fn print(input: TokenStream) -> TokenStream {
+    use crate::print::PulsePrint;
+    let item = parse_macro_input!(input as PulsePrint);
+    quote!(#item).into()
+}
+
+#[inline]
+#[proc_macro]
+pub fn println(input: TokenStream) -> TokenStream {
+    use crate::print::PulsePrint;
+    let item = parse_macro_input!(input with PulsePrint::parse_println);
+    quote!(#item).into()
+}
+
 #[inline]
 #[proc_macro]
 pub fn symbol_with_code(input: TokenStream) -> TokenStream {
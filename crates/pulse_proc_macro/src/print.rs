@@ -0,0 +1,129 @@
+use alloc::{string::String, vec::Vec};
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream, Result as ParseResult},
+    punctuated::Punctuated,
+    Expr, LitStr, Token,
+};
+
+/// print!("{} has {}", owner, balance)
+///
+/// Literal pieces of the format string go to `prints_l`, every `{}` to the
+/// matching argument's `Printable` impl. `{{` and `}}` escape braces; no
+/// other format specs are supported.
+pub struct PulsePrint {
+    format: LitStr,
+    args: Vec<Expr>,
+    newline: bool,
+}
+
+enum Piece {
+    Literal(String),
+    Arg,
+}
+
+impl PulsePrint {
+    /// Parses the arguments of `println!`.
+    pub fn parse_println(input: ParseStream) -> ParseResult<Self> {
+        Self::parse_with_newline(input, true)
+    }
+
+    fn parse_with_newline(input: ParseStream, newline: bool) -> ParseResult<Self> {
+        let format: LitStr = input.parse()?;
+        let args = if input.is_empty() {
+            Vec::new()
+        } else {
+            input.parse::<Token![,]>()?;
+            Punctuated::<Expr, Token![,]>::parse_terminated(input)?
+                .into_iter()
+                .collect()
+        };
+        Ok(Self {
+            format,
+            args,
+            newline,
+        })
+    }
+
+    fn pieces(&self) -> ParseResult<Vec<Piece>> {
+        let format = self.format.value();
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut chars = format.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, chars.peek()) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
+                    literal.push(c);
+                }
+                ('{', Some('}')) => {
+                    chars.next();
+                    if !literal.is_empty() {
+                        pieces.push(Piece::Literal(core::mem::take(&mut literal)));
+                    }
+                    pieces.push(Piece::Arg);
+                }
+                ('{', _) | ('}', _) => {
+                    return Err(syn::Error::new(
+                        self.format.span(),
+                        "only `{}` placeholders are supported; use `{{` and `}}` for literal braces",
+                    ));
+                }
+                _ => literal.push(c),
+            }
+        }
+        if self.newline {
+            literal.push('\n');
+        }
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(literal));
+        }
+        Ok(pieces)
+    }
+}
+
+impl Parse for PulsePrint {
+    fn parse(input: ParseStream) -> ParseResult<Self> {
+        Self::parse_with_newline(input, false)
+    }
+}
+
+impl ToTokens for PulsePrint {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let pieces = match self.pieces() {
+            Ok(pieces) => pieces,
+            Err(e) => return e.to_compile_error().to_tokens(tokens),
+        };
+        let placeholders = pieces.iter().filter(|p| matches!(p, Piece::Arg)).count();
+        if placeholders != self.args.len() {
+            let msg = alloc::format!(
+                "{} placeholder(s) in the format string but {} argument(s) given",
+                placeholders,
+                self.args.len()
+            );
+            return syn::Error::new(self.format.span(), msg)
+                .to_compile_error()
+                .to_tokens(tokens);
+        }
+
+        let mut args = self.args.iter();
+        let calls = pieces.iter().map(|piece| match piece {
+            Piece::Literal(s) => quote! {
+                ::pulse_cdt::contracts::prints_l(#s);
+            },
+            Piece::Arg => {
+                let arg = args.next();
+                quote! {
+                    ::pulse_cdt::contracts::Printable::print(&(#arg));
+                }
+            }
+        });
+        let expanded = quote! {
+            {
+                #(#calls)*
+            }
+        };
+        expanded.to_tokens(tokens);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::internal::{generic_arg, get_fields_options, is_generic, FieldOptions};
+use alloc::{format, string::ToString};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{
+    parse_quote, spanned::Spanned, Data, Field, Fields, GenericArgument, GenericParam, Generics,
+    Ident, Path, Type,
+};
+
+/// Which of the serde traits to implement.
+#[derive(Copy, Clone)]
+pub enum SerdeTrait {
+    Serialize,
+    Deserialize,
+}
+
+/// The serde impl that goes along a derived `Write` (`Serialize`) or `Read`
+/// (`Deserialize`) of a `#[pulse(serde)]` struct.
+///
+/// serde derives it for a copy of the struct marked `#[serde(remote)]`, so
+/// fields keep their names and `Vec<u8>` fields are hex strings, as in the
+/// node's JSON. The serde crate is the one `#root::serde_support` re-exports.
+pub fn expand(
+    root: &Path,
+    name: &Ident,
+    generics: &Generics,
+    data: &Data,
+    which: SerdeTrait,
+) -> TokenStream {
+    let fields = match data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return quote_spanned! { name.span() =>
+                compile_error!("`#[pulse(serde)]` is only supported on structs");
+            }
+        }
+    };
+    let support = quote!(#root::serde_support);
+    let serde = quote!(#support::serde);
+    let serde_crate = serde.to_string();
+    let remote = name.to_string();
+
+    let options = get_fields_options(fields);
+    let mirror_fields = fields
+        .iter()
+        .zip(&options)
+        .map(|(f, options)| mirror_field(root, &support, f, options));
+    let (_, mirror_generics, where_clause) = generics.split_for_impl();
+    let mirror_body = match fields {
+        Fields::Named(_) => quote! { #where_clause { #(#mirror_fields,)* } },
+        Fields::Unnamed(_) => quote! { ( #(#mirror_fields,)* ) #where_clause; },
+        Fields::Unit => quote! { #where_clause; },
+    };
+    let mirror = |derive: TokenStream| {
+        quote! {
+            #[derive(#serde::#derive)]
+            #[serde(crate = #serde_crate, remote = #remote)]
+            #[allow(dead_code)]
+            struct __Remote #generics #mirror_body
+        }
+    };
+    let mirror_path = mirror_generics.as_turbofish();
+
+    let mut generics = generics.clone();
+    let body = match which {
+        SerdeTrait::Serialize => {
+            for param in &mut generics.params {
+                if let GenericParam::Type(ref mut type_param) = *param {
+                    type_param.bounds.push(parse_quote!(#serde::Serialize));
+                }
+            }
+            let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+            let mirror = mirror(quote!(Serialize));
+            quote! {
+                #mirror
+
+                #[automatically_derived]
+                impl #impl_generics #serde::Serialize for #name #ty_generics #where_clause {
+                    fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+                    where
+                        __S: #serde::Serializer,
+                    {
+                        __Remote #mirror_path::serialize(self, serializer)
+                    }
+                }
+            }
+        }
+        SerdeTrait::Deserialize => {
+            let (_, ty_generics, _) = generics.split_for_impl();
+            let ty_generics = quote!(#ty_generics);
+            for param in &mut generics.params {
+                if let GenericParam::Type(ref mut type_param) = *param {
+                    type_param
+                        .bounds
+                        .push(parse_quote!(#serde::Deserialize<'de>));
+                }
+            }
+            generics.params.insert(0, parse_quote!('de));
+            let (impl_generics, _, where_clause) = generics.split_for_impl();
+            let mirror = mirror(quote!(Deserialize));
+            quote! {
+                #mirror
+
+                #[automatically_derived]
+                impl #impl_generics #serde::Deserialize<'de> for #name #ty_generics #where_clause {
+                    fn deserialize<__D>(deserializer: __D) -> ::core::result::Result<Self, __D::Error>
+                    where
+                        __D: #serde::Deserializer<'de>,
+                    {
+                        __Remote #mirror_path::deserialize(deserializer)
+                    }
+                }
+            }
+        }
+    };
+
+    quote! {
+        #[allow(non_local_definitions, unused_qualifications)]
+        const _: () = {
+            #body
+        };
+    }
+}
+
+/// The mirror's copy of a field, going through `hex_bytes` if it's a
+/// `Vec<u8>`, and following the field options. Binary extensions may be
+/// missing from the JSON, and absent ones are left out of it.
+fn mirror_field(
+    root: &Path,
+    support: &TokenStream,
+    field: &Field,
+    options: &FieldOptions,
+) -> TokenStream {
+    let ident = field.ident.as_ref().map(|ident| quote!(#ident:));
+    let ty = &field.ty;
+    let with = is_byte_vec(ty).then(|| {
+        let with = format!("{support}::hex_bytes");
+        quote!(#[serde(with = #with)])
+    });
+    let rename = options
+        .rename
+        .as_ref()
+        .map(|name| quote!(#[serde(rename = #name)]));
+    let skip = options.skip.then(|| quote!(#[serde(skip)]));
+    let default = options.default.then(|| quote!(#[serde(default)]));
+    let extension = is_generic(ty, "BinaryExtension").then(|| {
+        let is_none = format!("{}::BinaryExtension::is_none", quote!(#root));
+        quote!(#[serde(default, skip_serializing_if = #is_none)])
+    });
+    quote_spanned! { field.span() => #with #rename #skip #default #extension #ident #ty }
+}
+
+fn is_byte_vec(ty: &Type) -> bool {
+    generic_arg(ty, "Vec").is_some_and(
+        |arg| matches!(arg, GenericArgument::Type(Type::Path(inner)) if inner.path.is_ident("u8")),
+    )
+}
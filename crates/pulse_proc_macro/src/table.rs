@@ -1,15 +1,25 @@
+use alloc::vec::Vec;
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use syn::{
+    parenthesized,
     parse::{Parse, ParseStream},
-    parse_macro_input, Expr, Ident, ItemStruct, Result, Token,
+    parse_macro_input, Expr, Ident, ItemStruct, Result, Token, Type,
 };
 
+/// The chain addresses at most 16 secondary indexes per table.
+const MAX_SECONDARY_INDEXES: usize = 16;
+
 /// #[table(primary_key = row.balance.symbol.code().raw())]
 /// or
 /// #[table(primary_key = |row| row.balance.symbol.code().raw())]
+///
+/// Secondary indexes follow the primary key, numbered in order:
+/// #[table(primary_key = row.id, secondary_key(u64) = row.owner.raw())]
 pub struct TableArgs {
     primary_key: Expr,
+    secondary_keys: Vec<(Type, Expr)>,
 }
 
 impl Parse for TableArgs {
@@ -23,9 +33,46 @@ impl Parse for TableArgs {
         }
         input.parse::<Token![=]>()?;
         let primary_key: Expr = input.parse()?;
-        // optional trailing comma
-        let _ = input.parse::<Token![,]>();
-        Ok(Self { primary_key })
+
+        let mut secondary_keys = Vec::new();
+        while input.parse::<Token![,]>().is_ok() {
+            if input.is_empty() {
+                break;
+            }
+            let key_ident: Ident = input.parse()?;
+            if key_ident != "secondary_key" {
+                return Err(syn::Error::new(
+                    key_ident.span(),
+                    "expected `secondary_key(<type>) = <expr>`",
+                ));
+            }
+            let content;
+            parenthesized!(content in input);
+            let ty: Type = content.parse()?;
+            input.parse::<Token![=]>()?;
+            let expr: Expr = input.parse()?;
+            secondary_keys.push((ty, expr));
+        }
+
+        if secondary_keys.len() > MAX_SECONDARY_INDEXES {
+            return Err(syn::Error::new(
+                input.span(),
+                "a table supports at most 16 secondary indexes",
+            ));
+        }
+
+        Ok(Self {
+            primary_key,
+            secondary_keys,
+        })
+    }
+}
+
+// Allow either a plain expression that references `row`, or a closure `|row| ...`
+fn key_body(expr: &Expr) -> TokenStream2 {
+    match expr {
+        Expr::Closure(_) => quote! { (#expr)(row) },
+        _ => quote! { (#expr) },
     }
 }
 
@@ -42,12 +89,59 @@ pub fn table_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
     let generics = input.generics.clone();
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let pk_expr = args.primary_key;
+    let body = key_body(&args.primary_key);
+
+    let index_impls = args.secondary_keys.iter().enumerate().map(|(i, (ty, expr))| {
+        let body = key_body(expr);
+        quote! {
+            impl #impl_generics ::pulse_cdt::core::TableIndex<#i> for #ident #ty_generics #where_clause {
+                type Secondary = #ty;
 
-    // Allow either a plain expression that references `row`, or a closure `|row| ...`
-    let body = match pk_expr {
-        Expr::Closure(_) => quote! { (#pk_expr)(row) },
-        _ => quote! { (#pk_expr) },
+                #[inline]
+                fn secondary_key(row: &Self::Row) -> #ty {
+                    #body
+                }
+            }
+        }
+    });
+
+    // Only override the `Table` hooks when there is something to maintain.
+    let hooks = if args.secondary_keys.is_empty() {
+        quote! {}
+    } else {
+        let indexes: Vec<usize> = (0..args.secondary_keys.len()).collect();
+        quote! {
+            #[inline]
+            fn store_secondary_keys(
+                table: ::pulse_cdt::core::Name,
+                scope: u64,
+                payer: ::pulse_cdt::core::Name,
+                row: &Self::Row,
+            ) {
+                #( ::pulse_cdt::core::store_secondary_key::<Self, #indexes>(table, scope, payer, row); )*
+            }
+
+            #[inline]
+            fn update_secondary_keys(
+                code: ::pulse_cdt::core::Name,
+                table: ::pulse_cdt::core::Name,
+                scope: u64,
+                payer: ::pulse_cdt::core::Name,
+                row: &Self::Row,
+            ) {
+                #( ::pulse_cdt::core::update_secondary_key::<Self, #indexes>(code, table, scope, payer, row); )*
+            }
+
+            #[inline]
+            fn remove_secondary_keys(
+                code: ::pulse_cdt::core::Name,
+                table: ::pulse_cdt::core::Name,
+                scope: u64,
+                row: &Self::Row,
+            ) {
+                #( ::pulse_cdt::core::remove_secondary_key::<Self, #indexes>(code, table, scope, row); )*
+            }
+        }
     };
 
     let expanded = quote! {
@@ -63,7 +157,11 @@ pub fn table_macro(attr: TokenStream, item: TokenStream) -> TokenStream {
             fn primary_key(row: &Self::Row) -> u64 {
                 #body
             }
+
+            #hooks
         }
+
+        #(#index_impls)*
     };
 
     expanded.into()
@@ -0,0 +1,461 @@
+use alloc::{
+    borrow::ToOwned,
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet, vec_deque::VecDeque},
+    format,
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write as _;
+
+use crate::{BinaryExtension, VarInt32, VarUint32};
+
+// Lets derived impls name `String` without the deriving crate importing `alloc`.
+#[doc(hidden)]
+pub use alloc::string::String as __String;
+
+pub const ABI_VERSION: &str = "eosio::abi/1.2";
+
+/// Describes how a type is laid out on the wire, in ABI terms.
+///
+/// Derived along with `Write`; types that are ABI builtins name themselves
+/// with `#[pulse(abi = "...")]` instead of describing their fields.
+pub trait AbiType {
+    /// Returns the ABI name of the type, adding any structs, variants or
+    /// aliases it is made of to `abi`.
+    fn abi_type(abi: &mut Abi) -> String;
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AbiStruct {
+    pub base: String,
+    /// `(name, type)` pairs in serialization order.
+    pub fields: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AbiAction {
+    /// Name of the struct holding the action's parameters.
+    pub ty: String,
+    pub ricardian_contract: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiTable {
+    /// Name of the row struct.
+    pub ty: String,
+    pub index_type: String,
+    pub key_names: Vec<String>,
+    pub key_types: Vec<String>,
+}
+
+/// An ABI document. Every section is keyed by name, so the output does not
+/// depend on the order in which definitions were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Abi {
+    /// `new_type_name -> type`
+    pub types: BTreeMap<String, String>,
+    pub structs: BTreeMap<String, AbiStruct>,
+    pub actions: BTreeMap<String, AbiAction>,
+    pub tables: BTreeMap<String, AbiTable>,
+    /// `id -> body`
+    pub ricardian_clauses: BTreeMap<String, String>,
+    /// `name -> alternative types`, in tag order.
+    pub variants: BTreeMap<String, Vec<String>>,
+    /// `action name -> result type`
+    pub action_results: BTreeMap<String, String>,
+}
+
+impl Abi {
+    /// Returns the ABI name of `T`, adding its definitions.
+    #[inline]
+    pub fn add_type<T: AbiType + ?Sized>(&mut self) -> String {
+        T::abi_type(self)
+    }
+
+    /// Adds the struct `name` unless it is already there, describing its
+    /// fields with `fields`.
+    ///
+    /// The name is reserved before `fields` runs, so recursive types
+    /// terminate.
+    pub fn add_struct_with<'a, F>(&mut self, name: &str, fields: impl FnOnce(&mut Self) -> F) -> String
+    where
+        F: IntoIterator<Item = (&'a str, String)>,
+    {
+        if !self.structs.contains_key(name) {
+            self.structs.insert(name.to_owned(), AbiStruct::default());
+            let fields = fields(self)
+                .into_iter()
+                .map(|(name, ty)| (name.to_owned(), ty))
+                .collect();
+            self.structs.get_mut(name).expect("reserved above").fields = fields;
+        }
+        name.to_owned()
+    }
+
+    /// Adds the variant `name` unless it is already there.
+    pub fn add_variant_with<T>(&mut self, name: &str, types: impl FnOnce(&mut Self) -> T) -> String
+    where
+        T: IntoIterator<Item = String>,
+    {
+        if !self.variants.contains_key(name) {
+            self.variants.insert(name.to_owned(), Vec::new());
+            let types = types(self).into_iter().collect();
+            *self.variants.get_mut(name).expect("reserved above") = types;
+        }
+        name.to_owned()
+    }
+
+    /// Adds an action whose parameters are described by the struct `name`.
+    pub fn add_action<'a>(&mut self, name: &str, params: impl IntoIterator<Item = (&'a str, String)>) {
+        self.structs.insert(
+            name.to_owned(),
+            AbiStruct {
+                base: String::new(),
+                fields: params
+                    .into_iter()
+                    .map(|(name, ty)| (name.to_owned(), ty))
+                    .collect(),
+            },
+        );
+        self.actions.insert(
+            name.to_owned(),
+            AbiAction {
+                ty: name.to_owned(),
+                ricardian_contract: String::new(),
+            },
+        );
+    }
+
+    /// Adds a table of `T` rows, keyed by a 64-bit primary key.
+    pub fn add_table<T: AbiType>(&mut self, name: &str) {
+        let ty = T::abi_type(self);
+        self.tables.insert(
+            name.to_owned(),
+            AbiTable {
+                ty,
+                index_type: "i64".to_owned(),
+                key_names: Vec::new(),
+                key_types: Vec::new(),
+            },
+        );
+    }
+
+    /// Pretty-printed JSON with sorted keys, terminated by a newline.
+    pub fn to_json(&self) -> String {
+        let strings = |v: &[String]| Json::Arr(v.iter().map(|s| Json::Str(s.clone())).collect());
+        let types = self
+            .types
+            .iter()
+            .map(|(name, ty)| Json::obj([("new_type_name", Json::str(name)), ("type", Json::str(ty))]))
+            .collect();
+        let structs = self
+            .structs
+            .iter()
+            .map(|(name, def)| {
+                let fields = def
+                    .fields
+                    .iter()
+                    .map(|(name, ty)| Json::obj([("name", Json::str(name)), ("type", Json::str(ty))]))
+                    .collect();
+                Json::obj([
+                    ("base", Json::str(&def.base)),
+                    ("fields", Json::Arr(fields)),
+                    ("name", Json::str(name)),
+                ])
+            })
+            .collect();
+        let actions = self
+            .actions
+            .iter()
+            .map(|(name, action)| {
+                Json::obj([
+                    ("name", Json::str(name)),
+                    ("ricardian_contract", Json::str(&action.ricardian_contract)),
+                    ("type", Json::str(&action.ty)),
+                ])
+            })
+            .collect();
+        let tables = self
+            .tables
+            .iter()
+            .map(|(name, table)| {
+                Json::obj([
+                    ("index_type", Json::str(&table.index_type)),
+                    ("key_names", strings(&table.key_names)),
+                    ("key_types", strings(&table.key_types)),
+                    ("name", Json::str(name)),
+                    ("type", Json::str(&table.ty)),
+                ])
+            })
+            .collect();
+        let ricardian_clauses = self
+            .ricardian_clauses
+            .iter()
+            .map(|(id, body)| Json::obj([("body", Json::str(body)), ("id", Json::str(id))]))
+            .collect();
+        let variants = self
+            .variants
+            .iter()
+            .map(|(name, types)| Json::obj([("name", Json::str(name)), ("types", strings(types))]))
+            .collect();
+        let action_results = self
+            .action_results
+            .iter()
+            .map(|(name, ty)| Json::obj([("name", Json::str(name)), ("result_type", Json::str(ty))]))
+            .collect();
+
+        let doc = Json::obj([
+            ("____comment", Json::str("This file was generated. DO NOT EDIT ")),
+            ("abi_extensions", Json::Arr(Vec::new())),
+            ("action_results", Json::Arr(action_results)),
+            ("actions", Json::Arr(actions)),
+            ("error_messages", Json::Arr(Vec::new())),
+            ("ricardian_clauses", Json::Arr(ricardian_clauses)),
+            ("structs", Json::Arr(structs)),
+            ("tables", Json::Arr(tables)),
+            ("types", Json::Arr(types)),
+            ("variants", Json::Arr(variants)),
+            ("version", Json::str(ABI_VERSION)),
+        ]);
+        let mut out = String::new();
+        doc.write(&mut out, 0);
+        out.push('\n');
+        out
+    }
+}
+
+/// Just enough JSON to print an ABI the way `serde_json` pretty-prints it.
+enum Json {
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    fn str(s: &str) -> Self {
+        Self::Str(s.to_owned())
+    }
+
+    fn obj<const N: usize>(entries: [(&'static str, Json); N]) -> Self {
+        Self::Obj(entries.into())
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Self::Str(s) => write_json_string(out, s),
+            Self::Arr(items) if items.is_empty() => out.push_str("[]"),
+            Self::Arr(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    push_indent(out, indent + 1);
+                    item.write(out, indent + 1);
+                }
+                out.push('\n');
+                push_indent(out, indent);
+                out.push(']');
+            }
+            Self::Obj(entries) if entries.is_empty() => out.push_str("{}"),
+            Self::Obj(entries) => {
+                out.push('{');
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    push_indent(out, indent + 1);
+                    write_json_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                }
+                out.push('\n');
+                push_indent(out, indent);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// The ABI name of a generic struct instantiated with `args`, e.g.
+/// `Wrapper_name` for `Wrapper<Name>`.
+pub fn generic_abi_name(base: &str, args: &[String]) -> String {
+    let mut name = String::from(base);
+    for arg in args {
+        name.push('_');
+        name.push_str(&sanitize(arg));
+    }
+    name
+}
+
+/// The ABI name of the struct holding the fields of an enum variant, e.g.
+/// `ProposalTransfer` for `Proposal::Transfer { .. }`.
+pub fn variant_struct_name(enum_name: &str, variant: &str) -> String {
+    format!("{enum_name}{variant}")
+}
+
+/// The ABI type of a binary extension holding a `ty`, e.g. `uint64$`.
+pub fn binary_extension_abi_name(ty: String) -> String {
+    ty + "$"
+}
+
+/// Turns an ABI type into something usable inside a struct name.
+fn sanitize(ty: &str) -> String {
+    let mut out = String::with_capacity(ty.len());
+    for c in ty.chars() {
+        let c = if c.is_ascii_alphanumeric() { c } else { '_' };
+        if c != '_' || !out.ends_with('_') {
+            out.push(c);
+        }
+    }
+    out.trim_matches('_').to_owned()
+}
+
+macro_rules! impl_abi_builtin {
+    ($($t:ty => $name:literal),* $(,)?) => {
+        $(
+            impl AbiType for $t {
+                #[inline]
+                fn abi_type(_abi: &mut Abi) -> String {
+                    $name.to_owned()
+                }
+            }
+        )*
+    };
+}
+
+impl_abi_builtin! {
+    bool => "bool",
+    i8 => "int8",
+    i16 => "int16",
+    i32 => "int32",
+    i64 => "int64",
+    i128 => "int128",
+    u8 => "uint8",
+    u16 => "uint16",
+    u32 => "uint32",
+    u64 => "uint64",
+    u128 => "uint128",
+    f32 => "float32",
+    f64 => "float64",
+    usize => "varuint32",
+    VarInt32 => "varint32",
+    VarUint32 => "varuint32",
+    String => "string",
+    str => "string",
+}
+
+impl<T: AbiType + ?Sized> AbiType for &T {
+    #[inline]
+    fn abi_type(abi: &mut Abi) -> String {
+        T::abi_type(abi)
+    }
+}
+
+impl<T: AbiType> AbiType for Option<T> {
+    fn abi_type(abi: &mut Abi) -> String {
+        format!("{}?", T::abi_type(abi))
+    }
+}
+
+impl<T: AbiType> AbiType for BinaryExtension<T> {
+    fn abi_type(abi: &mut Abi) -> String {
+        binary_extension_abi_name(T::abi_type(abi))
+    }
+}
+
+/// `T[]`, or `bytes` for a sequence of `u8`.
+fn sequence<T: AbiType>(abi: &mut Abi) -> String {
+    let elem = T::abi_type(abi);
+    if elem == "uint8" {
+        "bytes".to_owned()
+    } else {
+        format!("{elem}[]")
+    }
+}
+
+impl<T: AbiType> AbiType for [T] {
+    fn abi_type(abi: &mut Abi) -> String {
+        sequence::<T>(abi)
+    }
+}
+
+impl<T: AbiType> AbiType for Vec<T> {
+    fn abi_type(abi: &mut Abi) -> String {
+        sequence::<T>(abi)
+    }
+}
+
+impl<T: AbiType> AbiType for VecDeque<T> {
+    fn abi_type(abi: &mut Abi) -> String {
+        sequence::<T>(abi)
+    }
+}
+
+impl<T: AbiType> AbiType for BTreeSet<T> {
+    fn abi_type(abi: &mut Abi) -> String {
+        sequence::<T>(abi)
+    }
+}
+
+impl<K: AbiType, V: AbiType> AbiType for BTreeMap<K, V> {
+    fn abi_type(abi: &mut Abi) -> String {
+        format!("{}[]", <(K, V)>::abi_type(abi))
+    }
+}
+
+/// Pairs are `pair_<A>_<B>` structs with `key` and `value` fields.
+impl<T1: AbiType, T2: AbiType> AbiType for (T1, T2) {
+    fn abi_type(abi: &mut Abi) -> String {
+        let first = T1::abi_type(abi);
+        let second = T2::abi_type(abi);
+        let name = format!("pair_{}_{}", sanitize(&first), sanitize(&second));
+        abi.add_struct_with(&name, |_| [("key", first), ("value", second)])
+    }
+}
+
+/// Other tuples are `tuple_<A>_<B>_...` structs with `field_<i>` fields.
+macro_rules! impl_abi_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: AbiType),+> AbiType for ($($t,)+) {
+            fn abi_type(abi: &mut Abi) -> String {
+                let types = vec![$($t::abi_type(abi)),+];
+                let name = generic_abi_name("tuple", &types);
+                let fields: Vec<(String, String)> = types
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, ty)| (format!("field_{i}"), ty))
+                    .collect();
+                abi.add_struct_with(&name, |_| fields.iter().map(|(n, t)| (n.as_str(), t.clone())))
+            }
+        }
+    };
+}
+
+impl_abi_tuple!(T1);
+impl_abi_tuple!(T1, T2, T3);
+impl_abi_tuple!(T1, T2, T3, T4);
+impl_abi_tuple!(T1, T2, T3, T4, T5);
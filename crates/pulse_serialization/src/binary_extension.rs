@@ -0,0 +1,102 @@
+use crate::{NumBytes, Read, ReadError, Write, WriteError};
+
+/// A field added to a struct after data was already stored or sent with
+/// the struct's previous layout: data that ends before the field reads as
+/// absent. Its ABI type is the inner one with a `$` suffix.
+///
+/// As absent extensions are simply missing from the data, only other
+/// extensions may follow one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct BinaryExtension<T>(Option<T>);
+
+impl<T> BinaryExtension<T> {
+    /// An extension holding `value`.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Self(Some(value))
+    }
+
+    /// An absent extension.
+    #[inline]
+    pub const fn none() -> Self {
+        Self(None)
+    }
+
+    #[inline]
+    pub const fn has_value(&self) -> bool {
+        self.0.is_some()
+    }
+
+    #[inline]
+    pub const fn is_none(&self) -> bool {
+        self.0.is_none()
+    }
+
+    #[inline]
+    pub const fn value(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    #[inline]
+    pub fn value_mut(&mut self) -> Option<&mut T> {
+        self.0.as_mut()
+    }
+
+    /// The value, or `default` if the extension is absent.
+    #[inline]
+    pub fn value_or(self, default: T) -> T {
+        self.0.unwrap_or(default)
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> From<T> for BinaryExtension<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> From<Option<T>> for BinaryExtension<T> {
+    #[inline]
+    fn from(value: Option<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: NumBytes> NumBytes for BinaryExtension<T> {
+    #[inline]
+    fn num_bytes(&self) -> usize {
+        self.0.as_ref().map_or(0, NumBytes::num_bytes)
+    }
+}
+
+impl<T: Read> Read for BinaryExtension<T> {
+    #[inline]
+    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        if *pos < bytes.len() {
+            T::read(bytes, pos).map(Self::new)
+        } else {
+            Ok(Self::none())
+        }
+    }
+}
+
+impl<T: Write> Write for BinaryExtension<T> {
+    #[inline]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        match &self.0 {
+            Some(value) => value.write(bytes, pos),
+            None => Ok(()),
+        }
+    }
+}
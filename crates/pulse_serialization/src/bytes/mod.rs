@@ -1,10 +1,12 @@
 mod data_stream;
 mod primitives;
+mod read_error;
 
 pub use self::data_stream::DataStream;
+pub use self::read_error::{PathSegment, ReadError, ReadErrorKind};
 use alloc::vec;
 use alloc::vec::Vec;
-pub use pulse_proc_macro::{NumBytes, Read, Write};
+pub use pulse_proc_macro::{NumBytes, Read, ReadBorrowed, Write};
 
 /// Count the number of bytes a type is expected to use.
 pub trait NumBytes {
@@ -32,13 +34,33 @@ pub trait Read: Sized + NumBytes {
     }
 }
 
-/// Error that can be returned when reading bytes.
-#[derive(Debug, Clone, Copy)]
-pub enum ReadError {
-    /// Not enough bytes.
-    NotEnoughBytes,
-    ParseError,
-    Overflow,
+/// Read bytes, possibly borrowing from them: `&'a str` and `&'a [u8]`
+/// point into the data instead of copying it, as do the structs deriving
+/// `ReadBorrowed`. Every [`Read`] type is also `ReadBorrowed`.
+pub trait ReadBorrowed<'a>: Sized {
+    /// Read bytes.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was a problem reading the data.
+    fn read_borrowed(bytes: &'a [u8], pos: &mut usize) -> Result<Self, ReadError>;
+
+    /// Deserializes a byte array into a data type borrowing from it.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if there was a problem reading the data.
+    #[inline(always)]
+    fn unpack_borrowed(bytes: &'a [u8]) -> Result<Self, ReadError> {
+        Self::read_borrowed(bytes, &mut 0)
+    }
+}
+
+impl<'a, T: Read> ReadBorrowed<'a> for T {
+    #[inline(always)]
+    fn read_borrowed(bytes: &'a [u8], pos: &mut usize) -> Result<Self, ReadError> {
+        T::read(bytes, pos)
+    }
 }
 
 /// Write bytes.
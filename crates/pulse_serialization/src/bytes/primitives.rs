@@ -8,7 +8,7 @@ use alloc::{
 
 use crate::VarUint32;
 
-use super::{NumBytes, Read, ReadError, Write, WriteError};
+use super::{NumBytes, Read, ReadBorrowed, ReadError, ReadErrorKind, Write, WriteError};
 
 impl NumBytes for usize {
     #[inline(always)]
@@ -73,6 +73,20 @@ impl NumBytes for i64 {
     }
 }
 
+impl NumBytes for u128 {
+    #[inline(always)]
+    fn num_bytes(&self) -> usize {
+        core::mem::size_of::<u128>()
+    }
+}
+
+impl NumBytes for i128 {
+    #[inline(always)]
+    fn num_bytes(&self) -> usize {
+        core::mem::size_of::<u128>()
+    }
+}
+
 impl NumBytes for f32 {
     #[inline]
     fn num_bytes(&self) -> usize {
@@ -94,6 +108,20 @@ impl NumBytes for String {
     }
 }
 
+impl NumBytes for &str {
+    #[inline(always)]
+    fn num_bytes(&self) -> usize {
+        self.len().num_bytes() + self.len()
+    }
+}
+
+impl NumBytes for &[u8] {
+    #[inline(always)]
+    fn num_bytes(&self) -> usize {
+        self.len().num_bytes() + self.len()
+    }
+}
+
 impl NumBytes for bool {
     #[inline(always)]
     fn num_bytes(&self) -> usize {
@@ -197,141 +225,91 @@ impl<T1: NumBytes, T2: NumBytes, T3: NumBytes, T4: NumBytes, T5: NumBytes> NumBy
     }
 }
 
-impl Read for usize {
-    #[inline(always)]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        VarUint32::read(bytes, pos).map(|v| v.0 as usize)
-    }
+/// The `len` bytes at `pos`, moving `pos` past them.
+#[inline(always)]
+fn take<'a>(
+    bytes: &'a [u8],
+    pos: &mut usize,
+    len: usize,
+    expected: &'static str,
+) -> Result<&'a [u8], ReadError> {
+    let start = *pos;
+    let value = start
+        .checked_add(len)
+        .and_then(|end| bytes.get(start..end))
+        .ok_or_else(|| ReadError::new(ReadErrorKind::NotEnoughBytes, expected, start))?;
+    *pos += len;
+    Ok(value)
+}
+
+/// The length prefix of a sequence of `T`, checking the data has at least
+/// one byte left for each element.
+#[inline(always)]
+fn read_len<T>(bytes: &[u8], pos: &mut usize) -> Result<usize, ReadError> {
+    let start = *pos;
+    let len = usize::read(bytes, pos)?;
+    if len > bytes.len().saturating_sub(*pos) {
+        return Err(ReadError::new(
+            ReadErrorKind::NotEnoughBytes,
+            core::any::type_name::<T>(),
+            start,
+        ));
+    }
+    Ok(len)
 }
 
-impl Read for u8 {
-    #[inline(always)]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        if bytes.len() < *pos + core::mem::size_of::<u8>() {
-            return Err(ReadError::NotEnoughBytes);
-        }
-        let value = u8::from_le_bytes([bytes[*pos]]);
-        *pos += core::mem::size_of::<u8>();
-        Ok(value)
-    }
-}
-
-impl Read for i8 {
-    #[inline(always)]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let result = u8::read(bytes, pos).expect("failed to read u8");
-        Ok(result as i8)
-    }
-}
-
-impl Read for u16 {
-    #[inline(always)]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        if bytes.len() < *pos + core::mem::size_of::<u16>() {
-            return Err(ReadError::NotEnoughBytes);
-        }
-        let value = u16::from_le_bytes([bytes[*pos], bytes[*pos + 1]]);
-        *pos += core::mem::size_of::<u16>();
-        Ok(value)
-    }
-}
-
-impl Read for i16 {
+impl Read for usize {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let result = u16::read(bytes, pos).expect("failed to read u16");
-        Ok(result as i16)
+        VarUint32::read(bytes, pos).map(|v| v.0 as usize)
     }
 }
 
-impl Read for u32 {
-    #[inline(always)]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        if bytes.len() < *pos + core::mem::size_of::<u32>() {
-            return Err(ReadError::NotEnoughBytes);
+macro_rules! read_le_bytes {
+    ($($ty:ident),*) => {$(
+        impl Read for $ty {
+            #[inline(always)]
+            fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+                const LEN: usize = core::mem::size_of::<$ty>();
+                let mut value = [0_u8; LEN];
+                value.copy_from_slice(take(bytes, pos, LEN, stringify!($ty))?);
+                Ok(Self::from_le_bytes(value))
+            }
         }
-        let value = u32::from_le_bytes([
-            bytes[*pos],
-            bytes[*pos + 1],
-            bytes[*pos + 2],
-            bytes[*pos + 3],
-        ]);
-        *pos += core::mem::size_of::<u32>();
-        Ok(value)
-    }
+    )*};
 }
 
-impl Read for i32 {
-    #[inline(always)]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let result = u32::read(bytes, pos).expect("failed to read u32");
-        Ok(result as i32)
-    }
-}
+read_le_bytes!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
 
-impl Read for u64 {
+impl Read for String {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        if bytes.len() < *pos + core::mem::size_of::<u64>() {
-            return Err(ReadError::NotEnoughBytes);
+        let start = *pos;
+        let len = read_len::<Self>(bytes, pos)?;
+        let str_bytes = take(bytes, pos, len, "String")?;
+        match str::from_utf8(str_bytes) {
+            Ok(s) => Ok(s.to_string()), // Into<String> in most contexts, still OK
+            Err(_) => Err(ReadError::new(ReadErrorKind::ParseError, "String", start)),
         }
-        let value = u64::from_le_bytes([
-            bytes[*pos],
-            bytes[*pos + 1],
-            bytes[*pos + 2],
-            bytes[*pos + 3],
-            bytes[*pos + 4],
-            bytes[*pos + 5],
-            bytes[*pos + 6],
-            bytes[*pos + 7],
-        ]);
-        *pos += core::mem::size_of::<u64>();
-        Ok(value)
     }
 }
 
-impl Read for i64 {
+impl<'a> ReadBorrowed<'a> for &'a str {
     #[inline(always)]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let result = u64::read(bytes, pos).expect("failed to read u64");
-        Ok(result as i64)
-    }
-}
-
-impl Read for f32 {
-    #[inline]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let bits = u32::read(bytes, pos)?;
-        let num = Self::from_bits(bits);
-        Ok(num)
+    fn read_borrowed(bytes: &'a [u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let start = *pos;
+        let len = read_len::<Self>(bytes, pos)?;
+        let str_bytes = take(bytes, pos, len, "&str")?;
+        str::from_utf8(str_bytes)
+            .map_err(|_| ReadError::new(ReadErrorKind::ParseError, "&str", start))
     }
 }
 
-impl Read for f64 {
-    #[inline]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let bits = u64::read(bytes, pos)?;
-        let num = Self::from_bits(bits);
-        Ok(num)
-    }
-}
-
-impl Read for String {
+impl<'a> ReadBorrowed<'a> for &'a [u8] {
     #[inline(always)]
-    fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let len = usize::read(bytes, pos)?;
-
-        if *pos + len > bytes.len() {
-            return Err(ReadError::NotEnoughBytes);
-        }
-
-        let str_bytes = &bytes[*pos..*pos + len];
-        *pos += len;
-
-        match str::from_utf8(str_bytes) {
-            Ok(s) => Ok(s.to_string()), // Into<String> in most contexts, still OK
-            Err(_) => Err(ReadError::ParseError),
-        }
+    fn read_borrowed(bytes: &'a [u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let len = read_len::<Self>(bytes, pos)?;
+        take(bytes, pos, len, "&[u8]")
     }
 }
 
@@ -341,15 +319,10 @@ where
 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let len = usize::read(bytes, pos)?;
-
-        if *pos + len > bytes.len() {
-            return Err(ReadError::NotEnoughBytes);
-        }
-
+        let len = read_len::<Self>(bytes, pos)?;
         let mut vec = Vec::with_capacity(len);
-        for _ in 0..len {
-            let item = T::read(bytes, pos)?;
+        for i in 0..len {
+            let item = T::read(bytes, pos).map_err(|e| e.in_index(i))?;
             vec.push(item);
         }
         Ok(vec)
@@ -362,15 +335,10 @@ where
 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let len = usize::read(bytes, pos)?;
-
-        if *pos + len > bytes.len() {
-            return Err(ReadError::NotEnoughBytes);
-        }
-
+        let len = read_len::<Self>(bytes, pos)?;
         let mut vec = VecDeque::with_capacity(len);
-        for _ in 0..len {
-            let item = T::read(bytes, pos)?;
+        for i in 0..len {
+            let item = T::read(bytes, pos).map_err(|e| e.in_index(i))?;
             vec.push_back(item);
         }
         Ok(vec)
@@ -380,16 +348,11 @@ where
 impl<K: Read + Write + NumBytes + Ord, V: Read + Write + NumBytes> Read for BTreeMap<K, V> {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let len = usize::read(bytes, pos)?;
-
-        if *pos + len > bytes.len() {
-            return Err(ReadError::NotEnoughBytes);
-        }
-
+        let len = read_len::<Self>(bytes, pos)?;
         let mut map = BTreeMap::new();
-        for _ in 0..len {
-            let key = K::read(bytes, pos)?;
-            let value = V::read(bytes, pos)?;
+        for i in 0..len {
+            let key = K::read(bytes, pos).map_err(|e| e.in_index(i))?;
+            let value = V::read(bytes, pos).map_err(|e| e.in_index(i))?;
             map.insert(key, value);
         }
         Ok(map)
@@ -399,15 +362,10 @@ impl<K: Read + Write + NumBytes + Ord, V: Read + Write + NumBytes> Read for BTre
 impl<V: Read + Write + NumBytes + Ord> Read for BTreeSet<V> {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let len = usize::read(bytes, pos)?;
-
-        if *pos + len > bytes.len() {
-            return Err(ReadError::NotEnoughBytes);
-        }
-
+        let len = read_len::<Self>(bytes, pos)?;
         let mut set = BTreeSet::new();
-        for _ in 0..len {
-            let value = V::read(bytes, pos)?;
+        for i in 0..len {
+            let value = V::read(bytes, pos).map_err(|e| e.in_index(i))?;
             set.insert(value);
         }
         Ok(set)
@@ -420,7 +378,7 @@ where
 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let first = T1::read(bytes, pos)?;
+        let first = T1::read(bytes, pos).map_err(|e| e.in_index(0))?;
         Ok((first,))
     }
 }
@@ -432,8 +390,8 @@ where
 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let first = T1::read(bytes, pos)?;
-        let second = T2::read(bytes, pos)?;
+        let first = T1::read(bytes, pos).map_err(|e| e.in_index(0))?;
+        let second = T2::read(bytes, pos).map_err(|e| e.in_index(1))?;
         Ok((first, second))
     }
 }
@@ -446,9 +404,9 @@ where
 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let first = T1::read(bytes, pos)?;
-        let second = T2::read(bytes, pos)?;
-        let third = T3::read(bytes, pos)?;
+        let first = T1::read(bytes, pos).map_err(|e| e.in_index(0))?;
+        let second = T2::read(bytes, pos).map_err(|e| e.in_index(1))?;
+        let third = T3::read(bytes, pos).map_err(|e| e.in_index(2))?;
         Ok((first, second, third))
     }
 }
@@ -462,10 +420,10 @@ where
 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let first = T1::read(bytes, pos)?;
-        let second = T2::read(bytes, pos)?;
-        let third = T3::read(bytes, pos)?;
-        let fourth = T4::read(bytes, pos)?;
+        let first = T1::read(bytes, pos).map_err(|e| e.in_index(0))?;
+        let second = T2::read(bytes, pos).map_err(|e| e.in_index(1))?;
+        let third = T3::read(bytes, pos).map_err(|e| e.in_index(2))?;
+        let fourth = T4::read(bytes, pos).map_err(|e| e.in_index(3))?;
         Ok((first, second, third, fourth))
     }
 }
@@ -480,11 +438,11 @@ where
 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let first = T1::read(bytes, pos)?;
-        let second = T2::read(bytes, pos)?;
-        let third = T3::read(bytes, pos)?;
-        let fourth = T4::read(bytes, pos)?;
-        let fifth = T5::read(bytes, pos)?;
+        let first = T1::read(bytes, pos).map_err(|e| e.in_index(0))?;
+        let second = T2::read(bytes, pos).map_err(|e| e.in_index(1))?;
+        let third = T3::read(bytes, pos).map_err(|e| e.in_index(2))?;
+        let fourth = T4::read(bytes, pos).map_err(|e| e.in_index(3))?;
+        let fifth = T5::read(bytes, pos).map_err(|e| e.in_index(4))?;
         Ok((first, second, third, fourth, fifth))
     }
 }
@@ -492,8 +450,8 @@ where
 impl Read for bool {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
-        let value = u8::read(bytes, pos).expect("failed to read u8");
-        Ok(value != 0)
+        let value = take(bytes, pos, 1, "bool")?;
+        Ok(value[0] != 0)
     }
 }
 
@@ -596,6 +554,26 @@ impl Write for i64 {
     }
 }
 
+impl Write for u128 {
+    #[inline(always)]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        let end = *pos + core::mem::size_of::<u128>();
+        if bytes.len() < end {
+            return Err(WriteError::NotEnoughSpace);
+        }
+        bytes[*pos..end].copy_from_slice(&self.to_le_bytes());
+        *pos = end;
+        Ok(())
+    }
+}
+
+impl Write for i128 {
+    #[inline(always)]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        (*self as u128).write(bytes, pos)
+    }
+}
+
 impl Write for f32 {
     #[inline]
     fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
@@ -622,6 +600,27 @@ impl<'a> Write for String {
     }
 }
 
+impl Write for &str {
+    #[inline(always)]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.as_bytes().write(bytes, pos)
+    }
+}
+
+impl Write for &[u8] {
+    #[inline(always)]
+    fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
+        self.len().write(bytes, pos)?;
+        let end = *pos + self.len();
+        bytes
+            .get_mut(*pos..end)
+            .ok_or(WriteError::NotEnoughSpace)?
+            .copy_from_slice(self);
+        *pos = end;
+        Ok(())
+    }
+}
+
 impl Write for bool {
     #[inline(always)]
     fn write(&self, bytes: &mut [u8], pos: &mut usize) -> Result<(), WriteError> {
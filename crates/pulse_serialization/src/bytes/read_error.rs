@@ -0,0 +1,177 @@
+use core::fmt;
+
+use alloc::{boxed::Box, vec::Vec};
+
+/// What went wrong reading a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadErrorKind {
+    /// The data ends before the value does.
+    NotEnoughBytes,
+    /// The bytes aren't a valid value, e.g. a string that isn't UTF-8 or an
+    /// out of range enum tag.
+    ParseError,
+    /// A number doesn't fit its type.
+    Overflow,
+}
+
+impl ReadErrorKind {
+    /// How the kind is described in error messages.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotEnoughBytes => "not enough bytes",
+            Self::ParseError => "invalid data",
+            Self::Overflow => "number out of range",
+        }
+    }
+}
+
+impl fmt::Display for ReadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A step of the path from the value being read to the one that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A struct field or an action argument.
+    Field(&'static str),
+    /// An element of a sequence or a tuple.
+    Index(usize),
+}
+
+/// Error that can be returned when reading bytes.
+///
+/// Besides the [`kind`](Self::kind), it records where the failing value
+/// starts in the data, the type being read and the fields leading to it,
+/// which `Display` writes as e.g.
+/// `transfer.quantity: not enough bytes at offset 24`. Contracts build
+/// the same message without `core::fmt`, see
+/// [`ReadErrorKind::as_str`].
+///
+/// The details are boxed, keeping `Result<T, ReadError>` small for the
+/// common case of reads that succeed. On wasm32 the type name is left out,
+/// to keep type names out of contract binaries.
+#[derive(Clone)]
+pub struct ReadError(Box<Details>);
+
+#[derive(Clone)]
+struct Details {
+    kind: ReadErrorKind,
+    offset: usize,
+    #[cfg(not(target_arch = "wasm32"))]
+    expected: &'static str,
+    /// Outermost first.
+    path: Vec<PathSegment>,
+}
+
+impl ReadError {
+    /// An error reading a value of type `expected` that starts at `offset`.
+    #[cold]
+    pub fn new(kind: ReadErrorKind, expected: &'static str, offset: usize) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        let _ = expected;
+        Self(Box::new(Details {
+            kind,
+            offset,
+            #[cfg(not(target_arch = "wasm32"))]
+            expected,
+            path: Vec::new(),
+        }))
+    }
+
+    #[inline]
+    pub fn kind(&self) -> ReadErrorKind {
+        self.0.kind
+    }
+
+    /// Where the value that failed starts in the data.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.0.offset
+    }
+
+    /// The type of the value that failed, `None` on wasm32.
+    #[inline]
+    pub fn expected(&self) -> Option<&'static str> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return Some(self.0.expected);
+        #[cfg(target_arch = "wasm32")]
+        return None;
+    }
+
+    /// The fields and indices from the value being read to the one that
+    /// failed, outermost first.
+    #[inline]
+    pub fn path(&self) -> &[PathSegment] {
+        &self.0.path
+    }
+
+    /// Records that the error happened in `field` of the enclosing value.
+    #[cold]
+    pub fn in_field(mut self, field: &'static str) -> Self {
+        self.0.path.insert(0, PathSegment::Field(field));
+        self
+    }
+
+    /// Records that the error happened in element `index` of the enclosing
+    /// sequence or tuple.
+    #[cold]
+    pub fn in_index(mut self, index: usize) -> Self {
+        self.0.path.insert(0, PathSegment::Index(index));
+        self
+    }
+
+    /// Reports the error as one reading `expected` that starts at `offset`,
+    /// dropping the path inside it. For types whose parts are an
+    /// implementation detail, such as an `asset`'s amount and symbol.
+    #[cold]
+    pub fn reading(mut self, expected: &'static str, offset: usize) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.0.expected = expected;
+        }
+        #[cfg(target_arch = "wasm32")]
+        let _ = expected;
+        self.0.offset = offset;
+        self.0.path.clear();
+        self
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.path().iter().enumerate() {
+            match segment {
+                PathSegment::Field(field) if i == 0 => f.write_str(field)?,
+                PathSegment::Field(field) => write!(f, ".{field}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        if !self.path().is_empty() {
+            f.write_str(": ")?;
+        }
+        write!(f, "{} at offset {}", self.kind(), self.offset())
+    }
+}
+
+impl fmt::Debug for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // A contract's panic handler only reports static messages, so
+        // `expect`ing a read there never shows this. Writing the kind alone
+        // keeps the escaping of the path's strings out of the binary.
+        #[cfg(target_arch = "wasm32")]
+        return f.write_str(self.kind().as_str());
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let mut debug = f.debug_struct("ReadError");
+            debug
+                .field("kind", &self.kind())
+                .field("offset", &self.offset());
+            if let Some(expected) = self.expected() {
+                debug.field("expected", &expected);
+            }
+            debug.field("path", &self.path()).finish()
+        }
+    }
+}
@@ -1,12 +1,24 @@
 #![no_std]
 extern crate alloc;
 
+pub mod abi;
+
+mod binary_extension;
+pub use binary_extension::*;
+
 mod bytes;
 
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub mod serde_support;
+
 mod varint;
 pub use varint::*;
 
-pub use self::bytes::{DataStream, NumBytes, Read, ReadError, Write, WriteError};
+pub use self::bytes::{
+    DataStream, NumBytes, PathSegment, Read, ReadBorrowed, ReadError, ReadErrorKind, Write,
+    WriteError,
+};
 
 #[cfg(test)]
 mod tests {
@@ -92,4 +104,226 @@ mod tests {
         let result = bool::read(&ds.as_bytes(), &mut 0).unwrap();
         assert_eq!(result, true);
     }
+
+    #[derive(Debug, PartialEq, pulse_proc_macro::NumBytes, pulse_proc_macro::Read, pulse_proc_macro::Write)]
+    #[pulse(crate_path = "crate")]
+    enum Proposal {
+        Empty,
+        Transfer(u64, u32),
+        Vote { voter: u64, approve: bool },
+    }
+
+    #[test]
+    fn test_enum() {
+        use super::{NumBytes, Write};
+
+        let cases = [
+            (Proposal::Empty, "00"),
+            (Proposal::Transfer(1, 2), "01010000000000000002000000"),
+            (
+                Proposal::Vote {
+                    voter: 3,
+                    approve: true,
+                },
+                "02030000000000000001",
+            ),
+        ];
+        for (value, encoded) in cases {
+            let bytes = value.pack().expect("Failed to write");
+            assert_eq!(hex::encode(&bytes), encoded);
+            assert_eq!(value.num_bytes(), bytes.len());
+            assert_eq!(Proposal::read(&bytes, &mut 0).unwrap(), value);
+        }
+
+        assert!(Proposal::read(&[0x03], &mut 0).is_err());
+    }
+
+    #[derive(pulse_proc_macro::NumBytes, pulse_proc_macro::Read, pulse_proc_macro::Write)]
+    #[pulse(crate_path = "crate")]
+    struct Ballot<T> {
+        proposals: alloc::vec::Vec<Proposal>,
+        weights: alloc::vec::Vec<(u64, T)>,
+        memo: Option<String>,
+    }
+
+    #[test]
+    fn test_read_error() {
+        use super::{PathSegment, ReadErrorKind, Write};
+        use alloc::{string::ToString, vec};
+
+        let err = i16::read(&[0x01], &mut 0).unwrap_err();
+        assert_eq!(err.kind(), ReadErrorKind::NotEnoughBytes);
+        assert_eq!(err.expected(), Some("i16"));
+        assert_eq!(err.to_string(), "not enough bytes at offset 0");
+
+        let ballot = Ballot {
+            proposals: vec![Proposal::Empty, Proposal::Transfer(1, 2)],
+            weights: vec![(7_u64, 1_u32)],
+            memo: None,
+        };
+        let bytes = ballot.pack().expect("Failed to write");
+
+        // The second proposal's `u32` starts at byte 11.
+        let err = Ballot::<u32>::read(&bytes[..13], &mut 0).err().unwrap();
+        assert_eq!(err.kind(), ReadErrorKind::NotEnoughBytes);
+        assert_eq!(err.offset(), 11);
+        assert_eq!(err.expected(), Some("u32"));
+        assert_eq!(
+            err.path(),
+            [
+                PathSegment::Field("proposals"),
+                PathSegment::Index(1),
+                PathSegment::Index(1),
+            ]
+        );
+        assert_eq!(
+            err.to_string(),
+            "proposals[1][1]: not enough bytes at offset 11"
+        );
+
+        let mut bad_tag = bytes.clone();
+        bad_tag[2] = 0x07;
+        let err = Ballot::<u32>::read(&bad_tag, &mut 0).err().unwrap();
+        assert_eq!(err.kind(), ReadErrorKind::ParseError);
+        assert_eq!(err.offset(), 2);
+        assert_eq!(err.to_string(), "proposals[1]: invalid data at offset 2");
+
+        let err = <(u8, u16)>::read(&[0x01, 0x02], &mut 0).unwrap_err();
+        assert_eq!(err.to_string(), "[1]: not enough bytes at offset 1");
+    }
+
+    #[derive(Debug, PartialEq, pulse_proc_macro::NumBytes, pulse_proc_macro::Read, pulse_proc_macro::Write)]
+    #[pulse(crate_path = "crate")]
+    struct Account {
+        #[pulse(rename = "owner")]
+        name: u64,
+        #[pulse(skip)]
+        cached: u32,
+        #[pulse(default)]
+        flags: u8,
+        balance: super::BinaryExtension<u64>,
+    }
+
+    #[test]
+    fn test_field_options() {
+        use super::{abi::AbiType, BinaryExtension, NumBytes, Write};
+        use alloc::{borrow::ToOwned, string::ToString, vec};
+
+        let account = Account {
+            name: 1,
+            cached: 5,
+            flags: 3,
+            balance: BinaryExtension::new(2),
+        };
+        let bytes = account.pack().expect("Failed to write");
+        assert_eq!(hex::encode(&bytes), "0100000000000000030200000000000000");
+        assert_eq!(account.num_bytes(), bytes.len());
+        let read = Account::read(&bytes, &mut 0).unwrap();
+        assert_eq!(read, Account { cached: 0, ..account });
+
+        // Data written before the extensions existed.
+        let read = Account::read(&bytes[..8], &mut 0).unwrap();
+        assert_eq!(
+            read,
+            Account {
+                name: 1,
+                cached: 0,
+                flags: 0,
+                balance: BinaryExtension::none(),
+            }
+        );
+        let bytes = read.pack().expect("Failed to write");
+        assert_eq!(bytes.len(), 9);
+        assert_eq!(Account::read(&bytes, &mut 0).unwrap(), read);
+
+        let err = Account::read(&bytes[..4], &mut 0).err().unwrap();
+        assert_eq!(err.to_string(), "owner: not enough bytes at offset 0");
+
+        let mut abi = super::abi::Abi::default();
+        Account::abi_type(&mut abi);
+        let pair = |name: &str, ty: &str| (name.to_owned(), ty.to_owned());
+        assert_eq!(
+            abi.structs["Account"].fields,
+            vec![
+                pair("owner", "uint64"),
+                pair("flags", "uint8$"),
+                pair("balance", "uint64$"),
+            ]
+        );
+    }
+
+    #[derive(Debug, PartialEq, pulse_proc_macro::NumBytes, pulse_proc_macro::ReadBorrowed, pulse_proc_macro::Write)]
+    #[pulse(crate_path = "crate")]
+    struct Memo<'a, T> {
+        id: T,
+        #[pulse(rename = "text")]
+        memo: &'a str,
+        data: &'a [u8],
+    }
+
+    #[test]
+    fn test_read_borrowed() {
+        use super::{ReadBorrowed, Write};
+        use alloc::string::ToString;
+
+        let memo = Memo {
+            id: 7_u8,
+            memo: "hi",
+            data: &[0xab],
+        };
+        let bytes = memo.pack().expect("Failed to write");
+        assert_eq!(hex::encode(&bytes), "0702686901ab");
+        let read = Memo::<u8>::unpack_borrowed(&bytes).unwrap();
+        assert_eq!(read, memo);
+        assert!(core::ptr::eq(read.memo.as_ptr(), &bytes[2]));
+
+        let err = Memo::<u8>::unpack_borrowed(&bytes[..3]).unwrap_err();
+        assert_eq!(err.to_string(), "text: not enough bytes at offset 1");
+        let err = <&str>::unpack_borrowed(&[0x01, 0xff]).unwrap_err();
+        assert_eq!(err.to_string(), "invalid data at offset 0");
+
+        // Types deriving `Read` are `ReadBorrowed` too.
+        assert_eq!(u16::unpack_borrowed(&[0x01, 0x00]).unwrap(), 1);
+    }
+
+    #[derive(pulse_proc_macro::NumBytes, pulse_proc_macro::Read, pulse_proc_macro::Write)]
+    #[pulse(crate_path = "crate", abi = "checksum256")]
+    struct Hash(u64, u64, u64, u64);
+
+    #[test]
+    fn test_abi_type() {
+        use super::abi::{Abi, AbiType};
+        use alloc::{borrow::ToOwned, vec};
+
+        let pair = |name: &str, ty: &str| (name.to_owned(), ty.to_owned());
+        let mut abi = Abi::default();
+        assert_eq!(Ballot::<u32>::abi_type(&mut abi), "Ballot_uint32");
+        assert_eq!(Hash::abi_type(&mut abi), "checksum256");
+
+        assert_eq!(
+            abi.structs["Ballot_uint32"].fields,
+            vec![
+                pair("proposals", "Proposal[]"),
+                pair("weights", "pair_uint64_uint32[]"),
+                pair("memo", "string?"),
+            ]
+        );
+        assert_eq!(
+            abi.structs["pair_uint64_uint32"].fields,
+            vec![pair("key", "uint64"), pair("value", "uint32")]
+        );
+        assert_eq!(
+            abi.variants["Proposal"],
+            vec!["ProposalEmpty", "ProposalTransfer", "ProposalVote"]
+        );
+        assert_eq!(
+            abi.structs["ProposalTransfer"].fields,
+            vec![pair("field_0", "uint64"), pair("field_1", "uint32")]
+        );
+        assert_eq!(
+            abi.structs["ProposalVote"].fields,
+            vec![pair("voter", "uint64"), pair("approve", "bool")]
+        );
+        assert!(!abi.structs.contains_key("Hash"));
+    }
 }
@@ -1,6 +1,11 @@
-use crate::{NumBytes, Read, ReadError, Write, WriteError};
+use crate::{NumBytes, Read, ReadError, ReadErrorKind, Write, WriteError};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct VarInt32(pub i32);
 
 impl NumBytes for VarInt32 {
@@ -27,12 +32,17 @@ impl NumBytes for VarInt32 {
 impl Read for VarInt32 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let start = *pos;
         let mut result: i64 = 0;
         let mut shift = 0u32;
 
         for _ in 0..5 {
             if *pos >= bytes.len() {
-                return Err(ReadError::NotEnoughBytes);
+                return Err(ReadError::new(
+                    ReadErrorKind::NotEnoughBytes,
+                    "VarInt32",
+                    start,
+                ));
             }
             let b = bytes[*pos];
             *pos += 1;
@@ -49,14 +59,14 @@ impl Read for VarInt32 {
                 }
                 // range check for i32
                 if result < i32::MIN as i64 || result > i32::MAX as i64 {
-                    return Err(ReadError::Overflow);
+                    return Err(ReadError::new(ReadErrorKind::Overflow, "VarInt32", start));
                 }
                 return Ok(VarInt32(result as i32));
             }
 
             shift += 7;
         }
-        Err(ReadError::ParseError)
+        Err(ReadError::new(ReadErrorKind::ParseError, "VarInt32", start))
     }
 }
 
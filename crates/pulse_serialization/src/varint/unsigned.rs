@@ -1,6 +1,11 @@
-use crate::{NumBytes, Read, ReadError, Write, WriteError};
+use crate::{NumBytes, Read, ReadError, ReadErrorKind, Write, WriteError};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct VarUint32(pub u32);
 
 impl NumBytes for VarUint32 {
@@ -18,13 +23,18 @@ impl NumBytes for VarUint32 {
 impl Read for VarUint32 {
     #[inline(always)]
     fn read(bytes: &[u8], pos: &mut usize) -> Result<Self, ReadError> {
+        let start = *pos;
         let mut result: u32 = 0;
         let mut shift = 0u32;
 
         // u32 needs at most 5 groups of 7 bits (5 * 7 = 35; last group limited to 4 bits)
         for i in 0..5 {
             if *pos >= bytes.len() {
-                return Err(ReadError::NotEnoughBytes);
+                return Err(ReadError::new(
+                    ReadErrorKind::NotEnoughBytes,
+                    "VarUint32",
+                    start,
+                ));
             }
             let byte = bytes[*pos];
             *pos += 1;
@@ -33,12 +43,12 @@ impl Read for VarUint32 {
 
             // prevent shifting beyond 31 bits
             if shift >= 32 {
-                return Err(ReadError::Overflow);
+                return Err(ReadError::new(ReadErrorKind::Overflow, "VarUint32", start));
             }
 
             // for the 5th byte, only the lower 4 bits are allowed for u32 (bits 28..31)
             if i == 4 && (low7 & 0xF0) != 0 {
-                return Err(ReadError::Overflow);
+                return Err(ReadError::new(ReadErrorKind::Overflow, "VarUint32", start));
             }
 
             result |= low7 << shift;
@@ -52,7 +62,11 @@ impl Read for VarUint32 {
         }
 
         // if we fell out of the loop, we saw 5 continuation bits -> too long for u32
-        Err(ReadError::ParseError)
+        Err(ReadError::new(
+            ReadErrorKind::ParseError,
+            "VarUint32",
+            start,
+        ))
     }
 }
 
@@ -692,8 +692,8 @@ impl TestContract {
             match index {
                 1 => {}
                 _ => {
-                    let bytes = value.to_le_bytes();
-                    check( db_get_i64( itr, &bytes, 8 ) == 8, "test_invalid_access: value in primary table was incorrect size" );
+                    let mut bytes = value.to_le_bytes();
+                    check( db_get_i64( itr, &mut bytes, 8 ) == 8, "test_invalid_access: value in primary table was incorrect size" );
                     value = u64::from_le_bytes(bytes);
                 }
             }